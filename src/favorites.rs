@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use gtk::gdk::{Key, ModifierType};
+use xdg::BaseDirectories;
+
+use crate::interface::{ActionResult, Context, EntryAction};
+
+/// Above any score from searching, so favorites stay on top of the other results.
+pub const FAVORITE_SCORE: u64 = 1 << 32;
+
+/// Values starred by the user, like characters, kept per plugin in the order they were added.
+#[derive(Clone, Debug, Default)]
+pub struct Favorites {
+    path: Option<PathBuf>,
+    favorites: HashMap<String, Vec<String>>,
+}
+
+impl Favorites {
+    pub fn load() -> Self {
+        let path = BaseDirectories::with_prefix("jogger")
+            .ok()
+            .and_then(|x| x.place_data_file("favorites.json").ok());
+
+        let favorites = path
+            .as_ref()
+            .and_then(|x| std::fs::read_to_string(x).ok())
+            .and_then(|x| serde_json::from_str(&x).ok())
+            .unwrap_or_default();
+
+        Self { path, favorites }
+    }
+
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+
+        match serde_json::to_string_pretty(&self.favorites) {
+            Ok(content) => {
+                if let Err(error) = std::fs::write(path, content) {
+                    tracing::error!(?error, "Failed to save favorites");
+                }
+            }
+            Err(error) => tracing::error!(?error, "Failed to serialize favorites"),
+        }
+    }
+
+    pub fn get(&self, kind: &str) -> &[String] {
+        self.favorites.get(kind).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn contains(&self, kind: &str, value: &str) -> bool {
+        self.get(kind).iter().any(|x| x == value)
+    }
+
+    pub fn toggle(&mut self, kind: &str, value: &str) {
+        let favorites = self.favorites.entry(kind.to_owned()).or_default();
+
+        if let Some(index) = favorites.iter().position(|x| x == value) {
+            favorites.remove(index);
+        } else {
+            favorites.push(value.to_owned());
+        }
+
+        self.save();
+    }
+}
+
+/// Adds the value to the favorites of the kind, or removes it when already there.
+pub fn action(kind: &'static str, value: &str, context: &Context) -> EntryAction {
+    let starred = context.favorites.contains(kind, value);
+    let value = value.to_owned();
+
+    EntryAction {
+        icon: if starred { "non-starred" } else { "starred" }.into(),
+        name: if starred {
+            "Remove from favorites"
+        } else {
+            "Add to favorites"
+        }
+        .into(),
+        function: Box::new(move |context| {
+            context.favorites.toggle(kind, &value);
+            ActionResult::Refresh
+        }),
+        key: Key::s,
+        modifier: ModifierType::CONTROL_MASK,
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use xdg::BaseDirectories;
+
+const MAX_ENTRIES: usize = 1000;
+
+const HOUR: i64 = 60 * 60;
+const DAY: i64 = 24 * HOUR;
+const WEEK: i64 = 7 * DAY;
+const MONTH: i64 = 30 * DAY;
+const QUARTER: i64 = 90 * DAY;
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Usage {
+    pub count: u32,
+    pub last: i64,
+    /// Scored as if just used, so it never decays.
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+/// Remembers which entries were activated, and how often, by their `id`.
+#[derive(Clone, Debug, Default)]
+pub struct Frecency {
+    path: Option<PathBuf>,
+    usages: HashMap<String, Usage>,
+}
+
+impl Frecency {
+    pub fn load() -> Self {
+        let path = BaseDirectories::with_prefix("jogger")
+            .ok()
+            .and_then(|x| x.place_data_file("frecency.json").ok());
+
+        let usages = path
+            .as_ref()
+            .and_then(|x| std::fs::read_to_string(x).ok())
+            .and_then(|x| serde_json::from_str(&x).ok())
+            .unwrap_or_default();
+
+        Self { path, usages }
+    }
+
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+
+        match serde_json::to_string(&self.usages) {
+            Ok(content) => {
+                if let Err(error) = std::fs::write(path, content) {
+                    tracing::error!(?error, "Failed to save usage data");
+                }
+            }
+            Err(error) => tracing::error!(?error, "Failed to serialize usage data"),
+        }
+    }
+
+    pub fn record(&mut self, id: &str) {
+        let usage = self.usages.entry(id.to_owned()).or_default();
+        usage.count = usage.count.saturating_add(1);
+        usage.last = chrono::Local::now().timestamp();
+
+        if self.usages.len() > MAX_ENTRIES {
+            self.prune();
+        }
+
+        self.save();
+    }
+
+    /// Forgets the entries with the lowest scores, keeping the store bounded.
+    fn prune(&mut self) {
+        let now = chrono::Local::now().timestamp();
+        let mut scores = self
+            .usages
+            .iter()
+            .filter(|(_, usage)| !usage.pinned)
+            .map(|(id, usage)| (usage_score(usage, now), id.clone()))
+            .collect::<Vec<_>>();
+
+        scores.sort();
+
+        let excess = self.usages.len().saturating_sub(MAX_ENTRIES);
+        for (_, id) in scores.into_iter().take(excess) {
+            self.usages.remove(&id);
+        }
+    }
+
+    /// A bonus to add to the score of the entry, 0 if it was never used.
+    pub fn score(&self, id: &str) -> u64 {
+        if id.is_empty() {
+            return 0;
+        }
+
+        self.usages
+            .get(id)
+            .map(|x| usage_score(x, chrono::Local::now().timestamp()))
+            .unwrap_or(0)
+    }
+
+    pub fn count(&self, id: &str) -> u32 {
+        self.usages.get(id).map_or(0, |x| x.count)
+    }
+
+    /// Every entry used, with its score.
+    pub fn usages(&self) -> impl Iterator<Item = (&str, &Usage, u64)> {
+        let now = chrono::Local::now().timestamp();
+        self.usages
+            .iter()
+            .map(move |(id, usage)| (id.as_str(), usage, usage_score(usage, now)))
+    }
+
+    /// Forgets the entry, as if it was never used.
+    pub fn reset(&mut self, id: &str) {
+        if self.usages.remove(id).is_some() {
+            self.save();
+        }
+    }
+
+    pub fn reset_all(&mut self) {
+        self.usages.clear();
+        self.save();
+    }
+
+    pub fn toggle_pin(&mut self, id: &str) {
+        if let Some(usage) = self.usages.get_mut(id) {
+            usage.pinned = !usage.pinned;
+            self.save();
+        }
+    }
+}
+
+fn usage_score(usage: &Usage, now: i64) -> u64 {
+    let age = if usage.pinned { 0 } else { now - usage.last };
+    let weight = match age {
+        ..HOUR => 40.0,
+        ..DAY => 32.0,
+        ..WEEK => 24.0,
+        ..MONTH => 16.0,
+        ..QUARTER => 8.0,
+        _ => 4.0,
+    };
+
+    (weight * (1.0 + usage.count as f64).ln()).round() as u64
+}
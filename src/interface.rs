@@ -1,11 +1,15 @@
 use derivative::Derivative;
+use std::collections::HashMap;
 use std::collections::VecDeque;
+use std::ffi::OsStr;
 use std::ffi::OsString;
 use std::fmt::Debug;
 use std::ops::Range;
 use std::path::Path;
 use std::path::PathBuf;
 use std::process::Command;
+use std::sync::Arc;
+use std::time::Duration;
 
 use gtk::IconLookupFlags;
 use gtk::IconTheme;
@@ -20,11 +24,32 @@ use gtk::pango::Attribute;
 use gtk::pango::Color;
 use gtk::pango::FontDescription;
 use itertools::Itertools;
-
+use parking_lot::Mutex;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use tokio_util::sync::CancellationToken;
+
+use crate::favorites::Favorites;
+use crate::frecency::Frecency;
+use crate::notes::Notes;
 use crate::utils::CommandExt;
 use crate::utils::IteratorExt;
+use crate::utils::read_clipboard;
 use crate::xdg_database::XdgAppDatabase;
 
+/// Offered along with copied secrets, clipboard managers that know it keep them out of their history.
+pub const SECRET_HINT_MIME: &str = "x-kde-passwordManagerHint";
+
+/// Parses the plugin specific keys of a `[[plugins]]` table in the config.
+pub fn parse_options<T: DeserializeOwned + Default>(options: &toml::Table) -> T {
+    toml::Value::Table(options.clone())
+        .try_into()
+        .unwrap_or_else(|error| {
+            tracing::warn!(%error, "Invalid plugin options");
+            Default::default()
+        })
+}
+
 pub trait Plugin: Debug + Send + Sync {
     fn open(&mut self) {}
 
@@ -34,12 +59,46 @@ pub trait Plugin: Debug + Send + Sync {
         None
     }
 
+    /// The `token` is cancelled once the results are no longer needed, slow
+    /// searches should check it and return early.
     #[allow(unused)]
-    fn search(&self, query: &str, context: &Context) -> Vec<Entry> {
+    fn search(&self, query: &str, context: &Context, token: &CancellationToken) -> Vec<Entry> {
         Vec::new()
     }
 
+    /// Like `search`, but can hand out the entries in batches as they are found,
+    /// so they are shown before the whole search is over.
+    fn search_stream(
+        &self,
+        query: &str,
+        context: &Context,
+        token: &CancellationToken,
+        sink: &mut dyn FnMut(Vec<Entry>),
+    ) {
+        sink(self.search(query, context, token))
+    }
+
     fn select(&self, _entry: &Entry) {}
+
+    /// Content for the preview pane when the entry is highlighted.
+    fn preview(&self, _entry: &Entry) -> Option<Preview> {
+        None
+    }
+
+    /// Searched again this often while its results are shown, for entries that change on their own.
+    fn refresh_interval(&self) -> Option<Duration> {
+        None
+    }
+}
+
+#[derive(Debug)]
+pub enum Preview {
+    Text(String),
+    /// Shown in a monospace font with its lines kept, like file contents.
+    Code(String),
+    /// Shown large, for characters like emojis.
+    Symbols(String),
+    Image(PathBuf),
 }
 
 #[derive(Derivative)]
@@ -62,7 +121,12 @@ impl EntryAction {
     pub fn copy(value: impl Into<String>) -> Box<ActionType> {
         let value = value.into();
 
-        Box::new(move |_| {
+        Box::new(move |context| {
+            if let Some(batch) = &mut context.batch_copies {
+                batch.push(value.clone());
+                return ActionResult::Ok;
+            }
+
             let mut opts = wl_clipboard_rs::copy::Options::new();
             opts.foreground(true);
             opts.copy(
@@ -74,6 +138,96 @@ impl EntryAction {
         })
     }
 
+    /// Copies a password or code, kept out of clipboard histories and cleared after a while.
+    pub fn copy_secret(value: impl Into<String>) -> Box<ActionType> {
+        use wl_clipboard_rs::copy::{ClipboardType, MimeSource, MimeType, Seat, Source};
+
+        let value = value.into();
+
+        Box::new(move |context| {
+            if let Some(batch) = &mut context.batch_copies {
+                batch.push(value.clone());
+                return ActionResult::Ok;
+            }
+
+            // Started first, serving the clipboard in the foreground only returns once it's replaced
+            let timeout = context.secret_timeout;
+            if !timeout.is_zero() {
+                let value = value.clone();
+                std::thread::spawn(move || {
+                    std::thread::sleep(timeout);
+
+                    // Unless something else was copied since
+                    if read_clipboard().is_some_and(|x| x == value)
+                        && let Err(error) =
+                            wl_clipboard_rs::copy::clear(ClipboardType::Regular, Seat::All)
+                    {
+                        tracing::warn!(%error, "Failed to clear the clipboard");
+                    }
+                });
+            }
+
+            let mut opts = wl_clipboard_rs::copy::Options::new();
+            opts.foreground(true);
+            opts.copy_multi(vec![
+                MimeSource {
+                    source: Source::Bytes(value.as_bytes().into()),
+                    mime_type: MimeType::Text,
+                },
+                MimeSource {
+                    source: Source::Bytes(b"secret"[..].into()),
+                    mime_type: MimeType::Specific(SECRET_HINT_MIME.into()),
+                },
+            ])
+            .is_ok()
+            .into()
+        })
+    }
+
+    /// Wraps a destructive action, so it only runs once the user confirms it.
+    pub fn confirm(message: impl Into<String>, function: Box<ActionType>) -> Box<ActionType> {
+        let message = message.into();
+        let function = Arc::new(Mutex::new(function));
+
+        Box::new(move |_| ActionResult::Confirm(message.clone(), function.clone()))
+    }
+
+    /// Wraps a destructive action, so its result shows the message with a way to revert it.
+    pub fn undoable(
+        message: impl Into<String>,
+        function: Box<ActionType>,
+        undo: Box<ActionType>,
+    ) -> Box<ActionType> {
+        let message = message.into();
+        let undo = Arc::new(Mutex::new(undo));
+
+        Self::map_result(function, move |result| match result {
+            ActionResult::Error => ActionResult::Error,
+            _ => ActionResult::Undoable(message.clone(), undo.clone()),
+        })
+    }
+
+    /// Maps the result of the function, once it's confirmed when it asks to be first,
+    /// so what follows a successful action doesn't run before the action itself.
+    pub fn map_result(
+        function: Box<ActionType>,
+        map: impl Fn(ActionResult) -> ActionResult + Send + Sync + 'static,
+    ) -> Box<ActionType> {
+        fn apply(result: ActionResult, map: Arc<MapType>) -> ActionResult {
+            match result {
+                ActionResult::Confirm(message, function) => {
+                    let confirmed: Box<ActionType> =
+                        Box::new(move |context| apply((function.lock())(context), map.clone()));
+                    ActionResult::Confirm(message, Arc::new(Mutex::new(confirmed)))
+                }
+                result => map(result),
+            }
+        }
+
+        let map: Arc<MapType> = Arc::new(map);
+        Box::new(move |context| apply(function(context), map.clone()))
+    }
+
     pub fn copy_bytes(value: &[u8]) -> Box<ActionType> {
         let value: Box<[u8]> = value.into();
 
@@ -89,18 +243,39 @@ impl EntryAction {
         })
     }
 
+    /// Shows the full command line to confirm first, when `confirm_commands` is set.
     pub fn command(command: String, args: Vec<String>, path: Option<PathBuf>) -> Box<ActionType> {
-        Box::new(move |_| {
+        let directory = move || {
+            path.clone()
+                .filter(|x| x.exists())
+                .unwrap_or_else(|| std::env::current_dir().unwrap())
+        };
+
+        let preview = {
+            let directory = directory.clone();
+            let line = std::iter::once(&command)
+                .chain(&args)
+                .map(|x| shell_quote(x))
+                .join(" ");
+            move || format!("Run {line} in {}?", directory().display())
+        };
+
+        let run: Box<ActionType> = Box::new(move |_| {
             Command::new(&command)
                 .args(&args)
-                .current_dir(
-                    path.as_ref()
-                        .filter(|x| x.exists())
-                        .unwrap_or(&std::env::current_dir().unwrap()),
-                )
+                .current_dir(directory())
                 .spawn_detached()
                 .is_ok()
                 .into()
+        });
+
+        let run = Arc::new(Mutex::new(run));
+        Box::new(move |context| {
+            if context.confirm_commands {
+                ActionResult::Confirm(preview(), run.clone())
+            } else {
+                (run.lock())(context)
+            }
         })
     }
 
@@ -126,24 +301,59 @@ impl EntryAction {
         program: Option<String>,
         arguments: Vec<String>,
         working_directory: Option<PathBuf>,
+    ) -> Box<ActionType> {
+        Self::launch_terminal_with_profile(None, program, arguments, working_directory)
+    }
+
+    pub fn launch_terminal_with_profile(
+        profile: Option<String>,
+        program: Option<String>,
+        arguments: Vec<String>,
+        working_directory: Option<PathBuf>,
     ) -> Box<ActionType> {
         Box::new(move |context| {
-            if let Some(emulator) = context.apps.terminal_emulator() {
+            let profile = profile
+                .as_ref()
+                .and_then(|x| context.terminal_profiles.get(x));
+            let emulator = profile
+                .and_then(|x| x.emulator.as_ref())
+                .and_then(|x| context.apps.app_map.get(x))
+                .or_else(|| context.apps.terminal_emulator());
+
+            if let Some(emulator) = emulator {
+                let terminal_args = &emulator.terminal_args;
                 let mut command = Command::new(emulator.program());
 
                 if let Some(working_directory) = &working_directory
-                    && let Some(arg) = &emulator.terminal_args.dir
+                    && let Some(arg) = &terminal_args.dir
                 {
-                    if arg.ends_with('=') {
-                        command.arg(format!("{arg}{}", working_directory.to_string_lossy()));
-                    } else {
+                    push_terminal_arg(&mut command, arg, working_directory);
+                }
+
+                if let Some(profile) = profile {
+                    if let Some(title) = &profile.title
+                        && let Some(arg) = &terminal_args.title
+                    {
+                        push_terminal_arg(&mut command, arg, title);
+                    }
+
+                    if let Some(app_id) = &profile.app_id
+                        && let Some(arg) = &terminal_args.app_id
+                    {
+                        push_terminal_arg(&mut command, arg, app_id);
+                    }
+
+                    if profile.hold
+                        && let Some(arg) = &terminal_args.hold
+                    {
                         command.arg(arg);
-                        command.arg(working_directory);
                     }
+
+                    command.args(&profile.args);
                 }
 
                 if let Some(program) = &program {
-                    command.arg(emulator.terminal_args.exec.as_deref().unwrap_or("-e"));
+                    command.arg(terminal_args.exec.as_deref().unwrap_or("-e"));
 
                     command.arg(program);
                     command.args(&arguments);
@@ -151,10 +361,10 @@ impl EntryAction {
 
                 match command.spawn_detached() {
                     Err(error) => {
-                        println!(
-                            "Failed to start terminal {:?} {:?}",
-                            command.get_args(),
-                            error
+                        tracing::error!(
+                            args = ?command.get_args(),
+                            ?error,
+                            "Failed to start terminal"
                         );
                         false
                     }
@@ -166,6 +376,110 @@ impl EntryAction {
             .into()
         })
     }
+
+    /// Opens a file in the configured editor, jumping to `line` if the editor supports it.
+    pub fn edit(path: PathBuf, line: Option<usize>) -> Box<ActionType> {
+        Box::new(move |context| {
+            let editor = context
+                .editor
+                .clone()
+                .or_else(|| std::env::var("VISUAL").ok())
+                .or_else(|| std::env::var("EDITOR").ok());
+
+            let Some(editor) = editor else {
+                let args = [path.to_string_lossy().into_owned()];
+                return context
+                    .apps
+                    .default_for_mime(&"text/plain".parse().unwrap())
+                    .is_some_and(|app| context.apps.launch(app, &args))
+                    .into();
+            };
+
+            let mut parts = editor.split_whitespace().map(str::to_owned);
+            let Some(program) = parts.next() else {
+                return ActionResult::Error;
+            };
+
+            let name = Path::new(&program)
+                .file_name()
+                .map(|x| x.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let mut args = parts.collect_vec();
+            args.extend(editor_line_args(&name, &path, line));
+
+            if TERMINAL_EDITORS.contains(&name.as_str()) {
+                let directory = path.parent().map(Path::to_path_buf);
+                return Self::launch_terminal(Some(program), args, directory)(context);
+            }
+
+            let mut command = Command::new(&program);
+            command.args(&args);
+
+            match command.spawn_detached() {
+                Err(error) => {
+                    tracing::error!(
+                        args = ?command.get_args(),
+                        ?error,
+                        "Failed to start editor"
+                    );
+                    false
+                }
+                _ => true,
+            }
+            .into()
+        })
+    }
+}
+
+const TERMINAL_EDITORS: &[&str] = &[
+    "vi", "vim", "nvim", "nano", "micro", "hx", "helix", "kak", "emacs", "ne", "joe", "mg",
+];
+
+/// The arguments that open `path` at `line` with the given editor.
+fn editor_line_args(editor: &str, path: &Path, line: Option<usize>) -> Vec<String> {
+    let path = path.to_string_lossy().into_owned();
+
+    let Some(line) = line else {
+        return vec![path];
+    };
+
+    match editor {
+        "code" | "code-oss" | "codium" | "vscodium" | "cursor" => {
+            vec!["-g".into(), format!("{path}:{line}")]
+        }
+        "hx" | "helix" | "subl" | "zed" | "zeditor" | "lapce" => vec![format!("{path}:{line}")],
+        "kate" | "kwrite" => vec!["-l".into(), line.to_string(), path],
+        "idea" | "pycharm" | "clion" | "goland" | "rustrover" | "webstorm" => {
+            vec!["--line".into(), line.to_string(), path]
+        }
+        _ => vec![format!("+{line}"), path],
+    }
+}
+
+/// Appends a terminal flag, joining it with its value when it ends in `=`.
+fn push_terminal_arg(command: &mut Command, arg: &str, value: impl AsRef<OsStr>) {
+    if arg.ends_with('=') {
+        let mut arg = OsString::from(arg);
+        arg.push(value);
+        command.arg(arg);
+    } else {
+        command.arg(arg);
+        command.arg(value);
+    }
+}
+
+/// Quotes the argument as a shell would need it, to show it unambiguously.
+fn shell_quote(arg: &str) -> String {
+    let plain = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|x| x.is_alphanumeric() || "-_./=:,+@%~".contains(x));
+
+    if plain {
+        arg.to_owned()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
 }
 
 impl Default for EntryAction {
@@ -185,6 +499,14 @@ pub enum ActionResult {
     Error,
     SetText(String),
     SetPlugin(Option<usize>),
+    /// Shows every result of the plugin, past its limit.
+    ShowMore(usize),
+    /// Runs the current search again, keeping the window open.
+    Refresh,
+    /// Asks the user with the message before running the action.
+    Confirm(String, Arc<Mutex<Box<ActionType>>>),
+    /// Done, but offers to revert it with the action for a few seconds.
+    Undoable(String, Arc<Mutex<Box<ActionType>>>),
 }
 
 impl From<bool> for ActionResult {
@@ -195,6 +517,8 @@ impl From<bool> for ActionResult {
 
 pub type ActionType = dyn Fn(&mut Context) -> ActionResult + Send;
 
+type MapType = dyn Fn(ActionResult) -> ActionResult + Send + Sync;
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum FormatStyle {
     Highlight,
@@ -371,6 +695,7 @@ pub struct Entry {
     pub actions: Vec<EntryAction>,
     pub id: String,
     pub drag_file: Option<PathBuf>,
+    /// Results of all the searched plugins are merged and sorted by this, higher first.
     pub score: u64,
 }
 
@@ -431,53 +756,173 @@ pub struct SubEntry {
     pub action: EntryAction,
 }
 
+/// A named set of overrides used when plugins open a terminal.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct TerminalProfile {
+    /// Desktop id of the emulator, if different from the default one.
+    pub emulator: Option<String>,
+    pub title: Option<String>,
+    pub app_id: Option<String>,
+    pub hold: bool,
+    pub args: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Power {
+    Ac,
+    Battery,
+}
+
+/// Checked once when the config is loaded, what it's set on is left out unless all of them hold.
+/// Like `when = { command = "hyprctl", compositor = "hyprland" }`.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Condition {
+    /// Found in `PATH`.
+    pub command: Option<String>,
+    /// Set to something.
+    pub env: Option<String>,
+    /// Like `hyprland`, `sway` or `niri`.
+    pub compositor: Option<String>,
+    pub power: Option<Power>,
+}
+
+impl Condition {
+    pub fn holds(&self) -> bool {
+        self.command.as_deref().is_none_or(command_exists)
+            && self
+                .env
+                .as_ref()
+                .is_none_or(|x| std::env::var_os(x).is_some_and(|x| !x.is_empty()))
+            && self
+                .compositor
+                .as_ref()
+                .is_none_or(|x| compositor().is_some_and(|y| x.eq_ignore_ascii_case(&y)))
+            && self.power.is_none_or(|x| power() == x)
+    }
+}
+
+fn command_exists(command: &str) -> bool {
+    if command.contains('/') {
+        return Path::new(command).is_file();
+    }
+
+    std::env::var_os("PATH")
+        .is_some_and(|x| std::env::split_paths(&x).any(|x| x.join(command).is_file()))
+}
+
+fn compositor() -> Option<String> {
+    if std::env::var_os("HYPRLAND_INSTANCE_SIGNATURE").is_some() {
+        Some("hyprland".into())
+    } else if std::env::var_os("SWAYSOCK").is_some() {
+        Some("sway".into())
+    } else if std::env::var_os("NIRI_SOCKET").is_some() {
+        Some("niri".into())
+    } else {
+        // Can be a list, like `ubuntu:GNOME`
+        std::env::var("XDG_CURRENT_DESKTOP")
+            .ok()
+            .and_then(|x| x.rsplit(':').next().map(str::to_lowercase))
+    }
+}
+
+/// Computers without a battery are always on AC.
+fn power() -> Power {
+    let Ok(supplies) = std::fs::read_dir("/sys/class/power_supply") else {
+        return Power::Ac;
+    };
+
+    let read = |path: PathBuf| std::fs::read_to_string(path).unwrap_or_default();
+    let mut battery = false;
+    for supply in supplies.filter_map(Result::ok).map(|x| x.path()) {
+        match read(supply.join("type")).trim() {
+            "Mains" if read(supply.join("online")).trim() == "1" => return Power::Ac,
+            "Battery" => battery = true,
+            _ => {}
+        }
+    }
+
+    if battery { Power::Battery } else { Power::Ac }
+}
+
+#[derive(Clone)]
 pub struct Context {
     messages: VecDeque<String>,
-    pub apps: XdgAppDatabase,
-    pub icons: Vec<(String, String)>,
+    pub apps: Arc<XdgAppDatabase>,
+    pub icons: Arc<Vec<(String, String)>>,
+    pub terminal_profiles: HashMap<String, TerminalProfile>,
+    pub frecency: Frecency,
+    pub notes: Notes,
+    pub favorites: Favorites,
+    pub editor: Option<String>,
+    /// Set while an action runs on several marked entries, copies are collected
+    /// here and put in the clipboard together at the end.
+    pub batch_copies: Option<Vec<String>>,
+    /// How long copied secrets stay in the clipboard, forever when zero.
+    pub secret_timeout: Duration,
+    /// Commands are shown in full and only run once confirmed.
+    pub confirm_commands: bool,
+    /// Launching an app focuses its window instead, when it has one.
+    pub focus_or_launch: bool,
+}
+
+/// The icons of the current theme, with the path of each relative to the theme.
+pub fn theme_icons() -> Vec<(String, String)> {
+    let theme = IconTheme::for_display(
+        &gtk::gdk::Display::default().expect("Could not connect to a display."),
+    );
+
+    let name = OsString::from(theme.theme_name());
+
+    theme
+        .icon_names()
+        .into_iter()
+        .flat_map(|x| {
+            let path = theme
+                .lookup_icon(
+                    &x,
+                    &[],
+                    96,
+                    1,
+                    gtk::TextDirection::None,
+                    IconLookupFlags::empty(),
+                )
+                .file()?
+                .path()?;
+
+            let parts = path.iter().skip_while(|x| *x != name).collect::<PathBuf>();
+            let parts = parts.to_string_lossy();
+
+            Some((
+                x.into(),
+                if parts.is_empty() {
+                    path.to_string_lossy().into()
+                } else {
+                    format!("#{parts}")
+                },
+            ))
+        })
+        .sorted()
+        .collect()
 }
 
 impl Default for Context {
     fn default() -> Self {
-        let theme = IconTheme::for_display(
-            &gtk::gdk::Display::default().expect("Could not connect to a display."),
-        );
-
-        let name = OsString::from(theme.theme_name());
-
         Self {
             messages: Default::default(),
             apps: Default::default(),
-            icons: theme
-                .icon_names()
-                .into_iter()
-                .flat_map(|x| {
-                    let path = theme
-                        .lookup_icon(
-                            &x,
-                            &[],
-                            96,
-                            1,
-                            gtk::TextDirection::None,
-                            IconLookupFlags::empty(),
-                        )
-                        .file()?
-                        .path()?;
-
-                    let parts = path.iter().skip_while(|x| *x != name).collect::<PathBuf>();
-                    let parts = parts.to_string_lossy();
-
-                    Some((
-                        x.into(),
-                        if parts.is_empty() {
-                            path.to_string_lossy().into()
-                        } else {
-                            format!("#{parts}")
-                        },
-                    ))
-                })
-                .sorted()
-                .collect(),
+            terminal_profiles: Default::default(),
+            frecency: Default::default(),
+            notes: Default::default(),
+            favorites: Default::default(),
+            editor: None,
+            batch_copies: None,
+            secret_timeout: Duration::from_secs(30),
+            confirm_commands: false,
+            focus_or_launch: false,
+            icons: Arc::new(theme_icons()),
         }
     }
 }
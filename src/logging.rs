@@ -0,0 +1,65 @@
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::{EnvFilter, fmt};
+use xdg::BaseDirectories;
+
+/// Log files older than a week are removed.
+const MAX_LOG_FILES: usize = 7;
+
+/// Logs to stderr and to a daily file in `$XDG_STATE_HOME/jogger/logs`.
+///
+/// `JOGGER_LOG` overrides the filter, using the `RUST_LOG` syntax. The returned
+/// guard flushes the file when dropped, so it must live until the app exits.
+pub fn init(verbose: bool) -> Option<WorkerGuard> {
+    let filter = || {
+        EnvFilter::try_from_env("JOGGER_LOG").unwrap_or_else(|_| {
+            EnvFilter::new(if verbose {
+                "warn,jogger=debug"
+            } else {
+                "warn,jogger=info"
+            })
+        })
+    };
+
+    let appender = BaseDirectories::with_prefix("jogger")
+        .ok()
+        .and_then(|x| x.create_state_directory("logs").ok())
+        .and_then(|directory| {
+            RollingFileAppender::builder()
+                .rotation(Rotation::DAILY)
+                .filename_prefix("jogger")
+                .filename_suffix("log")
+                .max_log_files(MAX_LOG_FILES)
+                .build(directory)
+                .ok()
+        });
+
+    let (file, guard) = match appender {
+        Some(appender) => {
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            let layer = fmt::layer()
+                .with_ansi(false)
+                .with_writer(writer)
+                .with_span_events(FmtSpan::CLOSE)
+                .with_filter(filter());
+
+            (Some(layer), Some(guard))
+        }
+        None => (None, None),
+    };
+
+    // Closing a span logs how long it took, which times the searches
+    let stderr = fmt::layer()
+        .with_writer(std::io::stderr)
+        .with_span_events(FmtSpan::CLOSE)
+        .with_filter(filter());
+
+    tracing_subscriber::registry()
+        .with(stderr)
+        .with(file)
+        .init();
+
+    guard
+}
@@ -1,7 +1,15 @@
 mod color;
+mod favorites;
+mod frecency;
 pub mod interface;
+mod logging;
+mod notes;
 mod plugins;
+mod remote;
 mod search_entry;
+mod shortcut;
+mod startup;
+mod state;
 pub mod utils;
 pub mod xdg_database;
 
@@ -10,27 +18,34 @@ use dbus::message::MatchRule;
 use dbus_crossroads::Crossroads;
 use fuzzy_matcher::FuzzyMatcher;
 use gtk::cairo::Region;
-use gtk::gdk::prelude::SurfaceExt;
+use gtk::gdk::prelude::{DisplayExt, MonitorExt, SurfaceExt};
 use gtk::gdk::{self, ContentProvider, Display, FileList, Key, ModifierType};
+use gtk::gio::prelude::ListModelExt;
 use gtk::glib::Propagation;
+use gtk::glib::object::{Cast, CastNone};
 use gtk::glib::translate::ToGlibPtr;
 use gtk::glib::value::ToValue;
-use gtk::prelude::{EventControllerExt, GestureSingleExt, NativeExt};
+use gtk::prelude::{AdjustmentExt, EventControllerExt, GestureSingleExt, NativeExt, ScrollableExt};
 use gtk::{
-    CenterBox, CssProvider, DragSource, EventControllerKey, GestureClick, IconTheme,
-    ListScrollFlags, ListView, Orientation, PropagationPhase, Separator,
+    CenterBox, CssProvider, DragSource, EventControllerKey, EventControllerScroll,
+    EventControllerScrollFlags, GestureClick, IconTheme, ListScrollFlags, ListView, Orientation,
+    PropagationPhase, Separator,
 };
 use itertools::Itertools;
-use parking_lot::RwLock;
+use parking_lot::{MappedRwLockWriteGuard, Mutex, RwLock, RwLockWriteGuard};
 use relm4::prelude::{AsyncComponent, AsyncComponentParts};
 use relm4::typed_view::list::{RelmListItem, TypedListView};
 use relm4::{AsyncComponentSender, RelmRemoveAllExt, view};
 use serde::Deserialize;
-use std::path::Path;
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::panic::AssertUnwindSafe;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::rc::Rc;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
 use xdg::BaseDirectories;
 
 use gtk::{
@@ -50,7 +65,11 @@ use relm4::{
 use search_entry::SearchEntryModel;
 
 use crate::color::PangoColor;
-use crate::interface::{ActionResult, Context, EntryAction, EntryIcon, FormattedString};
+use crate::interface::{
+    ActionResult, ActionType, Condition, Context, EntryAction, EntryIcon, FormattedString, Preview,
+    TerminalProfile, theme_icons,
+};
+use crate::utils::panic_message;
 
 trait FactoryVecDequeExt<T> {
     type Input;
@@ -75,6 +94,7 @@ where
 enum EntryMsg {
     Select,
     Unselect,
+    SetBadge(Option<usize>),
 }
 
 #[derive(Debug)]
@@ -105,6 +125,12 @@ struct GridEntryComponent {
     entry: Rc<Entry>,
     selected: bool,
     grid_size: usize,
+    /// Only the icon is shown, the name is in the tooltip.
+    compact: bool,
+    /// Number shown while Alt is held, activated by Alt+number.
+    badge: Option<usize>,
+    /// How often the entry was used, or "New" for apps installed recently.
+    usage: Option<String>,
 }
 
 impl Position<GridPosition, DynamicIndex> for GridEntryComponent {
@@ -124,7 +150,7 @@ impl Position<GridPosition, DynamicIndex> for GridEntryComponent {
 
 #[relm4::factory]
 impl FactoryComponent for GridEntryComponent {
-    type Init = (usize, Rc<Entry>, usize);
+    type Init = (usize, Rc<Entry>, usize, bool, Option<String>);
     type Input = EntryMsg;
     type Output = EntryOutput;
     type CommandOutput = ();
@@ -132,13 +158,11 @@ impl FactoryComponent for GridEntryComponent {
 
     view! {
         #[root]
-        GBox {
-            set_expand: true,
-            set_orientation: Vertical,
+        Overlay {
             #[watch]
             set_class_active: ("selected", self.selected),
             set_cursor_from_name: Some("pointer"),
-            // set_tooltip: &self.entry.name.text,
+            set_tooltip_text: self.compact.then_some(self.entry.name.text.as_str()),
 
             add_controller = GestureClick {
                 connect_pressed[sender, index] => move |_, _, _, _| {
@@ -162,44 +186,69 @@ impl FactoryComponent for GridEntryComponent {
                 },
             },
 
-            append = match &self.entry.icon {
-                EntryIcon::Name(value) => {
-                    Image {
-                        #[watch]
-                        set_icon_name: Some(value),
-                        set_pixel_size: 48,
-                        set_vexpand: true,
-                        set_valign: Align::End,
-                        add_css_class: "icon",
-                    }
-                },
-                EntryIcon::Path(value) => {
-                    Image {
-                        #[watch]
-                        set_from_file: Some(value),
-                        set_pixel_size: 48,
-                        set_vexpand: true,
-                        set_valign: Align::End,
-                        add_css_class: "icon",
-                    }
-                },
-                EntryIcon::Text(value) => {
-                    Label {
-                        #[watch]
-                        set_label: &value,
-                        add_css_class: "icon",
-                    }
-                },
-                EntryIcon::None => Image::new(),
+            add_overlay = &Label {
+                set_halign: Align::Start,
+                set_valign: Align::Start,
+                add_css_class: "badge",
+                #[watch]
+                set_visible: self.badge.is_some(),
+                #[watch]
+                set_label: &self.badge.map(|x| x.to_string()).unwrap_or_default(),
             },
 
-            Label {
-                set_label: &self.entry.name.text,
-                set_ellipsize: EllipsizeMode::End,
-                set_lines: 2,
-                set_vexpand: true,
-                set_justify: Justification::Center,
-                add_css_class: "grid_name",
+            add_overlay = &Label {
+                set_halign: Align::End,
+                set_valign: Align::Start,
+                add_css_class: "usage_badge",
+                set_visible: self.usage.is_some(),
+                set_label: self.usage.as_deref().unwrap_or_default(),
+            },
+
+            #[wrap(Some)]
+            set_child = &GBox {
+                set_expand: true,
+                set_orientation: Vertical,
+
+                append = match &self.entry.icon {
+                    EntryIcon::Name(value) => {
+                        Image {
+                            #[watch]
+                            set_icon_name: Some(value),
+                            set_pixel_size: 48,
+                            set_vexpand: true,
+                            set_valign: if self.compact { Align::Center } else { Align::End },
+                            add_css_class: "icon",
+                        }
+                    },
+                    EntryIcon::Path(value) => {
+                        Image {
+                            #[watch]
+                            set_from_file: Some(value),
+                            set_pixel_size: 48,
+                            set_vexpand: true,
+                            set_valign: if self.compact { Align::Center } else { Align::End },
+                            add_css_class: "icon",
+                        }
+                    },
+                    EntryIcon::Text(value) => {
+                        Label {
+                            #[watch]
+                            set_label: &value,
+                            add_css_class: "icon",
+                        }
+                    },
+                    EntryIcon::None => Image::new(),
+                },
+
+                Label {
+                    set_label: &self.entry.name.text,
+                    set_ellipsize: EllipsizeMode::End,
+                    set_lines: 2,
+                    set_vexpand: true,
+                    set_justify: Justification::Center,
+                    set_visible: !self.compact,
+                    add_css_class: "grid_name",
+                },
             },
         }
     }
@@ -210,6 +259,9 @@ impl FactoryComponent for GridEntryComponent {
             entry: value.1,
             selected: false,
             grid_size: value.2,
+            compact: value.3,
+            badge: None,
+            usage: value.4,
         }
     }
 
@@ -217,6 +269,7 @@ impl FactoryComponent for GridEntryComponent {
         match message {
             EntryMsg::Select => self.selected = true,
             EntryMsg::Unselect => self.selected = false,
+            EntryMsg::SetBadge(badge) => self.badge = badge,
         }
     }
 }
@@ -278,6 +331,7 @@ enum MoveDirection {
 #[derive(Debug)]
 enum AppMsg {
     Search(String),
+    Refresh,
     Activate(usize),
     ActivateSelected,
     ActivateSelectedWithAction(usize),
@@ -296,11 +350,42 @@ enum AppMsg {
     Toggle,
     ToggleActions,
     Reload,
-    SearchResults(Vec<(usize, Entry)>),
-    PluginLoaded(Box<dyn Plugin>),
+    SearchResults(usize, Vec<(usize, Entry)>),
+    SearchFinished(usize),
+    /// Computes the preview of the selected entry, unless it changed since.
+    ShowPreview(usize),
+    /// Shows the results received since the last time.
+    FlushResults,
+    /// The load generation, the index of the plugin and how long it took.
+    PluginLoaded(usize, usize, Box<dyn Plugin>, Duration),
     SetPlugin(Option<usize>),
     SetDragging(bool),
     ToggleLock,
+    ActivateVisible(usize),
+    ShowBadges(bool),
+    ToggleMark,
+    PluginFailed(usize, String),
+    ClearNotice(String),
+    AutoRefresh(usize),
+    EditNote,
+    SetQuery(String),
+    ShowMore(usize),
+    ThemeChanged,
+    /// Runs the default action of every result, asking first unless confirmed.
+    ActivateAll(bool),
+    Undo,
+    /// Opens the config where it failed to be read.
+    EditConfig,
+    ClearUndo(String),
+    ToggleZoom,
+    /// The next activated entry is sent back instead of being run.
+    Pick(std::sync::mpsc::Sender<Option<remote::Picked>>),
+    /// Runs the actions held back once a hook had them confirmed.
+    HooksConfirmed(usize),
+    /// The before hooks of the actions held back are done, with whether each of them may run.
+    HooksFinished(usize, Vec<bool>),
+    /// Goes back from the actions to the results, or from a plugin to all of them.
+    Pop,
 }
 
 #[derive(Debug)]
@@ -310,14 +395,71 @@ fn default_highlight_color() -> PangoColor {
     "#A2C9FE".parse().unwrap()
 }
 
-fn default_window_size() -> [usize; 2] {
-    [760, 760]
+fn default_secret_timeout() -> u64 {
+    30
+}
+
+fn default_new_app_days() -> u64 {
+    7
+}
+
+fn default_files_prefix() -> regex::Regex {
+    regex::Regex::new("^[~/]").unwrap()
+}
+
+/// The badge of a grid cell, "New" for apps installed recently and never used.
+fn usage_badge(context: &Context, id: &str, new_app_days: u64) -> Option<String> {
+    let count = context.frecency.count(id);
+    if count > 0 {
+        return Some(count.to_string());
+    }
+
+    let installed = context.apps.app_map.get(id)?.installed()?;
+    let age = installed.elapsed().unwrap_or_default();
+    (age < Duration::from_secs(new_app_days * 24 * 60 * 60)).then(|| "New".to_owned())
+}
+
+/// The window and grid sizes fitting the monitor, unless set in the config.
+fn layout_for_monitor(config: &AppConfig, monitor: Option<&gdk::Monitor>) -> ([usize; 2], usize) {
+    // In logical pixels, so already scaled
+    let side = monitor
+        .map(|x| x.geometry())
+        .map(|x| (x.width().min(x.height()) as f64 * 0.7) as usize)
+        .unwrap_or(760)
+        .clamp(400, 1600);
+
+    let window_size = config.window_size.unwrap_or([side, side]);
+    // Cells without names can be much smaller
+    let (cell, max) = if config.compact_grid {
+        (88, 16)
+    } else {
+        (150, 10)
+    };
+    let grid_size = config
+        .grid_size
+        .unwrap_or(window_size[0].min(window_size[1]) / cell)
+        .clamp(1, max);
+
+    (window_size, grid_size)
+}
+
+/// The monitor showing the window, or the first one before it's ever shown.
+fn current_monitor(window: &Window) -> Option<gdk::Monitor> {
+    let display = window.display();
+    window
+        .surface()
+        .and_then(|x| display.monitor_at_surface(&x))
+        .or_else(|| display.monitors().item(0).and_downcast())
 }
 
 fn default_default_plugin() -> bool {
     true
 }
 
+fn default_weight() -> f64 {
+    1.0
+}
+
 #[derive(Debug, Deserialize, Clone, Copy)]
 #[serde(rename_all = "snake_case")]
 enum PluginType {
@@ -332,14 +474,61 @@ enum PluginType {
     Emojis,
     Web,
     Icons,
+    Checksum,
+    Grep,
+    Json,
+    Spell,
+    Mail,
+    Feeds,
+    Wikipedia,
+    Transit,
+    Stocks,
+    Countdown,
+    Audio,
+    Machines,
+    Scripts,
+    Wine,
+    AppImages,
+    Shortcuts,
+    Links,
+    Ocr,
+    Microphone,
+    Devices,
+    Controls,
+    Wifi,
+    Pomodoro,
+    Habits,
+    Bitwarden,
+    Secrets,
+    Bookmarks,
+    Tabs,
+    RecentFiles,
+    Zoxide,
+    Projects,
+    JetBrains,
+    Zellij,
+    Containers,
+    Kubernetes,
+    Launcher,
+    Flatpak,
+    History,
+    Aliases,
+    Snippets,
+    Scratchpad,
+    Dictionary,
+    Timers,
+    External,
+    Native,
+    #[serde(skip_deserializing)]
+    Dmenu,
 }
 
 impl PluginType {
-    fn to_plugin(self, context: &Context) -> Box<dyn Plugin> {
+    fn to_plugin(self, context: &Context, options: &toml::Table) -> Box<dyn Plugin> {
         match self {
             Self::Applications => Box::new(plugins::applications::Applications::new(context)),
             Self::Files => Box::new(plugins::files::Files::new(context)),
-            Self::Windows => Box::new(plugins::hyprland::Hyprland::new(context)),
+            Self::Windows => Box::new(plugins::hyprland::Hyprland::new(context, options)),
             Self::Math => Box::new(plugins::math::Math::new(context)),
             Self::Clipboard => Box::new(plugins::clipboard::Clipboard::new(context)),
             Self::Terminal => Box::new(plugins::commands::Commands::new(context)),
@@ -348,10 +537,127 @@ impl PluginType {
             Self::Emojis => Box::new(plugins::emoji::Emojis::new(context)),
             Self::Web => Box::new(plugins::web::Web::new(context)),
             Self::Icons => Box::new(plugins::icons::Icons::new(context)),
+            Self::Checksum => Box::new(plugins::checksum::Checksum::new(context)),
+            Self::Grep => Box::new(plugins::grep::Grep::new(context, options)),
+            Self::Json => Box::new(plugins::json::Json::new(context)),
+            Self::Spell => Box::new(plugins::spell::Spell::new(context, options)),
+            Self::Mail => Box::new(plugins::mail::Mail::new(context)),
+            Self::Feeds => Box::new(plugins::feeds::Feeds::new(context, options)),
+            Self::Wikipedia => Box::new(plugins::wikipedia::Wikipedia::new(context, options)),
+            Self::Transit => Box::new(plugins::transit::Transit::new(context, options)),
+            Self::Stocks => Box::new(plugins::stocks::Stocks::new(context, options)),
+            Self::Countdown => Box::new(plugins::countdown::Countdown::new(context, options)),
+            Self::Audio => Box::new(plugins::audio::Audio::new(context)),
+            Self::Machines => Box::new(plugins::machines::Machines::new(context, options)),
+            Self::Scripts => Box::new(plugins::scripts::Scripts::new(context, options)),
+            Self::Wine => Box::new(plugins::wine::Wine::new(context, options)),
+            Self::AppImages => Box::new(plugins::appimages::AppImages::new(context, options)),
+            Self::Shortcuts => Box::new(plugins::shortcuts::Shortcuts::new(context, options)),
+            Self::Links => Box::new(plugins::links::Links::new(context, options)),
+            Self::Ocr => Box::new(plugins::ocr::Ocr::new(context, options)),
+            Self::Microphone => Box::new(plugins::microphone::Microphone::new(context)),
+            Self::Devices => Box::new(plugins::devices::Devices::new(context)),
+            Self::Controls => Box::new(plugins::controls::Controls::new(context, options)),
+            Self::Wifi => Box::new(plugins::wifi::Wifi::new(context)),
+            Self::Pomodoro => Box::new(plugins::pomodoro::Pomodoro::new(context, options)),
+            Self::Habits => Box::new(plugins::habits::Habits::new(context, options)),
+            Self::Bitwarden => Box::new(plugins::bitwarden::Bitwarden::new(context, options)),
+            Self::Secrets => Box::new(plugins::secrets::Secrets::new(context)),
+            Self::Bookmarks => Box::new(plugins::bookmarks::Bookmarks::new(context)),
+            Self::Tabs => Box::new(plugins::tabs::Tabs::new(context, options)),
+            Self::RecentFiles => Box::new(plugins::recent::RecentFiles::new(context)),
+            Self::Zoxide => Box::new(plugins::zoxide::Zoxide::new(context)),
+            Self::Projects => Box::new(plugins::projects::Projects::new(context, options)),
+            Self::JetBrains => Box::new(plugins::jetbrains::JetBrains::new(context)),
+            Self::Zellij => Box::new(plugins::zellij::Zellij::new(context, options)),
+            Self::Containers => Box::new(plugins::containers::Containers::new(context, options)),
+            Self::Kubernetes => Box::new(plugins::kubernetes::Kubernetes::new(context)),
+            Self::Launcher => Box::new(plugins::launcher::Launcher::new(context)),
+            Self::Flatpak => Box::new(plugins::flatpak::Flatpak::new(context)),
+            Self::History => Box::new(plugins::history::History::new(context)),
+            Self::Aliases => Box::new(plugins::aliases::Aliases::new(context)),
+            Self::Snippets => Box::new(plugins::snippets::Snippets::new(context, options)),
+            Self::Scratchpad => Box::new(plugins::scratchpad::Scratchpad::new(context, options)),
+            Self::Dictionary => Box::new(plugins::dictionary::Dictionary::new(context, options)),
+            Self::Timers => Box::new(plugins::timers::Timers::new(context)),
+            Self::External => Box::new(plugins::external::External::new(context, options)),
+            Self::Native => Box::new(plugins::native::Native::new(context, options)),
+            Self::Dmenu => Box::new(plugins::dmenu::Dmenu::new(context)),
+        }
+    }
+
+    /// The most results shown when neither the plugin nor the config set a limit.
+    fn max_results(self) -> Option<usize> {
+        match self {
+            Self::Applications => Some(20),
+            Self::Unicode | Self::Emojis => Some(128),
+            _ => None,
         }
     }
 }
 
+/// Takes the place of a plugin that panicked while loading, so the indices still line up.
+#[derive(Debug)]
+struct FailedPlugin(String);
+
+impl Plugin for FailedPlugin {
+    fn name(&self) -> &str {
+        &self.0
+    }
+}
+
+/// How long results wait for the ones after them, so the list is built about once a frame.
+const FLUSH_DELAY: Duration = Duration::from_millis(16);
+
+/// How long the selection has to stay on an entry before it's previewed.
+const PREVIEW_DELAY: Duration = Duration::from_millis(80);
+
+/// The most plugins searched at once, the others wait for one of them to finish.
+const MAX_SEARCH_THREADS: usize = 8;
+
+/// A plugin behind its own lock, so searches can go on without the others.
+type SharedPlugin = Arc<RwLock<Box<dyn Plugin>>>;
+
+fn shared(plugin: Box<dyn Plugin>) -> SharedPlugin {
+    Arc::new(RwLock::new(plugin))
+}
+
+/// Takes the place of a plugin until it's loaded, without results.
+#[derive(Debug)]
+struct PendingPlugin(String);
+
+impl Plugin for PendingPlugin {
+    fn name(&self) -> &str {
+        &self.0
+    }
+}
+
+fn load_plugin(
+    i: usize,
+    config: &PluginConfig,
+    context: &Context,
+    sender: &AsyncComponentSender<AppModel>,
+) -> Box<dyn Plugin> {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        config.r#type.to_plugin(context, &config.options)
+    }));
+
+    result.unwrap_or_else(|payload| {
+        let message = panic_message(&*payload);
+        tracing::error!(
+            plugin = ?config.r#type,
+            panic = %message,
+            "Plugin panicked while loading"
+        );
+        sender.input(AppMsg::PluginFailed(
+            i,
+            format!("{:?} failed to load: {message}", config.r#type),
+        ));
+
+        Box::new(FailedPlugin(format!("{:?}", config.r#type)))
+    })
+}
+
 #[derive(Debug, Deserialize, Clone)]
 struct PluginConfig {
     r#type: PluginType,
@@ -361,17 +667,209 @@ struct PluginConfig {
     regex: Option<regex::Regex>,
     #[serde(default = "default_default_plugin")]
     replace: bool,
+    /// Typing it switches to the plugin, keeping what follows as the query. Prefixes
+    /// ending in a letter or digit need a space after them, like `calc 2+2`.
+    prefix: Option<String>,
+    /// Disabled plugins are kept in the config but not loaded.
+    #[serde(default = "default_default_plugin")]
+    enabled: bool,
+    /// Plugins are only loaded where it holds, like on a given compositor.
+    #[serde(default)]
+    when: Condition,
+    /// Plugins with a higher priority are loaded, matched and ranked first,
+    /// ties keep the order of the config.
+    #[serde(default)]
+    priority: i32,
+    /// Multiplies the scores of the results, to rank them above or below the ones of other plugins.
+    #[serde(default = "default_weight")]
+    weight: f64,
+    /// The most results shown at once, the rest are behind a "Show more" entry.
+    #[serde(default)]
+    max_results: Option<usize>,
+    #[serde(flatten)]
+    options: toml::Table,
+}
+
+impl PluginConfig {
+    /// Tells apart plugins of the same type with different options.
+    fn key(&self) -> String {
+        if self.options.is_empty() {
+            format!("{:?}", self.r#type)
+        } else {
+            format!("{:?} {}", self.r#type, self.options)
+        }
+    }
+
+    /// The rest of the query, if it starts with the prefix of the plugin.
+    fn strip_prefix<'a>(&self, query: &'a str) -> Option<&'a str> {
+        let prefix = self.prefix.as_deref().filter(|x| !x.is_empty())?;
+        let rest = query.strip_prefix(prefix)?;
+
+        if prefix.ends_with(char::is_alphanumeric) {
+            rest.strip_prefix(char::is_whitespace)
+        } else {
+            Some(rest)
+        }
+    }
+}
+
+/// Used when the config doesn't list any plugins, the others have to be added there.
+fn default_plugins(files_prefix: &regex::Regex) -> Vec<PluginConfig> {
+    let plugin = |r#type, default, regex: Option<&str>, replace| PluginConfig {
+        r#type,
+        default,
+        regex: regex.map(|x| regex::Regex::new(x).unwrap()),
+        replace,
+        prefix: None,
+        enabled: true,
+        when: Condition::default(),
+        priority: 0,
+        weight: 1.0,
+        max_results: None,
+        options: Default::default(),
+    };
+
+    vec![
+        plugin(PluginType::Applications, true, None, true),
+        plugin(PluginType::Windows, true, None, true),
+        plugin(PluginType::Math, true, None, true),
+        plugin(PluginType::Files, true, Some(files_prefix.as_str()), false),
+        plugin(PluginType::Terminal, false, Some("^>$"), true),
+        plugin(PluginType::Emojis, false, Some("^:$"), true),
+        plugin(PluginType::Unicode, false, None, true),
+        plugin(PluginType::Ssh, false, None, true),
+        plugin(PluginType::Web, false, None, true),
+    ]
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum HookTime {
+    Before,
+    #[default]
+    After,
+}
+
+/// Runs a command around the actions it matches, with the entry and action in the
+/// `JOGGER_ENTRY`, `JOGGER_ID` and `JOGGER_ACTION` variables.
+#[derive(Debug, Deserialize)]
+struct Hook {
+    #[serde(default)]
+    when: HookTime,
+    /// Matched against the name of the action, like `^Open`. Any action when not set.
+    #[serde(default, with = "serde_regex")]
+    action: Option<regex::Regex>,
+    /// Matched against the name of the entry, which is the command for the terminal.
+    #[serde(default, with = "serde_regex")]
+    entry: Option<regex::Regex>,
+    /// Run with `sh`, the action is cancelled when one run before it fails.
+    command: Option<String>,
+    /// Asks before running the action.
+    #[serde(default)]
+    confirm: bool,
 }
 
-#[derive(Debug, Deserialize, Default)]
+impl Hook {
+    fn matches(&self, entry: &Entry, action: &EntryAction) -> bool {
+        self.action
+            .as_ref()
+            .is_none_or(|x| x.is_match(&action.name))
+            && self
+                .entry
+                .as_ref()
+                .is_none_or(|x| x.is_match(&entry.name.text))
+    }
+
+    fn command(&self, entry: &Entry, action: &EntryAction) -> Option<Command> {
+        let mut command = Command::new("sh");
+        command
+            .arg("-c")
+            .arg(self.command.as_ref()?)
+            .env("JOGGER_ENTRY", &entry.name.text)
+            .env("JOGGER_ID", &entry.id)
+            .env("JOGGER_ACTION", &action.name);
+        Some(command)
+    }
+}
+
+#[derive(Debug, Deserialize)]
 struct AppConfig {
     drag_command: Option<String>,
     drop_command: Option<String>,
     #[serde(default = "default_highlight_color")]
     highlight_color: PangoColor,
-    #[serde(default = "default_window_size")]
-    window_size: [usize; 2],
+    /// Fits the monitor when not set.
+    window_size: Option<[usize; 2]>,
+    /// Cells in each row and column of the home grid, fits the window when not set.
+    grid_size: Option<usize>,
+    /// Shows only the icons in the home grid, so more of them fit. The name of the
+    /// selected one is shown below it, and the others on hover.
+    #[serde(default)]
+    compact_grid: bool,
+    /// Marks the cells of the home grid with how often each was used,
+    /// or as new for apps installed in the last `new_app_days`.
+    #[serde(default)]
+    grid_badges: bool,
+    #[serde(default = "default_new_app_days")]
+    new_app_days: u64,
+    #[serde(default)]
     plugins: Vec<PluginConfig>,
+    /// Queries it matches search files, when the plugins are left to their defaults.
+    #[serde(default = "default_files_prefix", with = "serde_regex")]
+    files_prefix: regex::Regex,
+    #[serde(default)]
+    terminal_profiles: HashMap<String, TerminalProfile>,
+    /// Editor command used to open files at a line, defaults to `$VISUAL` or `$EDITOR`.
+    editor: Option<String>,
+    /// Shows a pane next to the list with a preview of the highlighted entry.
+    #[serde(default)]
+    preview: bool,
+    /// The most results shown at once for plugins without a limit of their own.
+    max_results: Option<usize>,
+    /// Queries up to this many characters filter the home grid in place,
+    /// instead of switching to the list. 0 turns it off.
+    #[serde(default)]
+    grid_filter: usize,
+    /// Bound to toggle the launcher when running on Hyprland, like `SUPER, SPACE`.
+    toggle_shortcut: Option<String>,
+    #[serde(default)]
+    hooks: Vec<Hook>,
+    /// Seconds until copied passwords and codes are cleared from the clipboard, 0 keeps them.
+    #[serde(default = "default_secret_timeout")]
+    secret_timeout: u64,
+    /// Shows commands with their arguments and directory, run only once confirmed.
+    #[serde(default)]
+    confirm_commands: bool,
+    /// Running an app focuses its most recent window when it has one open, on Hyprland.
+    #[serde(default)]
+    focus_or_launch: bool,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            drag_command: None,
+            drop_command: None,
+            highlight_color: default_highlight_color(),
+            window_size: None,
+            grid_size: None,
+            compact_grid: false,
+            grid_badges: false,
+            new_app_days: default_new_app_days(),
+            plugins: default_plugins(&default_files_prefix()),
+            files_prefix: default_files_prefix(),
+            terminal_profiles: Default::default(),
+            editor: None,
+            preview: false,
+            max_results: None,
+            grid_filter: 0,
+            toggle_shortcut: None,
+            hooks: Vec::new(),
+            secret_timeout: default_secret_timeout(),
+            confirm_commands: false,
+            focus_or_launch: false,
+        }
+    }
 }
 
 struct TypedListWidgets {
@@ -380,6 +878,9 @@ struct TypedListWidgets {
     tag: CenterBox,
     icon: Overlay,
     small_icon: CenterBox,
+    badge: Label,
+    /// Added on bind, rows are recycled so they must be removed again on unbind.
+    controllers: Vec<gtk::EventController>,
 }
 
 struct TypedListEntry {
@@ -388,6 +889,13 @@ struct TypedListEntry {
     sender: AsyncComponentSender<AppModel>,
     entry: Rc<Entry>,
     color: PangoColor,
+    /// The badge of the row currently showing this entry, if any.
+    badge: Option<Label>,
+    /// The row currently showing this entry, if any.
+    row: Option<GBox>,
+    marked: bool,
+    /// Shown in place of the small icon in global search, to tell which plugin the entry is from.
+    plugin_icon: Option<String>,
 }
 
 impl RelmListItem for TypedListEntry {
@@ -404,6 +912,14 @@ impl RelmListItem for TypedListEntry {
                     Overlay {
                         #[name = "small_icon"]
                         add_overlay = &CenterBox {},
+
+                        #[name = "badge"]
+                        add_overlay = &Label {
+                            set_halign: Align::Start,
+                            set_valign: Align::Start,
+                            set_visible: false,
+                            add_css_class: "badge",
+                        },
                     },
 
                     GBox {
@@ -449,6 +965,8 @@ impl RelmListItem for TypedListEntry {
                 tag,
                 icon,
                 small_icon,
+                badge,
+                controllers: Vec::new(),
             },
         )
     }
@@ -460,8 +978,15 @@ impl RelmListItem for TypedListEntry {
             tag,
             icon,
             small_icon,
+            badge,
+            controllers,
         } = widgets;
 
+        badge.set_visible(false);
+        self.badge = Some(badge.clone());
+        self.row = Some(root.clone());
+        root.set_class_active("marked", self.marked);
+
         let gesture = GestureClick::new();
 
         let index = self.index;
@@ -480,7 +1005,8 @@ impl RelmListItem for TypedListEntry {
             sender.input(AppMsg::GestureRelease);
         });
 
-        root.add_controller(gesture);
+        root.add_controller(gesture.clone());
+        controllers.push(gesture.upcast());
 
         let gesture = GestureClick::new();
         gesture.set_button(gdk::BUTTON_SECONDARY);
@@ -491,10 +1017,11 @@ impl RelmListItem for TypedListEntry {
             sender.input(AppMsg::GestureStart(index, true));
         });
 
-        root.add_controller(gesture);
+        root.add_controller(gesture.clone());
+        controllers.push(gesture.upcast());
 
         if let Some(path) = &self.entry.drag_file {
-            root.add_controller(create_drag_controller(
+            let drag_source = create_drag_controller(
                 path,
                 match &self.entry.icon {
                     EntryIcon::Name(name) => Some(name),
@@ -503,7 +1030,10 @@ impl RelmListItem for TypedListEntry {
                     _ => None,
                 },
                 self.sender.clone(),
-            ));
+            );
+
+            root.add_controller(drag_source.clone());
+            controllers.push(drag_source.upcast());
         }
 
         let entry = &self.entry;
@@ -587,7 +1117,11 @@ impl RelmListItem for TypedListEntry {
             match &entry.small_icon {
                 EntryIcon::Name(value) => Some(Image::from_icon_name(value)),
                 EntryIcon::Path(value) => Some(Image::from_file(value)),
-                _ => None,
+                _ => self.plugin_icon.as_deref().map(|x| {
+                    let image = Image::from_icon_name(x);
+                    image.add_css_class("plugin_icon");
+                    image
+                }),
             }
             .inspect(|x| {
                 x.set_use_fallback(true);
@@ -599,32 +1133,197 @@ impl RelmListItem for TypedListEntry {
             .as_ref(),
         );
     }
+
+    fn unbind(&mut self, widgets: &mut Self::Widgets, root: &mut Self::Root) {
+        self.badge = None;
+        self.row = None;
+
+        for controller in widgets.controllers.drain(..) {
+            root.remove_controller(&controller);
+        }
+    }
 }
 
 struct AppModel {
     query: String,
-    thread_handle: Option<stoppable_thread::StoppableHandle<()>>,
-    plugins: Arc<RwLock<Vec<Box<dyn Plugin>>>>,
+    search_token: CancellationToken,
+    /// Each behind its own lock, so a slow search only holds back what needs that plugin.
+    plugins: Vec<SharedPlugin>,
+    /// Plugins that panicked while searching, they are skipped until the config is reloaded.
+    disabled: Arc<Mutex<HashSet<usize>>>,
     selected_plugin: Option<usize>,
     selected_entry: usize,
     pressing_entry: bool,
     list_entries_wrapper: TypedListView<TypedListEntry, gtk::SingleSelection>,
     grid_entries: FactoryVecDeque<GridEntryComponent>,
     grid_size: usize,
+    window_size: [usize; 2],
+    /// Every entry for the home grid, shown `grid_size * grid_size` at a time.
+    grid_results: Vec<(usize, Rc<Entry>)>,
+    /// The ones matching the query while it filters the grid, all of them otherwise.
+    grid_matches: Vec<(usize, Rc<Entry>)>,
+    /// The query is short enough to filter the grid rather than search.
+    filtering_grid: bool,
+    grid_page: usize,
     search_entry: Controller<SearchEntryModel>,
     visible: bool,
-    context: Arc<RwLock<Context>>,
+    /// Searches work on a snapshot, so the actions don't wait for them to write to it.
+    context: Arc<RwLock<Arc<Context>>>,
     dragging: bool,
     config: AppConfig,
     css_provider: CssProvider,
     selected_action: Option<usize>,
+    /// While the actions are open what's typed filters them, this is the query to go back to.
+    actions_query: Option<String>,
+    action_filter: String,
     loading: bool,
     locked: bool,
+    dmenu: bool,
+    restore_selection: Option<usize>,
+    show_badges: bool,
+    /// The shortcut key being held, so it isn't dispatched again when it repeats.
+    held_key: Rc<Cell<Option<Key>>>,
+    /// The keys of the actions of the selected entry.
+    shortcuts: Rc<RefCell<Vec<(Key, ModifierType)>>>,
+    /// Entries marked with Ctrl+Space, actions are applied to all of them.
+    marked: Vec<(usize, Rc<Entry>)>,
+    preview: Option<gtk::Widget>,
+    preview_generation: usize,
+    /// An action waiting for the user to confirm it with Enter.
+    confirming: Option<(String, Arc<Mutex<Box<ActionType>>>)>,
+    /// Actions waiting on their hooks, with the index of each and whether they're a batch.
+    hooked: Option<(usize, Vec<(Rc<Entry>, usize)>, bool)>,
+    hook_generation: usize,
+    /// Reverts the last destructive action, offered for a few seconds after it.
+    undo: Option<(String, Arc<Mutex<Box<ActionType>>>)>,
+    /// The selected entry is shown enlarged on top of the results.
+    zoomed: bool,
+    /// A remote client waiting for the user to pick an entry.
+    picking: Option<std::sync::mpsc::Sender<Option<remote::Picked>>>,
+    /// Shown over the results for a few seconds, like when a plugin crashes.
+    notice: Option<String>,
+    config_error: Option<ConfigError>,
+    /// The id of the entry whose note is being written in the search bar,
+    /// and the query to go back to.
+    annotating: Option<(String, String)>,
+    search_generation: usize,
+    results_generation: usize,
+    /// Set while results wait to be shown, to whether they're the first of their search.
+    flushing: Option<bool>,
+    /// Every result of the search, sorted.
+    all_results: Vec<(usize, Rc<Entry>)>,
+    /// The ones shown, up to the limit of each plugin.
+    results: Vec<(usize, Rc<Entry>)>,
+    /// Plugins whose results are all shown, after picking "Show more".
+    expanded: HashSet<usize>,
+    profile: startup::StartupProfile,
+    /// How many plugins are still loading.
+    pending_plugins: usize,
+    /// Bumped on each reload, so plugins loaded for the previous config are dropped.
+    load_generation: usize,
+    load_token: CancellationToken,
 }
 
 impl AppModel {
+    /// Copies the context first if a search still holds a snapshot of it.
+    fn context_mut(&self) -> MappedRwLockWriteGuard<'_, Context> {
+        RwLockWriteGuard::map(self.context.write(), Arc::make_mut)
+    }
+
     fn use_grid(&self) -> bool {
-        self.selected_plugin.is_none() && self.query.is_empty()
+        self.selected_plugin.is_none() && (self.query.is_empty() || self.filtering_grid)
+    }
+
+    fn filter_grid(&mut self) {
+        if !self.filtering_grid {
+            self.grid_matches = self.grid_results.clone();
+            return;
+        }
+
+        let matcher = fuzzy_matcher::skim::SkimMatcherV2::default().ignore_case();
+        self.grid_matches = self
+            .grid_results
+            .iter()
+            .filter_map(|x| Some((matcher.fuzzy_match(&x.1.name.text, &self.query)?, x)))
+            // Stable, so ties keep the order of the grid
+            .sorted_by_key(|(score, _)| std::cmp::Reverse(*score))
+            .map(|(_, x)| x.clone())
+            .collect();
+    }
+
+    fn grid_pages(&self) -> usize {
+        self.grid_matches
+            .len()
+            .div_ceil(self.grid_size * self.grid_size)
+            .max(1)
+    }
+
+    fn show_grid_page(&mut self, page: usize) {
+        let page_size = self.grid_size * self.grid_size;
+        self.grid_page = page.min(self.grid_pages() - 1);
+
+        let context = self.context.read();
+        let mut grid_entries = self.grid_entries.guard();
+        grid_entries.clear();
+        for (plugin, entry) in self
+            .grid_matches
+            .iter()
+            .skip(self.grid_page * page_size)
+            .take(page_size)
+        {
+            let usage = self
+                .config
+                .grid_badges
+                .then(|| usage_badge(&context, &entry.id, self.config.new_app_days))
+                .flatten();
+
+            grid_entries.push_back((
+                *plugin,
+                entry.clone(),
+                self.grid_size,
+                self.config.compact_grid,
+                usage,
+            ));
+        }
+    }
+
+    /// Index of the first list row that is fully scrolled into view.
+    fn first_visible_row(&self) -> usize {
+        let rows = self.list_entries_wrapper.len() as f64;
+        self.list_entries_wrapper
+            .view
+            .vadjustment()
+            .filter(|x| x.upper() > 0.0)
+            .map(|x| (x.value() * rows / x.upper()).ceil() as usize)
+            .unwrap_or(0)
+    }
+
+    /// Numbers the first 9 visible entries for Alt+number, or hides the numbers.
+    fn update_badges(&self) {
+        if self.use_grid() {
+            for i in 0..self.grid_entries.len() {
+                let badge = (self.show_badges && i < 9).then_some(i + 1);
+                self.grid_entries.send(i, EntryMsg::SetBadge(badge));
+            }
+        } else {
+            let first = self.first_visible_row();
+            for i in 0..self.list_entries_wrapper.len() {
+                let Some(item) = self.list_entries_wrapper.get(i) else {
+                    continue;
+                };
+
+                if let Some(badge) = &item.borrow().badge {
+                    let number = (i as usize)
+                        .checked_sub(first)
+                        .filter(|x| self.show_badges && *x < 9);
+
+                    badge.set_visible(number.is_some());
+                    if let Some(number) = number {
+                        badge.set_label(&(number + 1).to_string());
+                    }
+                }
+            }
+        }
     }
 
     fn current_entry(&self) -> Option<Rc<Entry>> {
@@ -641,117 +1340,816 @@ impl AppModel {
         }
     }
 
-    fn execute_action(&self, action: &EntryAction, sender: AsyncComponentSender<Self>) {
-        match (action.function)(&mut self.context.write()) {
-            ActionResult::Ok => sender.input(AppMsg::MaybeHide),
-            ActionResult::Error => {}
-            ActionResult::SetText(text) => self.search_entry.emit(text),
-            ActionResult::SetPlugin(plugin) => {
-                sender.input(AppMsg::SetPlugin(plugin));
+    /// Loads the plugins in the background, the ones used most for how long they take first.
+    fn load_plugins(&mut self, sender: &AsyncComponentSender<Self>) {
+        let plugins = self.config.plugins.clone();
+
+        self.plugins = plugins
+            .iter()
+            .map(|x| shared(Box::new(PendingPlugin(format!("{:?}", x.r#type)))))
+            .collect();
+        self.pending_plugins = plugins.len();
+
+        let keys = plugins.iter().map(PluginConfig::key).collect_vec();
+        let mut order = self.profile.order(&keys);
+        // Stable, so the profile only orders plugins of the same priority
+        order.sort_by_key(|&i| std::cmp::Reverse(plugins[i].priority));
+
+        self.load_token.cancel();
+        self.load_token = CancellationToken::new();
+        self.load_generation += 1;
+
+        let generation = self.load_generation;
+        let token = self.load_token.clone();
+        let sender = sender.clone();
+        let context = self.context.clone();
+        tokio::spawn(async move {
+            let context = context.read().clone();
+            for i in order {
+                if token.is_cancelled() {
+                    return;
+                }
+
+                let start = Instant::now();
+                let plugin = load_plugin(i, &plugins[i], &context, &sender);
+                sender.input(AppMsg::PluginLoaded(generation, i, plugin, start.elapsed()));
             }
-        }
+        });
     }
-}
 
-fn widget_for_keybind(description: &str, key: Key, modifier: ModifierType) -> Button {
-    view! {
-        res = Button {
-            add_css_class: "keybind",
-            set_can_focus: false,
-            set_cursor_from_name: Some("pointer"),
+    /// The indices of the actions matching what's typed while they're open.
+    fn visible_actions(&self, actions: &[EntryAction]) -> Vec<usize> {
+        let matcher = fuzzy_matcher::skim::SkimMatcherV2::default().ignore_case();
+        actions
+            .iter()
+            .enumerate()
+            .filter(|(_, x)| {
+                self.action_filter.is_empty()
+                    || matcher.fuzzy_match(&x.name, &self.action_filter).is_some()
+            })
+            .map(|(i, _)| i)
+            .collect()
+    }
 
-            GBox {
-                Label {
-                    set_label: description,
-                    add_css_class: "description",
-                },
+    /// Puts back the query the actions were opened on.
+    fn close_actions(&mut self) {
+        self.selected_action = None;
+        self.action_filter.clear();
 
-                Label {
-                    set_label: "keyboard_command_key",
-                    add_css_class: "key_symbol",
-                    set_visible: modifier.contains(ModifierType::SUPER_MASK),
-                },
+        if let Some(query) = &self.actions_query {
+            self.search_entry.widget().set_text(query);
+        }
+    }
 
-                Label {
-                    set_label: "keyboard_control_key",
-                    add_css_class: "key_symbol",
-                    set_visible: modifier.contains(ModifierType::CONTROL_MASK),
-                },
+    /// The index of the plugin the shown entry comes from.
+    fn plugin_of(&self, entry: &Entry) -> Option<usize> {
+        self.results
+            .iter()
+            .chain(&self.grid_results)
+            .find(|(_, x)| std::ptr::eq(&**x, entry))
+            .map(|(i, _)| *i)
+    }
 
-                Label {
-                    set_label: "keyboard_option_key",
-                    add_css_class: "key_symbol",
-                    set_visible: modifier.contains(ModifierType::ALT_MASK),
-                },
+    fn start_search(&mut self, sender: &AsyncComponentSender<Self>) {
+        self.search_token.cancel();
+        self.search_token = CancellationToken::new();
 
-                Label {
-                    set_label: "shift_lock",
-                    add_css_class: "key_symbol",
-                    set_visible: modifier.contains(ModifierType::SHIFT_MASK),
-                },
+        self.search_generation += 1;
+        let generation = self.search_generation;
 
-                Label {
-                    set_label: &match key {
-                        Key::Return => "keyboard_return".to_owned(),
-                        _ => key
-                            .name()
-                                .map(|x| x.to_uppercase())
-                                .unwrap_or("<none>".to_owned()),
-                        },
-                        add_css_class: if key == Key::Return { "key_symbol" } else { "key" },
+        if !self.use_grid() {
+            self.loading = true;
+
+            let plugins = self.plugins.clone();
+            let config_plugins = self.config.plugins.clone();
+            let selected_plugin = self.selected_plugin;
+            let query = self.query.clone();
+            let context = self.context.read().clone();
+            let sender = sender.clone();
+            let token = self.search_token.clone();
+            let disabled = self.disabled.clone();
+            tokio::task::spawn_blocking(move || {
+                let plugins = config_plugins
+                    .iter()
+                    .zip(plugins.iter())
+                    .enumerate()
+                    .map(|(a, (b, c))| (a, b, c))
+                    .collect_vec();
+
+                let send = |entries: Vec<(usize, Entry)>| {
+                    if !token.is_cancelled() {
+                        sender.input(AppMsg::SearchResults(generation, entries));
                     }
-                }
-            }
-    }
+                };
 
-    res
-}
+                // Results are shown as soon as each plugin hands them out
+                let search = |i: usize, plugin: &SharedPlugin| {
+                    if disabled.lock().contains(&i) {
+                        return;
+                    }
 
-const MASK_ICONS: [(ModifierType, &str); 4] = [
-    (ModifierType::SUPER_MASK, "keyboard_command_key"),
-    (ModifierType::CONTROL_MASK, "keyboard_control_key"),
-    (ModifierType::ALT_MASK, "keyboard_option_key"),
-    (ModifierType::SHIFT_MASK, "shift"),
-];
+                    let plugin = plugin.read();
 
-fn create_actions_box(
-    actions: &[EntryAction],
-    index: usize,
-    sender: &AsyncComponentSender<AppModel>,
-) -> GBox {
-    let result = GBox::new(Orientation::Vertical, 0);
-    result.add_css_class("actions_box");
+                    let _span = tracing::debug_span!("search", plugin = plugin.name()).entered();
+                    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+                        plugin.search_stream(&query, &context, &token, &mut |entries| {
+                            send(entries.into_iter().map(|x| (i, x)).collect())
+                        });
+                    }));
 
-    for (
-        i,
-        EntryAction {
-            icon,
-            name,
-            key,
-            modifier,
-            ..
-        },
-    ) in actions.iter().enumerate()
-    {
-        let action_box = GBox::default();
-        action_box.add_css_class("action");
-        action_box.set_class_active("selected", i == index);
+                    if let Err(payload) = result {
+                        let message = panic_message(&*payload);
+                        tracing::error!(
+                            plugin = plugin.name(),
+                            panic = %message,
+                            "Plugin panicked"
+                        );
+                        sender.input(AppMsg::PluginFailed(
+                            i,
+                            format!("{} crashed and was disabled: {message}", plugin.name()),
+                        ));
+                    }
+                };
 
-        {
-            let icon = Image::from_icon_name(icon);
-            icon.set_pixel_size(24);
-            icon.set_halign(Align::Start);
-            icon.add_css_class("action_icon");
-            action_box.append(&icon);
-        }
+                match selected_plugin.and_then(|i| plugins.get(i)) {
+                    None => {
+                        let plugin = plugins.iter().find(|(_, plugin, _)| {
+                            !plugin.replace
+                                && plugin.regex.as_ref().is_some_and(|x| x.is_match(&query))
+                        });
 
-        {
-            let label = Label::new(Some(name));
-            label.set_halign(Align::Start);
-            label.set_hexpand(true);
-            label.add_css_class("action_name");
-            action_box.append(&label);
-        }
+                        if let Some((i, _, plugin)) = plugin {
+                            search(*i, plugin);
+                        } else {
+                            send(
+                                plugins
+                                    .iter()
+                                    .filter(|(_, x, _)| !x.default)
+                                    .flat_map(|(i, _, x)| {
+                                        let x = x.read();
+                                        Some((*i, plugin_entry_from_query(*i, x.as_ref(), &query)?))
+                                    })
+                                    .collect_vec(),
+                            );
+
+                            // A few workers take the plugins in turn, so slow ones don't hold
+                            // back the rest without starting a thread for each of them
+                            let queue =
+                                Mutex::new(plugins.iter().filter(|(_, plugin, _)| {
+                                    plugin.default && plugin.regex.is_none()
+                                }));
+                            let workers = std::thread::available_parallelism()
+                                .map_or(4, |x| x.get())
+                                .min(MAX_SEARCH_THREADS);
+                            std::thread::scope(|scope| {
+                                for _ in 0..workers {
+                                    scope.spawn(|| {
+                                        while !token.is_cancelled() {
+                                            let Some((i, _, plugin)) = queue.lock().next() else {
+                                                break;
+                                            };
+                                            search(*i, plugin);
+                                        }
+                                    });
+                                }
+                            });
+                        }
+                    }
+                    Some((i, _, plugin)) => search(*i, plugin),
+                }
+
+                if !token.is_cancelled() {
+                    sender.input(AppMsg::SearchFinished(generation));
+                }
+            });
+        } else {
+            sender.input(AppMsg::SearchFinished(generation));
+        }
+    }
+
+    /// Previews can be slow to compute, like screenshots, so they wait for the
+    /// selection to settle instead of being computed for every entry passed over.
+    fn update_preview(&mut self, sender: &AsyncComponentSender<Self>) {
+        self.preview_generation += 1;
+
+        if !self.config.preview || self.use_grid() {
+            self.preview = None;
+            return;
+        }
+
+        let generation = self.preview_generation;
+        let sender = sender.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(PREVIEW_DELAY).await;
+            sender.input(AppMsg::ShowPreview(generation));
+        });
+    }
+
+    fn show_preview(&mut self) {
+        let preview = if self.config.preview && !self.use_grid() {
+            self.list_entries_wrapper
+                .get(self.selected_entry as u32)
+                .and_then(|item| {
+                    let item = item.borrow();
+                    self.plugins.get(item.plugin)?.read().preview(&item.entry)
+                })
+        } else {
+            None
+        };
+
+        self.preview = preview.map(|x| create_preview(&x));
+    }
+
+    fn is_marked(&self, entry: &Rc<Entry>) -> bool {
+        self.marked
+            .iter()
+            .any(|(_, x)| Rc::ptr_eq(x, entry) || !entry.id.is_empty() && x.id == entry.id)
+    }
+
+    fn max_results(&self, plugin: usize) -> Option<usize> {
+        let config = self.config.plugins.get(plugin)?;
+        config
+            .max_results
+            .or(self.config.max_results)
+            .or(config.r#type.max_results())
+    }
+
+    /// Keeps the results of each plugin up to its limit, followed by an entry showing the rest.
+    fn limit_results(&self) -> Vec<(usize, Rc<Entry>)> {
+        let mut counts = HashMap::<usize, usize>::new();
+        let mut hidden = BTreeMap::<usize, usize>::new();
+        let mut results = Vec::new();
+
+        for (plugin, entry) in &self.all_results {
+            let count = counts.entry(*plugin).or_default();
+
+            if self.expanded.contains(plugin)
+                || self.max_results(*plugin).is_none_or(|x| *count < x)
+            {
+                *count += 1;
+                results.push((*plugin, entry.clone()));
+            } else {
+                *hidden.entry(*plugin).or_default() += 1;
+            }
+        }
+
+        for (plugin, count) in hidden {
+            let Some(name) = self.plugins.get(plugin).map(|x| x.read().name().to_owned()) else {
+                continue;
+            };
+
+            let entry = Entry {
+                name: FormattedString::plain(format!("Show more from {name}")),
+                description: Some(FormattedString::plain(match count {
+                    1 => "1 more result".to_owned(),
+                    x => format!("{x} more results"),
+                })),
+                icon: EntryIcon::Name("view-more".into()),
+                actions: vec![EntryAction {
+                    icon: "view-more".into(),
+                    name: "Show more".into(),
+                    function: Box::new(move |_| ActionResult::ShowMore(plugin)),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            };
+
+            let position = results
+                .iter()
+                .rposition(|(i, _)| *i == plugin)
+                .map_or(results.len(), |x| x + 1);
+            results.insert(position, (plugin, Rc::new(entry)));
+        }
+
+        results
+    }
+
+    fn rebuild_list(&self, sender: &AsyncComponentSender<Self>) {
+        // Only needed when the results come from several plugins
+        let plugin_icon = |plugin: usize| {
+            self.selected_plugin
+                .is_none()
+                .then(|| self.plugins.get(plugin)?.read().icon().map(str::to_owned))
+                .flatten()
+        };
+
+        self.list_entries_wrapper.clear();
+        self.list_entries_wrapper
+            .extend_from_iter(
+                self.results
+                    .iter()
+                    .enumerate()
+                    .map(|(index, (plugin, entry))| TypedListEntry {
+                        plugin: *plugin,
+                        index,
+                        entry: entry.clone(),
+                        color: self.config.highlight_color,
+                        sender: sender.clone(),
+                        badge: None,
+                        row: None,
+                        marked: self.is_marked(entry),
+                        plugin_icon: plugin_icon(*plugin),
+                    }),
+            );
+    }
+
+    fn execute_action(
+        &mut self,
+        entry: &Rc<Entry>,
+        index: usize,
+        sender: AsyncComponentSender<Self>,
+    ) {
+        if let Some(picking) = self.picking.take() {
+            let _ = picking.send(Some(remote::Picked {
+                name: entry.name.text.clone(),
+                id: entry.id.clone(),
+            }));
+            sender.input(AppMsg::Hide);
+            return;
+        }
+
+        if !self.marked.is_empty() && !self.use_grid() {
+            // The entry the action was picked on goes too, even when it isn't marked
+            let unmarked = (!self.is_marked(entry)).then(|| entry.clone());
+            let marked = std::mem::take(&mut self.marked);
+            let name = &entry.actions[index].name;
+            let actions = marked
+                .into_iter()
+                .map(|(_, x)| x)
+                .chain(unmarked)
+                .filter_map(|x| {
+                    let index = x.actions.iter().position(|x| x.name == *name)?;
+                    Some((x, index))
+                })
+                .collect();
+            self.execute_hooked(actions, true, false, sender);
+            return;
+        }
+
+        self.execute_hooked(vec![(entry.clone(), index)], false, false, sender);
+    }
+
+    /// Runs the actions once their before hooks allowed it. The hooks are commands,
+    /// so they run off the UI thread and the actions are held back until they're done.
+    fn execute_hooked(
+        &mut self,
+        actions: Vec<(Rc<Entry>, usize)>,
+        batch: bool,
+        confirmed: bool,
+        sender: AsyncComponentSender<Self>,
+    ) {
+        let before = |entry: &Entry, index: usize| {
+            let action = &entry.actions[index];
+            self.config
+                .hooks
+                .iter()
+                .filter(|x| x.when == HookTime::Before && x.matches(entry, action))
+                .collect_vec()
+        };
+
+        let confirm = actions
+            .iter()
+            .filter(|_| !confirmed)
+            .find(|(entry, index)| before(entry, *index).iter().any(|x| x.confirm))
+            .map(|(entry, index)| {
+                let action = &entry.actions[*index].name;
+                match actions.len() {
+                    1 => format!("{action} \"{}\"?", entry.name.text),
+                    n => format!("{action} \"{}\" and {} more?", entry.name.text, n - 1),
+                }
+            });
+
+        let commands = actions
+            .iter()
+            .map(|(entry, index)| {
+                before(entry, *index)
+                    .into_iter()
+                    .filter_map(|x| x.command(entry, &entry.actions[*index]))
+                    .collect_vec()
+            })
+            .collect_vec();
+
+        if let Some(message) = confirm {
+            self.hook_generation += 1;
+            let id = self.hook_generation;
+            let function: Box<ActionType> = Box::new(move |_| {
+                sender.input(AppMsg::HooksConfirmed(id));
+                // Run by the launcher, so the hooks see it
+                ActionResult::Error
+            });
+
+            self.close_actions();
+            self.confirming = Some((message, Arc::new(Mutex::new(function))));
+            self.hooked = Some((id, actions, batch));
+            return;
+        }
+
+        if commands.iter().all(Vec::is_empty) {
+            self.run_actions(actions, batch, sender);
+            return;
+        }
+
+        self.hook_generation += 1;
+        let id = self.hook_generation;
+        self.hooked = Some((id, actions, batch));
+
+        tokio::task::spawn_blocking(move || {
+            let passed = commands
+                .into_iter()
+                .map(|commands| {
+                    commands
+                        .into_iter()
+                        .all(|mut command| match command.status() {
+                            Ok(x) if x.success() => true,
+                            status => {
+                                tracing::debug!(?status, "Action cancelled by a hook");
+                                false
+                            }
+                        })
+                })
+                .collect();
+
+            sender.input(AppMsg::HooksFinished(id, passed));
+        });
+    }
+
+    fn run_after_hooks(&self, entry: &Entry, action: &EntryAction) {
+        let after = self
+            .config
+            .hooks
+            .iter()
+            .filter(|x| x.when == HookTime::After && x.matches(entry, action));
+
+        for mut command in after.filter_map(|x| x.command(entry, action)) {
+            if let Err(error) = command.spawn() {
+                tracing::warn!(?error, "Failed to run a hook");
+            }
+        }
+    }
+
+    fn run_actions(
+        &mut self,
+        actions: Vec<(Rc<Entry>, usize)>,
+        batch: bool,
+        sender: AsyncComponentSender<Self>,
+    ) {
+        if batch {
+            self.execute_batch(&actions, sender);
+            return;
+        }
+
+        let Some((entry, index)) = actions.first() else {
+            return;
+        };
+        let action = &entry.actions[*index];
+
+        let result = (action.function)(&mut self.context_mut());
+
+        // Nothing ran yet when it asks to be confirmed first
+        let succeeded = !matches!(result, ActionResult::Error | ActionResult::Confirm(..));
+
+        if succeeded && !entry.id.is_empty() {
+            self.context_mut().frecency.record(&entry.id);
+        }
+
+        if succeeded && let Some(plugin) = self.plugin_of(entry) {
+            self.profile.record_use(&self.config.plugins[plugin].key());
+        }
+
+        if succeeded {
+            self.run_after_hooks(entry, action);
+        }
+
+        self.handle_result(result, sender);
+    }
+
+    /// Runs the picked action of each entry, copies are joined together.
+    fn execute_batch(
+        &mut self,
+        actions: &[(Rc<Entry>, usize)],
+        sender: AsyncComponentSender<Self>,
+    ) {
+        let mut succeeded = Vec::new();
+        let (result, copies) = {
+            let mut context = self.context_mut();
+            context.batch_copies = Some(Vec::new());
+
+            let mut result = ActionResult::Error;
+            let mut confirms = Vec::new();
+            for (entry, index) in actions {
+                let action = &entry.actions[*index];
+                match (action.function)(&mut context) {
+                    ActionResult::Confirm(message, function) => confirms.push((message, function)),
+                    x => {
+                        if !matches!(x, ActionResult::Error) {
+                            if !entry.id.is_empty() {
+                                context.frecency.record(&entry.id);
+                            }
+                            succeeded.push((entry, action));
+                        }
+                        result = x;
+                    }
+                }
+            }
+
+            let result = confirm_all(confirms).unwrap_or(result);
+            (result, context.batch_copies.take().unwrap_or_default())
+        };
+
+        for (entry, action) in succeeded {
+            self.run_after_hooks(entry, action);
+        }
+
+        if !copies.is_empty() {
+            // Symbols like emojis are joined together, anything else one per line
+            let separator = if copies.iter().all(|x| !x.chars().any(char::is_alphanumeric)) {
+                ""
+            } else {
+                "\n"
+            };
+
+            let mut context = self.context_mut();
+            (EntryAction::copy(copies.join(separator)))(&mut context);
+        }
+
+        self.rebuild_list(&sender);
+        self.handle_result(result, sender);
+    }
+
+    fn handle_result(&mut self, result: ActionResult, sender: AsyncComponentSender<Self>) {
+        match result {
+            ActionResult::Ok => sender.input(AppMsg::MaybeHide),
+            ActionResult::Error => {}
+            ActionResult::SetText(text) => self.search_entry.emit(text),
+            ActionResult::Refresh => sender.input(AppMsg::Refresh),
+            ActionResult::SetPlugin(plugin) => {
+                sender.input(AppMsg::SetPlugin(plugin));
+            }
+            ActionResult::ShowMore(plugin) => sender.input(AppMsg::ShowMore(plugin)),
+            ActionResult::Confirm(message, function) => {
+                self.close_actions();
+                self.confirming = Some((message, function));
+            }
+            ActionResult::Undoable(message, function) => {
+                self.undo = Some((message.clone(), function));
+                // Kept open for the toast to be seen, with the results updated
+                sender.input(AppMsg::Refresh);
+
+                tokio::spawn(async move {
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                    sender.input(AppMsg::ClearUndo(message));
+                });
+            }
+        }
+    }
+}
+
+/// Asks once for all the actions of a batch that need to be confirmed, then runs every one of them.
+fn confirm_all(confirms: Vec<(String, Arc<Mutex<Box<ActionType>>>)>) -> Option<ActionResult> {
+    let (first, _) = confirms.first()?;
+    let message = match confirms.len() {
+        1 => first.clone(),
+        n => format!("{} and {} more?", first.trim_end_matches('?'), n - 1),
+    };
+
+    let function: Box<ActionType> = Box::new(move |context| {
+        let mut result = ActionResult::Error;
+        let mut pending = Vec::new();
+        for (_, function) in &confirms {
+            match (function.lock())(context) {
+                ActionResult::Confirm(message, function) => pending.push((message, function)),
+                x => result = x,
+            }
+        }
+
+        // Like commands, which may ask again
+        confirm_all(pending).unwrap_or(result)
+    });
+
+    Some(ActionResult::Confirm(
+        message,
+        Arc::new(Mutex::new(function)),
+    ))
+}
+
+fn widget_for_keybind(description: &str, key: Key, modifier: ModifierType) -> Button {
+    view! {
+        res = Button {
+            add_css_class: "keybind",
+            set_can_focus: false,
+            set_cursor_from_name: Some("pointer"),
+
+            GBox {
+                Label {
+                    set_label: description,
+                    add_css_class: "description",
+                },
+
+                Label {
+                    set_label: "keyboard_command_key",
+                    add_css_class: "key_symbol",
+                    set_visible: modifier.contains(ModifierType::SUPER_MASK),
+                },
+
+                Label {
+                    set_label: "keyboard_control_key",
+                    add_css_class: "key_symbol",
+                    set_visible: modifier.contains(ModifierType::CONTROL_MASK),
+                },
+
+                Label {
+                    set_label: "keyboard_option_key",
+                    add_css_class: "key_symbol",
+                    set_visible: modifier.contains(ModifierType::ALT_MASK),
+                },
+
+                Label {
+                    set_label: "shift_lock",
+                    add_css_class: "key_symbol",
+                    set_visible: modifier.contains(ModifierType::SHIFT_MASK),
+                },
+
+                Label {
+                    set_label: &match key {
+                        Key::Return => "keyboard_return".to_owned(),
+                        _ => key
+                            .name()
+                                .map(|x| x.to_uppercase())
+                                .unwrap_or("<none>".to_owned()),
+                        },
+                        add_css_class: if key == Key::Return { "key_symbol" } else { "key" },
+                    }
+                }
+            }
+    }
+
+    res
+}
+
+fn create_preview(preview: &Preview) -> gtk::Widget {
+    match preview {
+        Preview::Text(text) | Preview::Code(text) | Preview::Symbols(text) => {
+            let label = Label::new(Some(text));
+            label.set_halign(Align::Start);
+            label.set_valign(Align::Start);
+            label.set_xalign(0.0);
+            label.set_selectable(true);
+            label.set_can_focus(false);
+
+            match preview {
+                Preview::Code(_) => label.add_css_class("preview_code"),
+                Preview::Symbols(_) => {
+                    label.set_wrap(true);
+                    label.add_css_class("preview_symbols");
+                }
+                _ => {
+                    label.set_wrap(true);
+                    label.add_css_class("preview_text");
+                }
+            }
+
+            label.upcast()
+        }
+        Preview::Image(path) => {
+            let picture = gtk::Picture::for_filename(path);
+            picture.set_can_shrink(true);
+            picture.set_content_fit(gtk::ContentFit::Contain);
+            picture.set_valign(Align::Start);
+
+            picture.upcast()
+        }
+    }
+}
+
+/// The keybinds of every action that can be triggered from the keyboard.
+fn create_action_bar(
+    actions: &[EntryAction],
+    pressing: bool,
+    sender: &AsyncComponentSender<AppModel>,
+) -> GBox {
+    let result = GBox::default();
+
+    for (i, action) in actions.iter().enumerate() {
+        // Only the first action is bound to a plain Return
+        let bound = i == 0
+            || !(action.key == Key::Escape
+                || action.key == Key::Return && action.modifier.is_empty());
+        if !bound {
+            continue;
+        }
+
+        let button = widget_for_keybind(&action.name, action.key, action.modifier);
+        button.set_class_active("selected", i == 0 && pressing);
+
+        let sender = sender.clone();
+        button.connect_clicked(move |_| {
+            sender.input(AppMsg::ActivateSelectedWithAction(i));
+        });
+
+        result.append(&button);
+    }
+
+    result
+}
+
+/// Modifiers that are part of a keybind, lock keys like Num Lock are ignored.
+const SHORTCUT_MASK: ModifierType = ModifierType::SHIFT_MASK
+    .union(ModifierType::CONTROL_MASK)
+    .union(ModifierType::ALT_MASK)
+    .union(ModifierType::SUPER_MASK);
+
+const MASK_ICONS: [(ModifierType, &str); 4] = [
+    (ModifierType::SUPER_MASK, "keyboard_command_key"),
+    (ModifierType::CONTROL_MASK, "keyboard_control_key"),
+    (ModifierType::ALT_MASK, "keyboard_option_key"),
+    (ModifierType::SHIFT_MASK, "shift"),
+];
+
+/// The entry enlarged, with nothing cut off, for reading it more easily.
+fn create_zoom_box(entry: &Entry, color: PangoColor) -> GBox {
+    let result = GBox::new(Orientation::Vertical, 12);
+    result.add_css_class("zoom");
+
+    match &entry.icon {
+        EntryIcon::Name(value) => {
+            let image = Image::from_icon_name(value);
+            image.set_use_fallback(true);
+            image.set_pixel_size(128);
+            result.append(&image);
+        }
+        EntryIcon::Path(value) => {
+            let image = Image::from_file(value);
+            image.set_pixel_size(128);
+            result.append(&image);
+        }
+        EntryIcon::Text(value) => {
+            let label = Label::new(Some(value));
+            label.add_css_class("zoom_icon");
+            result.append(&label);
+        }
+        EntryIcon::None => {}
+    }
+
+    let texts = [
+        (Some(&entry.name), "zoom_name"),
+        (entry.tag.as_ref(), "zoom_tag"),
+        (entry.description.as_ref(), "zoom_description"),
+    ];
+
+    for (text, class) in texts {
+        let Some(text) = text.filter(|x| !x.text.is_empty()) else {
+            continue;
+        };
+
+        let label = Label::new(Some(&text.text));
+        label.set_attributes(Some(&text.to_attr_list(color.into())));
+        label.set_wrap(true);
+        label.set_wrap_mode(gtk::pango::WrapMode::WordChar);
+        label.set_max_width_chars(32);
+        label.set_justify(Justification::Center);
+        label.add_css_class(class);
+        result.append(&label);
+    }
+
+    result
+}
+
+fn create_actions_box(
+    actions: &[EntryAction],
+    visible: &[usize],
+    index: usize,
+    sender: &AsyncComponentSender<AppModel>,
+) -> ScrolledWindow {
+    let result = GBox::new(Orientation::Vertical, 0);
+
+    for &i in visible {
+        let EntryAction {
+            icon,
+            name,
+            key,
+            modifier,
+            ..
+        } = &actions[i];
+
+        let action_box = GBox::default();
+        action_box.add_css_class("action");
+        action_box.set_class_active("selected", i == index);
+
+        {
+            let icon = Image::from_icon_name(icon);
+            icon.set_pixel_size(24);
+            icon.set_halign(Align::Start);
+            icon.add_css_class("action_icon");
+            action_box.append(&icon);
+        }
+
+        {
+            let label = Label::new(Some(name));
+            label.set_halign(Align::Start);
+            label.set_hexpand(true);
+            label.add_css_class("action_name");
+            action_box.append(&label);
+        }
 
         for (mask, icon) in MASK_ICONS {
             if modifier.contains(mask) {
@@ -795,7 +2193,28 @@ fn create_actions_box(
         result.append(&button);
     }
 
-    result
+    let scrolled = ScrolledWindow::new();
+    scrolled.add_css_class("actions_box");
+    scrolled.set_hscrollbar_policy(gtk::PolicyType::Never);
+    scrolled.set_propagate_natural_height(true);
+    scrolled.set_max_content_height(480);
+    scrolled.set_child(Some(&result));
+
+    // Keeps the selected action in view once the list gets its size
+    let count = visible.len().max(1) as f64;
+    let index = visible.iter().position(|&x| x == index).unwrap_or(0);
+    scrolled.vadjustment().connect_changed(move |adjustment| {
+        let row = adjustment.upper() / count;
+        let top = row * index as f64;
+
+        if top < adjustment.value() {
+            adjustment.set_value(top);
+        } else if top + row > adjustment.value() + adjustment.page_size() {
+            adjustment.set_value(top + row - adjustment.page_size());
+        }
+    });
+
+    scrolled
 }
 
 #[relm4::component(async)]
@@ -804,15 +2223,15 @@ impl AsyncComponent for AppModel {
     type Output = ();
     type CommandOutput = CommandMsg;
 
-    type Init = (AppConfig, CssProvider);
+    type Init = (AppConfig, CssProvider, bool, Option<ConfigError>);
 
     view! {
         Window {
             set_title: Some("Jogger"),
             #[watch]
             set_default_size: (
-                model.config.window_size[0] as i32,
-                model.config.window_size[1] as i32,
+                model.window_size[0] as i32,
+                model.window_size[1] as i32,
             ),
             #[watch]
             set_visible: model.visible,
@@ -831,8 +2250,9 @@ impl AsyncComponent for AppModel {
                 add_controller = EventControllerKey::new() {
                     set_propagation_phase: PropagationPhase::Capture,
 
-                    connect_key_pressed[sender, entry = model.search_entry.widget().clone()] => move |_, key, _, modifier| {
+                    connect_key_pressed[sender, entry = model.search_entry.widget().clone(), held = model.held_key.clone(), shortcuts = model.shortcuts.clone()] => move |_, key, _, modifier| {
                         let is_empty = entry.text().is_empty();
+                        let modifier = modifier & SHORTCUT_MASK;
 
                         match key {
                             Key::Tab | Key::ISO_Left_Tab => {
@@ -885,7 +2305,7 @@ impl AsyncComponent for AppModel {
                             }
                             Key::BackSpace => {
                                 if is_empty {
-                                    sender.input(AppMsg::SetPlugin(None));
+                                    sender.input(AppMsg::Pop);
                                     return Propagation::Stop;
                                 }
                             }
@@ -897,6 +2317,44 @@ impl AsyncComponent for AppModel {
                             }
                             Key::c => {
                                 if modifier == ModifierType::CONTROL_MASK && entry.selection_bounds().is_none() {
+                                    if held.replace(Some(key.to_lower())) != Some(key.to_lower()) {
+                                        sender.input(AppMsg::Shortcut(key, modifier));
+                                    }
+                                    return Propagation::Stop;
+                                }
+                            }
+                            Key::space => {
+                                if modifier == ModifierType::CONTROL_MASK {
+                                    sender.input(AppMsg::ToggleMark);
+                                    return Propagation::Stop;
+                                }
+                            }
+                            Key::Return | Key::KP_Enter => {
+                                if modifier == ModifierType::CONTROL_MASK | ModifierType::SHIFT_MASK {
+                                    sender.input(AppMsg::ActivateAll(false));
+                                    return Propagation::Stop;
+                                } else if !modifier.is_empty() {
+                                    if held.replace(Some(key.to_lower())) != Some(key.to_lower()) {
+                                        sender.input(AppMsg::Shortcut(key, modifier));
+                                    }
+                                    return Propagation::Stop;
+                                }
+                            }
+                            Key::plus | Key::equal | Key::KP_Add => {
+                                if modifier.contains(ModifierType::CONTROL_MASK) {
+                                    sender.input(AppMsg::ToggleZoom);
+                                    return Propagation::Stop;
+                                }
+                            }
+                            Key::z => {
+                                if modifier == ModifierType::CONTROL_MASK {
+                                    sender.input(AppMsg::Undo);
+                                    return Propagation::Stop;
+                                }
+                            }
+                            Key::n => {
+                                if modifier == ModifierType::CONTROL_MASK {
+                                    sender.input(AppMsg::EditNote);
                                     return Propagation::Stop;
                                 }
                             }
@@ -916,30 +2374,47 @@ impl AsyncComponent for AppModel {
                                 sender.input(AppMsg::Reload);
                                 return Propagation::Stop;
                             },
-                            _ => {}
+                            Key::Alt_L | Key::Alt_R => {
+                                sender.input(AppMsg::ShowBadges(true));
+                            }
+                            _ => {
+                                if modifier == ModifierType::ALT_MASK
+                                    && let Some(n @ 1..=9) = key.to_unicode().and_then(|x| x.to_digit(10))
+                                {
+                                    sender.input(AppMsg::ActivateVisible(n as usize - 1));
+                                    return Propagation::Stop;
+                                }
+
+                                // Dispatched on press, the modifiers may be let go before the key.
+                                // Held down, the key repeats, but the action only runs once
+                                if !modifier.is_empty() && shortcuts.borrow().contains(&(key.to_lower(), modifier)) {
+                                    if held.replace(Some(key.to_lower())) != Some(key.to_lower()) {
+                                        sender.input(AppMsg::Shortcut(key, modifier));
+                                    }
+                                    return Propagation::Stop;
+                                }
+                            }
                         }
 
                         Propagation::Proceed
                     },
 
-                    connect_key_released[sender, entry = model.search_entry.widget().clone()] => move |_, key, _, modifier| {
+                    connect_key_released[sender, held = model.held_key.clone()] => move |_, key, _, modifier| {
+                        if held.get() == Some(key.to_lower()) {
+                            held.set(None);
+                        }
+
                         match key {
-                            Key::Return | Key::KP_Enter if modifier == ModifierType::NO_MODIFIER_MASK  => {
+                            Key::Return | Key::KP_Enter if (modifier & SHORTCUT_MASK).is_empty() => {
                                 sender.input(AppMsg::ActivateSelected);
                             }
                             Key::Escape => {
                                 sender.input(AppMsg::Escape);
                             }
-                            Key::c => {
-                                if modifier == ModifierType::CONTROL_MASK && entry.selection_bounds().is_none() {
-                                    sender.input(AppMsg::Shortcut(key, modifier));
-                                }
-                            }
-                            _ => {
-                                if modifier != ModifierType::NO_MODIFIER_MASK {
-                                    sender.input(AppMsg::Shortcut(key, modifier));
-                                }
+                            Key::Alt_L | Key::Alt_R => {
+                                sender.input(AppMsg::ShowBadges(false));
                             }
+                            _ => {}
                         };
                     },
                 },
@@ -955,8 +2430,8 @@ impl AsyncComponent for AppModel {
                             #[watch]
                             set_icon_name: Some(model.selected_plugin
                                 .and_then(|index| {
-                                    let plugin = model.plugins.read();
-                                    Some(plugin.get(index)?.icon()?.to_owned())
+                                    let plugin = model.plugins.get(index)?.read();
+                                    Some(plugin.icon()?.to_owned())
                                 })
                                 // .and_then(|plugin| plugin.icon())
                                 .unwrap_or("edit-find".to_string()))
@@ -995,28 +2470,76 @@ impl AsyncComponent for AppModel {
 
                     if model.use_grid() {
                         &GBox {
+                            set_orientation: Vertical,
+
+                            add_controller = EventControllerScroll::new(
+                                EventControllerScrollFlags::VERTICAL | EventControllerScrollFlags::DISCRETE,
+                            ) {
+                                connect_scroll[sender] => move |_, _, dy| {
+                                    if dy < 0.0 {
+                                        sender.input(AppMsg::Move(MoveDirection::PageUp));
+                                    } else if dy > 0.0 {
+                                        sender.input(AppMsg::Move(MoveDirection::PageDown));
+                                    }
+                                    Propagation::Stop
+                                },
+                            },
+
                             #[local_ref]
                             entries_grid -> Grid {
                                 #[watch]
                                 set_sensitive: model.selected_action.is_none(),
+                                #[watch]
+                                set_class_active: ("compact", model.config.compact_grid),
 
                                 set_row_homogeneous: true,
                                 set_column_homogeneous: true,
                                 set_expand: true,
                             },
+
+                            Label {
+                                add_css_class: "grid_selected_name",
+                                set_ellipsize: EllipsizeMode::End,
+                                #[watch]
+                                set_visible: model.config.compact_grid,
+                                #[watch]
+                                set_label: &model.current_entry().map(|x| x.name.text.clone()).unwrap_or_default(),
+                            },
+
+                            Label {
+                                add_css_class: "page_indicator",
+                                #[watch]
+                                set_visible: model.grid_pages() > 1,
+                                #[watch]
+                                set_label: &format!("{} / {}", model.grid_page + 1, model.grid_pages()),
+                            },
                         }
                     } else {
-                        scrolled_window = &ScrolledWindow {
-                            #[local_ref]
-                            my_view -> ListView {
-                                #[watch]
-                                set_sensitive: model.selected_action.is_none(),
-                            }
+                        &GBox {
+                            ScrolledWindow {
+                                set_hexpand: true,
 
-                            // #[local_ref]
-                            // entries -> ListBox {
+                                #[local_ref]
+                                my_view -> ListView {
+                                    #[watch]
+                                    set_sensitive: model.selected_action.is_none(),
+                                }
 
-                            // },
+                                // #[local_ref]
+                                // entries -> ListBox {
+
+                                // },
+                            },
+
+                            ScrolledWindow {
+                                add_css_class: "preview",
+                                set_width_request: 320,
+                                set_hscrollbar_policy: gtk::PolicyType::Never,
+                                #[watch]
+                                set_visible: model.preview.is_some(),
+                                #[watch]
+                                set_child: model.preview.as_ref(),
+                            },
                         }
                     },
 
@@ -1032,6 +2555,103 @@ impl AsyncComponent for AppModel {
                         },
                     },
 
+                    add_overlay = &Label {
+                        set_halign: Align::Center,
+                        set_valign: Align::Start,
+                        set_wrap: true,
+                        add_css_class: "notice",
+                        #[watch]
+                        set_visible: model.notice.is_some(),
+                        #[watch]
+                        set_label: model.notice.as_deref().unwrap_or_default(),
+                    },
+
+                    add_overlay = &GBox {
+                        set_halign: Align::Center,
+                        set_valign: Align::End,
+                        add_css_class: "confirm_box",
+                        #[watch]
+                        set_visible: model.confirming.is_some(),
+
+                        Label {
+                            add_css_class: "confirm_message",
+                            #[watch]
+                            set_label: model.confirming.as_ref().map_or("", |(x, _)| x),
+                        },
+
+                        append = &widget_for_keybind("Confirm", Key::Return, ModifierType::empty()) -> Button {
+                            connect_clicked[sender] => move |_| {
+                                sender.input(AppMsg::ActivateSelected);
+                            },
+                        },
+
+                        append = &widget_for_keybind("Cancel", Key::Escape, ModifierType::empty()) -> Button {
+                            connect_clicked[sender] => move |_| {
+                                sender.input(AppMsg::Escape);
+                            },
+                        },
+                    },
+
+                    add_overlay = &Overlay {
+                        set_halign: Align::Center,
+                        set_valign: Align::Center,
+                        #[watch]
+                        set_visible: model.zoomed,
+
+                        #[watch]
+                        set_child: model.current_entry()
+                            .filter(|_| model.zoomed)
+                            .map(|x| create_zoom_box(&x, model.config.highlight_color))
+                            .as_ref(),
+                    },
+
+                    add_overlay = &GBox {
+                        set_halign: Align::Center,
+                        set_valign: Align::Start,
+                        add_css_class: "undo_box",
+                        #[watch]
+                        set_visible: model.config_error.is_some(),
+
+                        Label {
+                            add_css_class: "confirm_message",
+                            set_wrap: true,
+                            #[watch]
+                            set_label: &model.config_error.as_ref().map_or(String::new(), |x| match x.line {
+                                Some(line) => format!("Config error on line {line}: {}", x.message),
+                                None => format!("Config error: {}", x.message),
+                            }),
+                        },
+
+                        Button {
+                            set_label: "Edit",
+                            set_can_focus: false,
+                            set_cursor_from_name: Some("pointer"),
+                            connect_clicked[sender] => move |_| {
+                                sender.input(AppMsg::EditConfig);
+                            },
+                        },
+                    },
+
+                    add_overlay = &GBox {
+                        set_halign: Align::Center,
+                        set_valign: Align::End,
+                        add_css_class: "undo_box",
+                        #[watch]
+                        set_visible: model.undo.is_some() && model.confirming.is_none(),
+
+                        Label {
+                            add_css_class: "confirm_message",
+                            #[watch]
+                            set_label: model.undo.as_ref().map_or("", |(x, _)| x),
+                        },
+
+                        append = &widget_for_keybind("Undo", Key::z, ModifierType::CONTROL_MASK) -> Button {
+                            connect_clicked[sender] => move |_| {
+                                sender.input(AppMsg::Undo);
+                            },
+                        },
+                    },
+
                     add_overlay = &Overlay {
                         set_halign: Align::End,
                         set_valign: Align::End,
@@ -1039,11 +2659,16 @@ impl AsyncComponent for AppModel {
                         set_visible: model.selected_action.is_some(),
 
                         #[watch]
-                        set_child: Some(&create_actions_box(
-                            model.current_entry().as_ref().map_or(&[], |x| &x.actions),
-                            model.selected_action.unwrap_or(0),
-                            &sender,
-                        )),
+                        set_child: Some(&{
+                            let entry = model.current_entry();
+                            let actions = entry.as_ref().map_or(&[][..], |x| &x.actions);
+                            create_actions_box(
+                                actions,
+                                &model.visible_actions(actions),
+                                model.selected_action.unwrap_or(0),
+                                &sender,
+                            )
+                        }),
                     },
                 },
 
@@ -1056,8 +2681,8 @@ impl AsyncComponent for AppModel {
                             #[watch]
                             set_icon_name: Some(model.selected_plugin
                                 .and_then(|index| {
-                                    let plugin = model.plugins.read();
-                                    Some(plugin.get(index)?.icon()?.to_owned())
+                                    let plugin = model.plugins.get(index)?.read();
+                                    Some(plugin.icon()?.to_owned())
                                 })
                                 // .and_then(|plugin| plugin.icon())
                                 .unwrap_or("edit-find".to_string()))
@@ -1068,32 +2693,29 @@ impl AsyncComponent for AppModel {
                             #[watch]
                             set_label: &model.selected_plugin
                                 .and_then(|index| {
-                                    let plugin = model.plugins.read();
-                                    Some(plugin.get(index)?.name().to_string())
+                                    Some(model.plugins.get(index)?.read().name().to_string())
                                 })
                                 .unwrap_or_default()
                         },
+
+                        Label {
+                            add_css_class: "marked_count",
+                            #[watch]
+                            set_visible: !model.marked.is_empty(),
+                            #[watch]
+                            set_label: &format!("{} marked", model.marked.len()),
+                        },
                     },
 
                     #[wrap(Some)]
                     set_end_widget = &GBox {
                         CenterBox {
                             #[watch]
-                            set_end_widget: model.current_entry()
-                                .as_ref()
-                                .and_then(|x| x.actions.first())
-                                .map(|x| {
-                                    let sender = sender.clone();
-                                    let selected_entry = model.selected_entry;
-
-                                    let button = widget_for_keybind(&x.name, x.key, x.modifier);
-                                    button.set_class_active("selected", model.pressing_entry);
-                                    button.connect_clicked(move |_| {
-                                        sender.input(AppMsg::Activate(selected_entry))
-                                    });
-                                    button
-                                })
-                                .as_ref(),
+                            set_end_widget: Some(&create_action_bar(
+                                model.current_entry().as_ref().map_or(&[], |x| &x.actions),
+                                model.pressing_entry,
+                                &sender,
+                            )),
                         },
 
                         Separator {
@@ -1131,33 +2753,68 @@ impl AsyncComponent for AppModel {
             .launch(Grid::default())
             .forward(sender.input_sender(), EntryOutput::into);
 
-        let grid_size = 5;
+        let (window_size, grid_size) = layout_for_monitor(&init.0, current_monitor(&root).as_ref());
 
         let search_entry = SearchEntryModel::builder()
             .launch(())
             .forward(sender.input_sender(), AppMsg::Search);
 
-        let plugins = init.0.plugins.clone();
+        let dmenu = init.2;
 
-        let model = AppModel {
+        let mut model = AppModel {
             query: String::new(),
-            thread_handle: None,
-            plugins: Arc::new(RwLock::new(Vec::new())),
-            selected_plugin: None,
+            search_token: CancellationToken::new(),
+            plugins: Vec::new(),
+            disabled: Arc::new(Mutex::new(HashSet::new())),
+            selected_plugin: dmenu.then_some(0),
             selected_entry: 0,
             pressing_entry: false,
             list_entries_wrapper,
             grid_entries,
             grid_size,
+            window_size,
+            grid_results: Vec::new(),
+            grid_matches: Vec::new(),
+            filtering_grid: false,
+            grid_page: 0,
             search_entry,
-            visible: false,
-            context: Arc::new(RwLock::new(Context::default())),
+            visible: dmenu,
+            context: Arc::new(RwLock::new(Arc::new(create_context(&init.0)))),
             dragging: false,
             config: init.0,
             css_provider: init.1,
             selected_action: None,
+            actions_query: None,
+            action_filter: String::new(),
             loading: false,
             locked: false,
+            dmenu,
+            restore_selection: None,
+            show_badges: false,
+            held_key: Rc::new(Cell::new(None)),
+            shortcuts: Rc::new(RefCell::new(Vec::new())),
+            marked: Vec::new(),
+            preview: None,
+            preview_generation: 0,
+            confirming: None,
+            hooked: None,
+            hook_generation: 0,
+            undo: None,
+            zoomed: false,
+            picking: None,
+            notice: None,
+            config_error: init.3,
+            annotating: None,
+            search_generation: 0,
+            results_generation: 0,
+            flushing: None,
+            all_results: Vec::new(),
+            results: Vec::new(),
+            expanded: HashSet::new(),
+            profile: startup::StartupProfile::load(),
+            pending_plugins: 0,
+            load_generation: 0,
+            load_token: CancellationToken::new(),
         };
 
         let my_view = &model.list_entries_wrapper.view;
@@ -1166,6 +2823,10 @@ impl AsyncComponent for AppModel {
 
         let _sender = sender.clone();
         tokio::spawn(async move {
+            if dmenu {
+                return;
+            }
+
             let (resource, c) = dbus_tokio::connection::new_session_sync().unwrap();
             let mut cr = Crossroads::new();
             let token = cr.register("com.psyvern.jogger", move |b| {
@@ -1180,6 +2841,18 @@ impl AsyncComponent for AppModel {
                     sender.input(AppMsg::Toggle);
                     Ok((true,))
                 });
+
+                let sender = _sender.clone();
+                b.method(
+                    "ShowWithQuery",
+                    ("query",),
+                    ("status",),
+                    move |_, _, (query,): (String,)| {
+                        sender.input(AppMsg::Show);
+                        sender.input(AppMsg::SetQuery(query));
+                        Ok((true,))
+                    },
+                );
             });
             cr.insert("/com/psyvern/jogger", &[token], ());
             c.start_receive(
@@ -1202,17 +2875,81 @@ impl AsyncComponent for AppModel {
             std::future::pending::<()>().await;
         });
 
-        let context = model.context.clone();
-        tokio::spawn(async move {
-            let context = context.read();
-            for plugin in plugins {
-                sender.input(AppMsg::PluginLoaded(plugin.r#type.to_plugin(&context)));
-            }
-        });
+        if !dmenu && let Some(shortcut) = &model.config.toggle_shortcut {
+            shortcut::register(shortcut);
+        }
+
+        if !dmenu {
+            let sender = sender.clone();
+            remote::listen(move |request| {
+                match request {
+                    remote::Request::Show => sender.input(AppMsg::Show),
+                    remote::Request::Toggle => sender.input(AppMsg::Toggle),
+                    remote::Request::Hide => sender.input(AppMsg::Hide),
+                    remote::Request::Query { query } => {
+                        sender.input(AppMsg::Show);
+                        sender.input(AppMsg::SetQuery(query));
+                    }
+                    remote::Request::Pick { query } => {
+                        let (picking, picked) = std::sync::mpsc::channel();
+                        sender.input(AppMsg::Pick(picking));
+                        sender.input(AppMsg::Show);
+                        if let Some(query) = query {
+                            sender.input(AppMsg::SetQuery(query));
+                        }
+
+                        return match picked.recv() {
+                            Ok(Some(picked)) => remote::Response::picked(picked),
+                            _ => remote::Response::error("Nothing was picked"),
+                        };
+                    }
+                }
+
+                remote::Response::ok()
+            });
+        }
+
+        // Switching the system theme doesn't need a restart
+        if let Some(settings) = gtk::Settings::default() {
+            let _sender = sender.clone();
+            settings.connect_gtk_icon_theme_name_notify(move |_| {
+                _sender.input(AppMsg::ThemeChanged);
+            });
+
+            let _sender = sender.clone();
+            settings.connect_gtk_theme_name_notify(move |_| {
+                _sender.input(AppMsg::ThemeChanged);
+            });
+
+            let _sender = sender.clone();
+            settings.connect_gtk_application_prefer_dark_theme_notify(move |_| {
+                _sender.input(AppMsg::ThemeChanged);
+            });
+        }
+
+        model.load_plugins(&sender);
 
         AsyncComponentParts { model, widgets }
     }
 
+    async fn update_with_view(
+        &mut self,
+        widgets: &mut Self::Widgets,
+        message: Self::Input,
+        sender: AsyncComponentSender<Self>,
+        root: &Self::Root,
+    ) {
+        self.update(message, sender.clone(), root).await;
+
+        // Read by the key handler, which can't see the model
+        *self.shortcuts.borrow_mut() = self
+            .current_entry()
+            .map(|x| x.actions.iter().map(|x| (x.key, x.modifier)).collect())
+            .unwrap_or_default();
+
+        self.update_view(widgets, sender);
+    }
+
     async fn update(
         &mut self,
         message: Self::Input,
@@ -1221,6 +2958,31 @@ impl AsyncComponent for AppModel {
     ) {
         match message {
             AppMsg::Search(query) => {
+                // The text is the note, not something to search
+                if self.annotating.is_some() {
+                    return;
+                }
+
+                if let Some(previous) = self.actions_query.take() {
+                    if self.selected_action.is_some() {
+                        self.action_filter = query;
+                        self.actions_query = Some(previous);
+
+                        if let Some(entry) = self.current_entry() {
+                            let visible = self.visible_actions(&entry.actions);
+                            if let Some(&first) = visible.first() {
+                                self.selected_action = Some(first);
+                            }
+                        }
+                        return;
+                    }
+
+                    // Put back once the actions were closed
+                    if previous == query {
+                        return;
+                    }
+                }
+
                 if self.use_grid() {
                     self.grid_entries
                         .try_send(self.selected_entry, EntryMsg::Unselect);
@@ -1229,15 +2991,26 @@ impl AsyncComponent for AppModel {
 
                 self.selected_entry = 0;
                 self.selected_action = None;
+                self.confirming = None;
                 self.query = query;
+                self.expanded.clear();
 
                 if self.selected_plugin.is_none() && !self.query.is_empty() {
-                    let plugins = self.plugins.read();
+                    let prefixed = self.config.plugins.iter().enumerate().find_map(|(i, x)| {
+                        Some((i, x.strip_prefix(&self.query)?.trim_start().to_owned()))
+                    });
+
+                    if let Some((plugin, rest)) = prefixed {
+                        self.selected_plugin = Some(plugin);
+                        self.search_entry.widget().set_text(&rest);
+                        return;
+                    }
+
                     let plugin = self
                         .config
                         .plugins
                         .iter()
-                        .zip(plugins.iter())
+                        .zip(self.plugins.iter())
                         .enumerate()
                         .find(|(_, (plugin, _))| {
                             plugin.replace
@@ -1254,142 +3027,144 @@ impl AsyncComponent for AppModel {
                     }
                 };
 
-                if let Some(handle) = self.thread_handle.take() {
-                    handle.stop();
-                }
-
-                if !self.query.is_empty() || self.selected_plugin.is_some() {
-                    self.loading = true;
-
-                    let plugins = self.plugins.clone();
-                    let config_plugins = self.config.plugins.clone();
-                    let selected_plugin = self.selected_plugin;
-                    let query = self.query.clone();
-                    let context = self.context.clone();
-                    self.thread_handle = Some(stoppable_thread::spawn(move |stopped| {
-                        let context = context.read();
-
-                        let plugins = plugins.read();
-                        let plugins = config_plugins
-                            .iter()
-                            .zip(plugins.iter())
-                            .enumerate()
-                            .map(|(a, (b, c))| (a, b, c))
-                            .collect_vec();
-
-                        let mut entries = {
-                            match selected_plugin.and_then(|i| plugins.get(i)) {
-                                None => {
-                                    let plugin = plugins.iter().find(|(_, plugin, _)| {
-                                        !plugin.replace
-                                            && plugin
-                                                .regex
-                                                .as_ref()
-                                                .is_some_and(|x| x.is_match(&query))
-                                    });
-
-                                    if let Some((i, _, plugin)) = plugin {
-                                        plugin
-                                            .search(&query, &context)
-                                            .into_iter()
-                                            .map(|x| (*i, x))
-                                            .collect_vec()
-                                    } else {
-                                        plugins
-                                            .iter()
-                                            .filter(|(_, x, _)| !x.default)
-                                            .flat_map(|(i, _, x)| {
-                                                Some((
-                                                    *i,
-                                                    plugin_entry_from_query(
-                                                        *i,
-                                                        x.as_ref(),
-                                                        &query,
-                                                    )?,
-                                                ))
-                                            })
-                                            .chain(
-                                                plugins
-                                                    .iter()
-                                                    .filter(|(_, plugin, _)| {
-                                                        plugin.default && plugin.regex.is_none()
-                                                    })
-                                                    .flat_map(|(i, _, x)| {
-                                                        x.search(&query, &context)
-                                                            .into_iter()
-                                                            .map(move |x| (*i, x))
-                                                    }),
-                                            )
-                                            .collect_vec()
-                                    }
-                                }
-                                Some((i, _, plugin)) => plugin
-                                    .search(&query, &context)
-                                    .into_iter()
-                                    .map(|x| (*i, x))
-                                    .collect_vec(),
-                            }
-                        };
+                // Queries meant for a plugin, like `~/` for files, go to the list right away
+                let filtering_grid = !self.query.is_empty()
+                    && self.query.chars().count() <= self.config.grid_filter
+                    && !self
+                        .config
+                        .plugins
+                        .iter()
+                        .any(|x| x.regex.as_ref().is_some_and(|x| x.is_match(&self.query)));
 
-                        entries.sort_by(|a, b| b.1.score.cmp(&a.1.score));
+                if filtering_grid || self.filtering_grid {
+                    self.filtering_grid = filtering_grid;
+                    self.filter_grid();
+                    self.show_grid_page(0);
+                    self.grid_entries.try_send(0, EntryMsg::Select);
+                }
 
-                        if !stopped.get() {
-                            sender.input(AppMsg::SearchResults(entries));
-                        }
-                    }));
-                } else {
-                    sender.input(AppMsg::SearchResults(vec![]))
+                self.start_search(&sender);
+            }
+            AppMsg::Refresh => {
+                self.close_actions();
+                if !self.use_grid() {
+                    self.restore_selection = Some(self.selected_entry);
                 }
+
+                self.start_search(&sender);
             }
             AppMsg::Activate(index) => {
                 let entry = self.get_entry(index);
 
-                if let Some(action) = entry.as_ref().and_then(|x| x.actions.first()) {
-                    self.execute_action(action, sender);
+                if let Some(entry) = entry
+                    && !entry.actions.is_empty()
+                {
+                    self.execute_action(&entry, 0, sender);
                 }
             }
             AppMsg::Shortcut(key, modifier) => {
                 let entry = self.current_entry();
-                let key = key.to_lower();
+                let key = match key.to_lower() {
+                    Key::KP_Enter => Key::Return,
+                    key => key,
+                };
 
-                if let Some(entry) = entry {
-                    for action in &entry.actions {
-                        if key == action.key && modifier == action.modifier {
-                            self.execute_action(action, sender);
-                            break;
-                        }
-                    }
+                if let Some(entry) = entry
+                    && let Some(index) = entry
+                        .actions
+                        .iter()
+                        .position(|x| key == x.key && modifier == x.modifier)
+                {
+                    self.execute_action(&entry, index, sender);
                 }
             }
             AppMsg::Escape => {
-                if self.selected_action.is_some() {
-                    self.selected_action = None;
+                if let Some((_, query)) = self.annotating.take() {
+                    self.notice = None;
+                    self.search_entry.widget().set_text(&query);
+                } else if self.confirming.is_some() {
+                    self.confirming = None;
+                } else if self.zoomed {
+                    self.zoomed = false;
+                } else if self.selected_action.is_some() {
+                    self.close_actions();
                 } else {
                     sender.input(AppMsg::Hide);
                 }
             }
             AppMsg::Show => {
+                // The launcher may be shown on another monitor than last time
+                let (window_size, grid_size) =
+                    layout_for_monitor(&self.config, current_monitor(root).as_ref());
+                self.window_size = window_size;
+                if self.grid_size != grid_size {
+                    self.grid_size = grid_size;
+                    self.show_grid_page(0);
+                }
+
                 self.visible = true;
+                // It may have been let go while the window was hidden
+                self.held_key.set(None);
+
+                let disabled = self.disabled.lock().clone();
+                for (i, plugin) in self.plugins.iter().enumerate() {
+                    if disabled.contains(&i) {
+                        continue;
+                    }
 
-                for plugin in self.plugins.write().iter_mut() {
-                    plugin.open();
+                    let mut plugin = plugin.write();
+                    let result = std::panic::catch_unwind(AssertUnwindSafe(|| plugin.open()));
+
+                    if let Err(payload) = result {
+                        let message = panic_message(&*payload);
+                        tracing::error!(
+                            plugin = plugin.name(),
+                            panic = %message,
+                            "Plugin panicked"
+                        );
+                        sender.input(AppMsg::PluginFailed(
+                            i,
+                            format!("{} crashed and was disabled: {message}", plugin.name()),
+                        ));
+                    }
                 }
 
                 self.grid_entries.try_send(0, EntryMsg::Select);
             }
             AppMsg::Hide => {
+                if self.dmenu {
+                    std::process::exit(1);
+                }
+
                 self.visible = false;
                 self.selected_action = None;
+                self.actions_query = None;
+                self.action_filter.clear();
+                self.zoomed = false;
+                if let Some(picking) = self.picking.take() {
+                    let _ = picking.send(None);
+                }
+                // Otherwise clearing the text would be taken as the note
+                if self.annotating.take().is_some() {
+                    self.notice = None;
+                }
                 self.search_entry.widget().set_text("");
-                self.thread_handle = None;
+                self.search_token.cancel();
                 self.selected_plugin = None;
                 self.selected_entry = 0;
                 self.grid_entries.broadcast(EntryMsg::Unselect);
+                if self.grid_page != 0 {
+                    self.show_grid_page(0);
+                }
+                self.show_badges = false;
+                self.update_badges();
+                self.marked.clear();
+                self.confirming = None;
                 self.locked = false;
             }
             AppMsg::MaybeHide => {
                 if self.locked {
-                    self.selected_action = None;
+                    self.close_actions();
                 } else {
                     sender.input(AppMsg::Hide);
                 }
@@ -1403,40 +3178,43 @@ impl AsyncComponent for AppModel {
                 if let Some(entry) = self.current_entry()
                     && entry.actions.len() > 1
                 {
-                    self.selected_action = match self.selected_action {
-                        Some(_) => None,
-                        None => Some(0),
+                    if self.selected_action.is_some() {
+                        self.close_actions();
+                    } else {
+                        self.selected_action = Some(0);
+                        self.actions_query = Some(self.query.clone());
+                        self.search_entry.widget().set_text("");
                     }
                 }
             }
+            AppMsg::Pop => {
+                if self.selected_action.is_some() {
+                    self.close_actions();
+                } else {
+                    sender.input(AppMsg::SetPlugin(None));
+                }
+            }
             AppMsg::Reload => {
+                if self.dmenu {
+                    return;
+                }
+
                 let base_dirs = BaseDirectories::with_prefix("jogger").unwrap();
 
-                let config = base_dirs.place_config_file("config.toml").unwrap();
-                let config: AppConfig = if std::fs::exists(&config).unwrap_or(false) {
-                    let content = std::fs::read_to_string(&config).unwrap();
-                    toml::from_str(&content).unwrap()
-                } else {
-                    Default::default()
-                };
+                // The config in use is kept until the new one can be read
+                let (config, error) = load_config(&base_dirs);
+                if error.is_some() {
+                    self.config_error = error;
+                    return;
+                }
 
                 self.config = config;
+                self.config_error = None;
 
-                self.context = Arc::new(RwLock::new(Context::default()));
+                self.context = Arc::new(RwLock::new(Arc::new(create_context(&self.config))));
 
-                self.plugins.write().clear();
-
-                {
-                    let sender = sender.clone();
-                    let plugins = self.config.plugins.clone();
-                    let context = self.context.clone();
-                    tokio::spawn(async move {
-                        let context = context.read();
-                        for plugin in plugins {
-                            sender.input(AppMsg::PluginLoaded(plugin.r#type.to_plugin(&context)));
-                        }
-                    });
-                }
+                self.disabled.lock().clear();
+                self.load_plugins(&sender);
 
                 sender.input(AppMsg::ScrollToStart);
 
@@ -1444,11 +3222,16 @@ impl AsyncComponent for AppModel {
             }
             AppMsg::Move(direction) => {
                 if let Some(action) = self.selected_action {
-                    let action = action as isize;
                     let Some(entry) = self.current_entry() else {
                         return;
                     };
-                    let count = entry.actions.len() as isize;
+                    let visible = self.visible_actions(&entry.actions);
+                    if visible.is_empty() {
+                        return;
+                    }
+
+                    let action = visible.iter().position(|&x| x == action).unwrap_or(0) as isize;
+                    let count = visible.len() as isize;
                     let action = match direction {
                         MoveDirection::Back | MoveDirection::Up => action - 1,
                         MoveDirection::Forward | MoveDirection::Down => action + 1,
@@ -1457,7 +3240,7 @@ impl AsyncComponent for AppModel {
                         _ => action,
                     };
 
-                    self.selected_action = Some(action.rem_euclid(count) as usize);
+                    self.selected_action = Some(visible[action.rem_euclid(count) as usize]);
                     return;
                 }
 
@@ -1470,6 +3253,25 @@ impl AsyncComponent for AppModel {
                     return;
                 }
 
+                if use_grid && matches!(direction, MoveDirection::PageUp | MoveDirection::PageDown)
+                {
+                    let pages = self.grid_pages();
+                    let page = match direction {
+                        MoveDirection::PageUp => (self.grid_page + pages - 1) % pages,
+                        _ => (self.grid_page + 1) % pages,
+                    };
+
+                    if page != self.grid_page {
+                        self.show_grid_page(page);
+
+                        let index = self.selected_entry.min(self.grid_entries.len() - 1);
+                        self.grid_entries.try_send(index, EntryMsg::Select);
+                        self.selected_entry = index;
+                    }
+
+                    return;
+                }
+
                 let move_grid = |f: fn(i32, i32) -> i32| -> usize {
                     f(self.selected_entry as i32, self.grid_size as i32) as usize
                 };
@@ -1497,6 +3299,17 @@ impl AsyncComponent for AppModel {
                 let move_grid_down = || move_grid(|i, size| (i + size).rem_euclid(size * size));
                 let move_list_back = || move_list(|i, size| (i - 1).rem_euclid(size));
                 let move_list_forward = || move_list(|i, size| (i + 1).rem_euclid(size));
+                // Rows fitting in the view, estimated from the scrollable height
+                let list_page = || {
+                    let rows = self.list_entries_wrapper.len() as f64;
+                    self.list_entries_wrapper
+                        .view
+                        .vadjustment()
+                        .filter(|x| x.upper() > 0.0)
+                        .map(|x| (x.page_size() * rows / x.upper()) as usize)
+                        .unwrap_or(1)
+                        .max(1)
+                };
 
                 let new = match direction {
                     MoveDirection::Back => {
@@ -1555,7 +3368,11 @@ impl AsyncComponent for AppModel {
                             self.selected_entry
                         }
                     }
-                    _ => self.selected_entry,
+                    MoveDirection::PageUp => self.selected_entry.saturating_sub(list_page()),
+                    MoveDirection::PageDown => {
+                        let last = self.list_entries_wrapper.len() as usize - 1;
+                        (self.selected_entry + list_page()).min(last)
+                    }
                 };
 
                 if new != self.selected_entry {
@@ -1569,7 +3386,7 @@ impl AsyncComponent for AppModel {
                     self.grid_entries.try_send(index, EntryMsg::Select);
 
                     self.grid_entries.get(index).and_then(|entry| {
-                        self.plugins.read().get(entry.plugin)?.select(&entry.entry);
+                        self.plugins.get(entry.plugin)?.read().select(&entry.entry);
                         Some(())
                     });
                 } else {
@@ -1581,14 +3398,19 @@ impl AsyncComponent for AppModel {
                         .get(index as u32)
                         .and_then(|entry| {
                             let entry = entry.borrow();
-                            self.plugins.read().get(entry.plugin)?.select(&entry.entry);
+                            self.plugins.get(entry.plugin)?.read().select(&entry.entry);
                             Some(())
                         });
 
                     sender.input(AppMsg::ScrollToSelected);
                 }
 
+                if index != self.selected_entry {
+                    self.confirming = None;
+                }
+
                 self.selected_entry = index;
+                self.update_preview(&sender);
             }
             AppMsg::GestureStart(index, secondary) => {
                 sender.input(AppMsg::SelectEntry(index));
@@ -1610,18 +3432,83 @@ impl AsyncComponent for AppModel {
                 self.pressing_entry = false;
             }
             AppMsg::ActivateSelected => {
-                if let Some(action) = self.selected_action {
-                    sender.input(AppMsg::ActivateSelectedWithAction(action));
+                if let Some((id, query)) = self.annotating.take() {
+                    let note = self.search_entry.widget().text();
+                    self.context_mut().notes.set(&id, &note);
+                    self.notice = None;
+                    self.search_entry.widget().set_text(&query);
+                } else if let Some((_, function)) = self.confirming.take() {
+                    let result = (function.lock())(&mut self.context_mut());
+                    self.handle_result(result, sender);
+                } else if let Some(action) = self.selected_action {
+                    // Nothing is shown when no action matches
+                    let visible = self
+                        .current_entry()
+                        .is_some_and(|x| self.visible_actions(&x.actions).contains(&action));
+                    if visible {
+                        sender.input(AppMsg::ActivateSelectedWithAction(action));
+                    }
                 } else {
                     sender.input(AppMsg::Activate(self.selected_entry));
                 }
             }
+            AppMsg::ActivateAll(false) => {
+                let count = self
+                    .all_results
+                    .iter()
+                    .filter(|(_, x)| !x.actions.is_empty())
+                    .count();
+
+                if self.use_grid() || count == 0 {
+                    return;
+                }
+
+                let function: Box<ActionType> = Box::new(move |_| {
+                    sender.input(AppMsg::ActivateAll(true));
+                    // The actions are run by the launcher, so there's nothing left to do
+                    ActionResult::Error
+                });
+
+                self.close_actions();
+                self.confirming = Some((
+                    format!("Run the default action on all {count} results?"),
+                    Arc::new(Mutex::new(function)),
+                ));
+            }
+            AppMsg::ActivateAll(true) => {
+                let actions = self
+                    .all_results
+                    .iter()
+                    .filter(|(_, x)| !x.actions.is_empty())
+                    .map(|(_, x)| (x.clone(), 0))
+                    .collect();
+                self.execute_hooked(actions, true, false, sender);
+            }
             AppMsg::ActivateSelectedWithAction(action) => {
                 let entry = self.current_entry();
-                let action = entry.as_ref().map(|x| &x.actions[action]);
 
-                if let Some(action) = action {
-                    self.execute_action(action, sender);
+                if let Some(entry) = entry
+                    && action < entry.actions.len()
+                {
+                    self.execute_action(&entry, action, sender);
+                }
+            }
+            AppMsg::HooksConfirmed(id) => {
+                if let Some((_, actions, batch)) = self.hooked.take_if(|(x, ..)| *x == id) {
+                    self.execute_hooked(actions, batch, true, sender);
+                }
+            }
+            AppMsg::HooksFinished(id, passed) => {
+                if let Some((_, actions, batch)) = self.hooked.take_if(|(x, ..)| *x == id) {
+                    let actions = actions
+                        .into_iter()
+                        .zip(passed)
+                        .filter_map(|(x, passed)| passed.then_some(x))
+                        .collect_vec();
+
+                    if !actions.is_empty() {
+                        self.run_actions(actions, batch, sender);
+                    }
                 }
             }
             AppMsg::ScrollToSelected => {
@@ -1638,52 +3525,171 @@ impl AsyncComponent for AppModel {
                         .scroll_to(0, ListScrollFlags::empty(), None);
                 }
             }
-            AppMsg::SearchResults(entries) => {
+            AppMsg::SearchResults(generation, entries) => {
+                if generation != self.search_generation {
+                    return;
+                }
+
+                let first = self.results_generation != generation;
+
+                if first {
+                    self.all_results.clear();
+                    self.results_generation = generation;
+                }
+
+                let context = self.context.read().clone();
+                let mut entries = entries
+                    .into_iter()
+                    .map(|(plugin, mut entry)| {
+                        if let Some(config) = self.config.plugins.get(plugin) {
+                            entry.score = (entry.score as f64 * config.weight.max(0.0)) as u64;
+                        }
+
+                        if let Some(note) = context.notes.get(&entry.id) {
+                            entry.description = Some(FormattedString::plain(note));
+                        }
+
+                        (plugin, Rc::new(entry))
+                    })
+                    .collect_vec();
+
+                // Merges the results of all plugins, ties keep the configured plugin order
+                let order = |a: &(usize, Rc<Entry>), b: &(usize, Rc<Entry>)| {
+                    b.1.score.cmp(&a.1.score).then(a.0.cmp(&b.0))
+                };
+                entries.sort_by(order);
+                self.all_results = std::mem::take(&mut self.all_results)
+                    .into_iter()
+                    .merge_by(entries, |a, b| order(a, b).is_le())
+                    .collect();
+
+                // Plugins answer in bursts, the list is built once for all of them
+                if self.flushing.is_none() {
+                    tokio::spawn(async move {
+                        tokio::time::sleep(FLUSH_DELAY).await;
+                        sender.input(AppMsg::FlushResults);
+                    });
+                }
+                self.flushing = Some(self.flushing.unwrap_or(false) || first);
+            }
+            AppMsg::FlushResults => {
+                let Some(first) = self.flushing.take() else {
+                    return;
+                };
+
+                let selected = self.current_entry();
+                self.results = self.limit_results();
+
+                self.rebuild_list(&sender);
+                self.update_preview(&sender);
+
+                if first {
+                    match self.restore_selection.take() {
+                        Some(index) if !self.list_entries_wrapper.is_empty() => {
+                            let last = self.list_entries_wrapper.len() as usize - 1;
+                            sender.input(AppMsg::SelectEntry(index.min(last)));
+                        }
+                        _ => sender.input(AppMsg::ScrollToStart),
+                    }
+                } else if self.selected_entry != 0
+                    && let Some(index) = selected.and_then(|selected| {
+                        self.results
+                            .iter()
+                            .position(|(_, x)| Rc::ptr_eq(x, &selected))
+                    })
+                {
+                    // Keeps the entry picked by the user selected while more results come in
+                    sender.input(AppMsg::SelectEntry(index));
+                }
+            }
+            AppMsg::SearchFinished(generation) => {
+                if generation != self.search_generation {
+                    return;
+                }
+
                 self.loading = false;
 
-                self.list_entries_wrapper.clear();
-                self.list_entries_wrapper
-                    .extend_from_iter(entries.into_iter().enumerate().map(
-                        |(index, (plugin, entry))| TypedListEntry {
-                            plugin,
-                            index,
-                            entry: Rc::new(entry),
-                            color: self.config.highlight_color,
-                            sender: sender.clone(),
-                        },
-                    ));
+                // No plugin returned anything, the previous results are stale
+                if self.results_generation != generation {
+                    sender.input(AppMsg::SearchResults(generation, Vec::new()));
+                }
 
-                sender.input(AppMsg::ScrollToStart);
+                let interval = self
+                    .all_results
+                    .iter()
+                    .map(|(i, _)| *i)
+                    .unique()
+                    .filter_map(|i| self.plugins.get(i)?.read().refresh_interval())
+                    .min();
+
+                if let Some(interval) = interval {
+                    tokio::spawn(async move {
+                        tokio::time::sleep(interval).await;
+                        sender.input(AppMsg::AutoRefresh(generation));
+                    });
+                }
             }
-            AppMsg::PluginLoaded(plugin) => {
-                self.plugins.write().push(plugin);
-                let plugins = self.plugins.read();
-                if plugins.len() == self.config.plugins.len() {
-                    let plugins = self.plugins.read();
-                    let entries = self
+            AppMsg::PluginLoaded(generation, i, plugin, time) => {
+                // Left from before a reload
+                if generation != self.load_generation {
+                    return;
+                }
+                let Some(config) = self.config.plugins.get(i) else {
+                    return;
+                };
+
+                self.profile.record_load(&config.key(), time);
+                self.plugins[i] = shared(plugin);
+                self.pending_plugins = self.pending_plugins.saturating_sub(1);
+
+                if self.pending_plugins == 0 {
+                    self.profile.save();
+
+                    let disabled = self.disabled.lock().clone();
+                    self.grid_results = self
                         .config
                         .plugins
                         .iter()
-                        .zip(plugins.iter())
+                        .zip(self.plugins.iter())
                         .enumerate()
-                        .filter(|(_, (plugin, _))| plugin.default && plugin.regex.is_none())
+                        .filter(|(i, (plugin, _))| {
+                            plugin.default && plugin.regex.is_none() && !disabled.contains(i)
+                        })
                         .flat_map(|(i, (_, x))| {
-                            x.search("", &self.context.read())
-                                .into_iter()
-                                .map(move |x| (i, Rc::new(x)))
+                            let x = x.read();
+                            let context = self.context.read();
+                            let entries = std::panic::catch_unwind(AssertUnwindSafe(|| {
+                                x.search("", &context, &CancellationToken::new())
+                            }))
+                            .unwrap_or_else(|payload| {
+                                let message = panic_message(&*payload);
+                                tracing::error!(
+                                    plugin = x.name(),
+                                    panic = %message,
+                                    "Plugin panicked"
+                                );
+                                sender.input(AppMsg::PluginFailed(
+                                    i,
+                                    format!("{} crashed and was disabled: {message}", x.name()),
+                                ));
+                                Vec::new()
+                            });
+
+                            entries.into_iter().map(move |x| (i, Rc::new(x)))
                         })
-                        .take(self.grid_size * self.grid_size);
+                        .collect();
 
-                    let mut grid_entries = self.grid_entries.guard();
-                    grid_entries.clear();
-                    for entry in entries {
-                        grid_entries.push_back((entry.0, entry.1, self.grid_size));
-                    }
+                    self.filter_grid();
+                    self.show_grid_page(0);
 
                     sender.input(AppMsg::Search(self.query.clone()));
                 }
             }
             AppMsg::SetPlugin(plugin) => {
+                if self.dmenu {
+                    return;
+                }
+
                 self.selected_plugin = plugin;
 
                 if plugin.is_some() {
@@ -1717,12 +3723,195 @@ impl AsyncComponent for AppModel {
             AppMsg::ToggleLock => {
                 self.locked = !self.locked;
             }
+            AppMsg::ActivateVisible(n) => {
+                let index = if self.use_grid() {
+                    n
+                } else {
+                    self.first_visible_row() + n
+                };
+
+                if self.get_entry(index).is_some() {
+                    sender.input(AppMsg::SelectEntry(index));
+                    sender.input(AppMsg::Activate(index));
+                }
+            }
+            AppMsg::ToggleMark => {
+                if self.use_grid() {
+                    return;
+                }
+
+                let Some((plugin, entry)) = self.results.get(self.selected_entry).cloned() else {
+                    return;
+                };
+
+                if self.is_marked(&entry) {
+                    self.marked.retain(|(_, x)| {
+                        !(Rc::ptr_eq(x, &entry) || !entry.id.is_empty() && x.id == entry.id)
+                    });
+                } else {
+                    self.marked.push((plugin, entry));
+                }
+
+                // Entries with the same id are marked together
+                for i in 0..self.list_entries_wrapper.len() {
+                    let Some(item) = self.list_entries_wrapper.get(i) else {
+                        continue;
+                    };
+
+                    let mut item = item.borrow_mut();
+                    let marked = self.is_marked(&item.entry);
+                    if item.marked != marked {
+                        item.marked = marked;
+                        if let Some(row) = &item.row {
+                            row.set_class_active("marked", marked);
+                        }
+                    }
+                }
+            }
+            AppMsg::ShowBadges(show) => {
+                if self.show_badges != show {
+                    self.show_badges = show;
+                    self.update_badges();
+                }
+            }
+            AppMsg::PluginFailed(plugin, message) => {
+                self.disabled.lock().insert(plugin);
+                self.notice = Some(message.clone());
+
+                if self.grid_results.iter().any(|(i, _)| *i == plugin) {
+                    self.grid_results.retain(|(i, _)| *i != plugin);
+                    self.filter_grid();
+                    self.show_grid_page(0);
+                }
+
+                tokio::spawn(async move {
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                    sender.input(AppMsg::ClearNotice(message));
+                });
+            }
+            AppMsg::AutoRefresh(generation) => {
+                // Anything the user did since then started a new search
+                if generation == self.search_generation
+                    && self.visible
+                    && self.selected_action.is_none()
+                    && self.confirming.is_none()
+                {
+                    sender.input(AppMsg::Refresh);
+                }
+            }
+            AppMsg::EditNote => {
+                let Some(entry) = self.current_entry() else {
+                    return;
+                };
+
+                // Notes are stored by id, so they can't stick to entries without one
+                if entry.id.is_empty() || self.annotating.is_some() {
+                    return;
+                }
+
+                let note = self
+                    .context
+                    .read()
+                    .notes
+                    .get(&entry.id)
+                    .unwrap_or_default()
+                    .to_owned();
+
+                self.notice = Some(format!(
+                    "Note for {}, Enter to save, Escape to cancel",
+                    entry.name.text
+                ));
+                self.annotating = Some((entry.id.clone(), self.query.clone()));
+                self.search_entry.widget().set_text(&note);
+            }
+            AppMsg::SetQuery(query) => {
+                self.search_entry.emit(query);
+            }
+            AppMsg::ThemeChanged => {
+                self.context_mut().icons = Arc::new(theme_icons());
+
+                let base_dirs = BaseDirectories::with_prefix("jogger").unwrap();
+                load_css(&base_dirs, &self.config, &self.css_provider);
+
+                // Rows and cells are built again, looking their icons up in the new theme
+                self.show_grid_page(self.grid_page);
+                self.rebuild_list(&sender);
+                self.update_preview(&sender);
+            }
+            AppMsg::ShowMore(plugin) => {
+                self.expanded.insert(plugin);
+                self.results = self.limit_results();
+
+                // The selection stays where the entry was
+                let index = self.selected_entry;
+                self.rebuild_list(&sender);
+                self.update_preview(&sender);
+                sender.input(AppMsg::SelectEntry(index));
+            }
+            AppMsg::Pick(picking) => {
+                // Only one client picks at a time
+                if let Some(previous) = self.picking.replace(picking) {
+                    let _ = previous.send(None);
+                }
+            }
+            AppMsg::ToggleZoom => {
+                self.zoomed = !self.zoomed && self.current_entry().is_some();
+            }
+            AppMsg::Undo => {
+                if let Some((_, function)) = self.undo.take() {
+                    let result = (function.lock())(&mut self.context_mut());
+                    self.handle_result(result, sender);
+                } else if let Some(text) = self.search_entry.widget().delegate() {
+                    // Otherwise it's still the undo of the text
+                    let _ = text.activate_action("text.undo", None);
+                }
+            }
+            AppMsg::EditConfig => {
+                if let Some(error) = self.config_error.take() {
+                    let result =
+                        (EntryAction::edit(error.path, error.line))(&mut self.context_mut());
+                    self.handle_result(result, sender);
+                }
+            }
+            AppMsg::ClearUndo(message) => {
+                if self.undo.as_ref().is_some_and(|(x, _)| *x == message) {
+                    self.undo = None;
+                }
+            }
+            AppMsg::ShowPreview(generation) => {
+                if generation == self.preview_generation {
+                    self.show_preview();
+                }
+            }
+            AppMsg::ClearNotice(message) => {
+                // A newer notice stays up for its own duration
+                if self.notice.as_ref() == Some(&message) {
+                    self.notice = None;
+                }
+            }
         }
     }
 }
 
 fn main() {
-    if std::env::args().contains(&"--show".to_string()) {
+    let verbose = std::env::args().any(|x| x == "--verbose" || x == "-v");
+    let _guard = logging::init(verbose);
+
+    // The path given after the flag, if any
+    let argument = |flag: &str| {
+        let mut args = std::env::args().skip_while(|x| x != flag).skip(1);
+        args.next().filter(|x| !x.starts_with('-'))
+    };
+
+    if std::env::args().contains(&"--export".to_string()) {
+        if !state::export(argument("--export").as_deref()) {
+            std::process::exit(1);
+        }
+    } else if std::env::args().contains(&"--import".to_string()) {
+        if !state::import(argument("--import").as_deref()) {
+            std::process::exit(1);
+        }
+    } else if std::env::args().contains(&"--show".to_string()) {
         let conn = dbus::blocking::Connection::new_session().unwrap();
 
         let proxy = conn.with_proxy(
@@ -1745,10 +3934,12 @@ fn main() {
 
         match proxy.method_call::<(bool,), _, _, _>("com.psyvern.jogger", "ToggleWindow", ()) {
             Ok((_status,)) => {}
-            Err(_) => start(),
+            Err(_) => start(false),
         };
+    } else if std::env::args().contains(&"--dmenu".to_string()) {
+        start(true);
     } else {
-        start();
+        start(false);
     }
 }
 
@@ -1774,6 +3965,105 @@ fn load_css(base: &BaseDirectories, config: &AppConfig, provider: &CssProvider)
     provider.load_from_string(&style);
 }
 
+/// Why the config couldn't be read, shown until it's fixed with an action to open it.
+#[derive(Debug)]
+struct ConfigError {
+    message: String,
+    path: PathBuf,
+    line: Option<usize>,
+}
+
+fn read_config(path: &Path) -> Result<AppConfig, ConfigError> {
+    if !std::fs::exists(path).unwrap_or(false) {
+        return Ok(Default::default());
+    }
+
+    let error = |message: String, line| ConfigError {
+        message,
+        path: path.to_owned(),
+        line,
+    };
+
+    let content = std::fs::read_to_string(path).map_err(|x| error(x.to_string(), None))?;
+    let mut config: AppConfig = toml::from_str(&content).map_err(|x| {
+        let line = x
+            .span()
+            .map(|span| content[..span.start].matches('\n').count() + 1);
+        error(x.message().to_owned(), line)
+    })?;
+
+    if config.plugins.is_empty() {
+        config.plugins = default_plugins(&config.files_prefix);
+    }
+
+    Ok(config)
+}
+
+/// The defaults are used when the config can't be read, along with why.
+fn load_config(base_dirs: &BaseDirectories) -> (AppConfig, Option<ConfigError>) {
+    let path = base_dirs.place_config_file("config.toml").unwrap();
+    let (mut config, error) = match read_config(&path) {
+        Ok(config) => (config, None),
+        Err(error) => {
+            tracing::error!(?error, "Failed to read the config");
+            (AppConfig::default(), Some(error))
+        }
+    };
+
+    // Libraries dropped in the plugins directory are loaded without being configured
+    for path in plugins::native::discover() {
+        let configured = config.plugins.iter().any(|x| {
+            matches!(x.r#type, PluginType::Native)
+                && x.options
+                    .get("path")
+                    .and_then(|x| x.as_str())
+                    .and_then(|x| expanduser::expanduser(x).ok())
+                    .is_some_and(|x| x == path)
+        });
+
+        if !configured {
+            let mut options = toml::Table::new();
+            options.insert("path".into(), path.to_string_lossy().into_owned().into());
+
+            config.plugins.push(PluginConfig {
+                r#type: PluginType::Native,
+                default: true,
+                regex: None,
+                replace: false,
+                prefix: None,
+                enabled: true,
+                when: Condition::default(),
+                priority: 0,
+                weight: 1.0,
+                max_results: None,
+                options,
+            });
+        }
+    }
+
+    // Everything else refers to plugins by their index in this list
+    config.plugins.retain(|x| x.enabled && x.when.holds());
+    config
+        .plugins
+        .sort_by_key(|x| std::cmp::Reverse(x.priority));
+
+    (config, error)
+}
+
+fn create_context(config: &AppConfig) -> Context {
+    let mut context = Context::default();
+    context.terminal_profiles = config.terminal_profiles.clone();
+    context.frecency = frecency::Frecency::load();
+    context.notes = notes::Notes::load();
+    context.favorites = favorites::Favorites::load();
+    context.editor = config.editor.clone();
+    context.secret_timeout = Duration::from_secs(config.secret_timeout);
+    context.confirm_commands = config.confirm_commands;
+    context.focus_or_launch = config.focus_or_launch;
+
+    context
+}
+
 fn plugin_entry_from_query(index: usize, x: &dyn Plugin, query: &str) -> Option<Entry> {
     let matcher = fuzzy_matcher::skim::SkimMatcherV2::default().ignore_case();
 
@@ -1807,18 +4097,40 @@ fn plugin_entry_from_query(index: usize, x: &dyn Plugin, query: &str) -> Option<
     })
 }
 
-fn start() {
-    let app = RelmApp::new("com.psyvern.jogger").with_args(Vec::new());
+fn start(dmenu: bool) {
+    let app = if dmenu {
+        // The dmenu picker is a short-lived process, so it must not be
+        // forwarded to an already running daemon.
+        RelmApp::from_app(
+            gtk::Application::builder()
+                .application_id("com.psyvern.jogger.dmenu")
+                .flags(gtk::gio::ApplicationFlags::NON_UNIQUE)
+                .build(),
+        )
+    } else {
+        RelmApp::new("com.psyvern.jogger")
+    }
+    .with_args(Vec::new());
 
     let base_dirs = BaseDirectories::with_prefix("jogger").unwrap();
 
-    let config = base_dirs.place_config_file("config.toml").unwrap();
-    let config: AppConfig = if std::fs::exists(&config).unwrap_or(false) {
-        let content = std::fs::read_to_string(&config).unwrap();
-        toml::from_str(&content).unwrap()
-    } else {
-        Default::default()
-    };
+    let (mut config, config_error) = load_config(&base_dirs);
+
+    if dmenu {
+        config.plugins = vec![PluginConfig {
+            r#type: PluginType::Dmenu,
+            default: true,
+            regex: None,
+            replace: false,
+            prefix: None,
+            enabled: true,
+            when: Condition::default(),
+            priority: 0,
+            weight: 1.0,
+            max_results: None,
+            options: Default::default(),
+        }];
+    }
 
     let provider = gtk::CssProvider::new();
     load_css(&base_dirs, &config, &provider);
@@ -1828,6 +4140,6 @@ fn start() {
         gtk::STYLE_PROVIDER_PRIORITY_USER,
     );
 
-    app.run_async::<AppModel>((config, provider));
+    app.run_async::<AppModel>((config, provider, dmenu, config_error));
     // app.run::<AppModel>(plugins);
 }
@@ -0,0 +1,63 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use xdg::BaseDirectories;
+
+/// Notes written by the user for entries, by their `id`, shown in place of their description.
+#[derive(Clone, Debug, Default)]
+pub struct Notes {
+    path: Option<PathBuf>,
+    notes: HashMap<String, String>,
+}
+
+impl Notes {
+    pub fn load() -> Self {
+        let path = BaseDirectories::with_prefix("jogger")
+            .ok()
+            .and_then(|x| x.place_data_file("notes.json").ok());
+
+        let notes = path
+            .as_ref()
+            .and_then(|x| std::fs::read_to_string(x).ok())
+            .and_then(|x| serde_json::from_str(&x).ok())
+            .unwrap_or_default();
+
+        Self { path, notes }
+    }
+
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+
+        match serde_json::to_string_pretty(&self.notes) {
+            Ok(content) => {
+                if let Err(error) = std::fs::write(path, content) {
+                    tracing::error!(?error, "Failed to save notes");
+                }
+            }
+            Err(error) => tracing::error!(?error, "Failed to serialize notes"),
+        }
+    }
+
+    pub fn get(&self, id: &str) -> Option<&str> {
+        if id.is_empty() {
+            return None;
+        }
+
+        self.notes.get(id).map(String::as_str)
+    }
+
+    /// Replaces the note of the entry, an empty one removes it.
+    pub fn set(&mut self, id: &str, note: &str) {
+        let note = note.trim();
+
+        if note.is_empty() {
+            self.notes.remove(id);
+        } else {
+            self.notes.insert(id.to_owned(), note.to_owned());
+        }
+
+        self.save();
+    }
+}
@@ -0,0 +1,189 @@
+use std::process::Command;
+use std::sync::Arc;
+
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use gtk::gdk::{Key, ModifierType};
+use parking_lot::Mutex;
+use tokio_util::sync::CancellationToken;
+
+use crate::interface::{
+    Context, Entry, EntryAction, EntryIcon, FormatStyle, FormattedString, Plugin,
+};
+
+/// Printed between the aliases and the functions, to tell them apart.
+const SEPARATOR: &str = "--jogger--";
+
+#[derive(Debug, Clone)]
+struct Alias {
+    name: String,
+    /// What the alias expands to, none for functions.
+    expansion: Option<String>,
+}
+
+/// Undoes the single quotes the shells print values with, like `'it'\''s'`.
+fn unquote(value: &str) -> String {
+    let mut result = String::new();
+    let mut quoted = false;
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => quoted = !quoted,
+            '\\' if !quoted => result.extend(chars.next()),
+            c => result.push(c),
+        }
+    }
+
+    result
+}
+
+/// Starts the shell interactively, like a terminal would, so its config is read.
+fn load(shell: &str) -> Vec<Alias> {
+    let kind = shell.rsplit('/').next().unwrap_or(shell);
+    let script = match kind {
+        "zsh" => format!("alias; echo {SEPARATOR}; print -rl -- ${{(k)functions}}"),
+        "bash" => format!("alias; echo {SEPARATOR}; compgen -A function"),
+        "fish" => format!("alias; echo {SEPARATOR}; functions --names"),
+        _ => {
+            tracing::debug!(shell, "Unsupported shell for aliases");
+            return Vec::new();
+        }
+    };
+
+    let output = match Command::new(shell).args(["-ic", &script]).output() {
+        Ok(x) => String::from_utf8_lossy(&x.stdout).into_owned(),
+        Err(error) => {
+            tracing::warn!(?error, shell, "Failed to start the shell");
+            return Vec::new();
+        }
+    };
+
+    // Anything printed by the config comes before
+    let (aliases, functions) = output.split_once(SEPARATOR).unwrap_or((&output, ""));
+
+    let aliases = aliases.lines().filter_map(|line| {
+        // `alias ll='ls -la'` in bash, `ll='ls -la'` in zsh, `alias ll 'ls -la'` in fish
+        let line = line.strip_prefix("alias ").unwrap_or(line);
+        let (name, expansion) = if kind == "fish" {
+            line.split_once(' ')?
+        } else {
+            line.split_once('=')?
+        };
+
+        Some(Alias {
+            name: name.to_owned(),
+            expansion: Some(unquote(expansion)),
+        })
+    });
+
+    // Those starting with `_` are for completions, or internal to plugins
+    let functions = functions
+        .split([',', '\n'])
+        .map(str::trim)
+        .filter(|x| !x.is_empty() && !x.starts_with('_'))
+        .map(|x| Alias {
+            name: x.to_owned(),
+            expansion: None,
+        });
+
+    aliases.chain(functions).collect()
+}
+
+/// The aliases and functions defined in the config of the shell, to run in a terminal.
+#[derive(Debug)]
+pub struct Aliases {
+    shell: String,
+    aliases: Arc<Mutex<Vec<Alias>>>,
+}
+
+impl Aliases {
+    pub fn new(_: &Context) -> Self {
+        let shell = std::env::var("SHELL").unwrap_or_else(|_| "bash".into());
+        let aliases = Arc::new(Mutex::new(Vec::new()));
+
+        // Only once, since the config can take a while to run
+        {
+            let shell = shell.clone();
+            let aliases = aliases.clone();
+            std::thread::spawn(move || {
+                let loaded = load(&shell);
+                *aliases.lock() = loaded;
+            });
+        }
+
+        Self { shell, aliases }
+    }
+
+    fn alias_to_entry(&self, alias: &Alias, name: FormattedString, score: u64) -> Entry {
+        Entry {
+            name,
+            tag: Some(FormattedString::plain(if alias.expansion.is_some() {
+                "Alias"
+            } else {
+                "Function"
+            })),
+            description: alias
+                .expansion
+                .as_ref()
+                .map(|x| FormattedString::from_style(x, FormatStyle::Monospace)),
+            icon: EntryIcon::Name("terminal".into()),
+            actions: vec![
+                // The shell is kept open after, to see the output
+                EntryAction {
+                    icon: "terminal".into(),
+                    name: "Run in terminal".into(),
+                    function: EntryAction::launch_terminal(
+                        Some(self.shell.clone()),
+                        vec!["-ic".into(), format!("{}; exec {}", alias.name, self.shell)],
+                        None,
+                    ),
+                    ..Default::default()
+                },
+                EntryAction {
+                    icon: "edit-copy".into(),
+                    name: "Copy".into(),
+                    function: EntryAction::copy(alias.expansion.as_ref().unwrap_or(&alias.name)),
+                    key: Key::c,
+                    modifier: ModifierType::CONTROL_MASK,
+                },
+            ],
+            id: format!("alias:{}", alias.name),
+            score,
+            ..Default::default()
+        }
+    }
+}
+
+impl Plugin for Aliases {
+    fn name(&self) -> &str {
+        "Aliases"
+    }
+
+    fn icon(&self) -> Option<&str> {
+        Some("terminal")
+    }
+
+    fn search(&self, query: &str, _: &Context, _: &CancellationToken) -> Vec<Entry> {
+        let query = query
+            .strip_prefix("alias")
+            .filter(|x| x.is_empty() || x.starts_with(char::is_whitespace))
+            .unwrap_or(query)
+            .trim();
+
+        let aliases = self.aliases.lock();
+        let matcher = SkimMatcherV2::default().smart_case();
+        aliases
+            .iter()
+            .filter_map(|x| {
+                if query.is_empty() {
+                    return Some(self.alias_to_entry(x, FormattedString::plain(&x.name), 0));
+                }
+
+                let (score, indices) = matcher.fuzzy_indices(&x.name, query)?;
+                let name = FormattedString::from_indices(&x.name, indices);
+                Some(self.alias_to_entry(x, name, score.try_into().ok()?))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,303 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Arc;
+
+use freedesktop_desktop_entry::get_languages_from_env;
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use gtk::gdk::{Key, ModifierType};
+use parking_lot::Mutex;
+use serde::Deserialize;
+use tokio_util::sync::CancellationToken;
+use xdg::BaseDirectories;
+
+use crate::interface::{
+    ActionResult, ActionType, Context, Entry, EntryAction, EntryIcon, FormattedString, Plugin,
+    parse_options,
+};
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+struct AppImagesConfig {
+    directories: Vec<String>,
+}
+
+impl Default for AppImagesConfig {
+    fn default() -> Self {
+        Self {
+            directories: vec![
+                "~/Applications".into(),
+                "~/.local/bin".into(),
+                "~/Downloads".into(),
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct AppImage {
+    path: PathBuf,
+    name: String,
+    comment: Option<String>,
+    icon: Option<PathBuf>,
+    /// The desktop entry embedded in the image, used to integrate it.
+    desktop: Option<String>,
+}
+
+fn is_appimage(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|x| x.eq_ignore_ascii_case("appimage"))
+}
+
+/// Extracts the desktop entry and icons at the root of the image, once for each version of it.
+fn extract(path: &Path, cache: &Path) -> Option<PathBuf> {
+    let modified = std::fs::metadata(path)
+        .and_then(|x| x.modified())
+        .ok()?
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()?
+        .as_secs();
+    let directory = cache.join(format!(
+        "{}-{modified}",
+        path.file_stem()?.to_string_lossy()
+    ));
+    let root = directory.join("squashfs-root");
+
+    if root.is_dir() {
+        return Some(root);
+    }
+
+    std::fs::create_dir_all(&directory).ok()?;
+    for pattern in ["*.desktop", "*.png", "*.svg", ".DirIcon"] {
+        let result = Command::new(path)
+            .args(["--appimage-extract", pattern])
+            .current_dir(&directory)
+            .output();
+
+        if let Err(error) = result {
+            tracing::debug!(path = %path.display(), %error, "Failed to extract AppImage");
+            return None;
+        }
+    }
+
+    root.is_dir().then_some(root)
+}
+
+fn read_appimage(path: PathBuf, cache: Option<&Path>, locales: &[String]) -> AppImage {
+    let fallback = AppImage {
+        name: path
+            .file_stem()
+            .map(|x| x.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        comment: None,
+        icon: None,
+        desktop: None,
+        path: path.clone(),
+    };
+
+    let Some(root) = cache.and_then(|x| extract(&path, x)) else {
+        return fallback;
+    };
+
+    let Some(desktop_path) = std::fs::read_dir(&root).ok().and_then(|x| {
+        x.filter_map(|x| Some(x.ok()?.path()))
+            .find(|x| x.extension().is_some_and(|x| x == "desktop"))
+    }) else {
+        return fallback;
+    };
+
+    let Ok(entry) =
+        freedesktop_desktop_entry::DesktopEntry::from_path(&desktop_path, Some(locales))
+    else {
+        return fallback;
+    };
+
+    let icon = entry
+        .icon()
+        .and_then(|name| {
+            ["png", "svg"]
+                .iter()
+                .map(|x| root.join(format!("{name}.{x}")))
+                .find(|x| x.is_file())
+        })
+        .or_else(|| Some(root.join(".DirIcon")).filter(|x| x.is_file()));
+
+    AppImage {
+        name: entry
+            .name(locales)
+            .map(|x| x.into_owned())
+            .unwrap_or(fallback.name),
+        comment: entry.comment(locales).map(|x| x.into_owned()),
+        icon,
+        desktop: std::fs::read_to_string(&desktop_path).ok(),
+        path,
+    }
+}
+
+/// Writes a desktop entry for the image in the data directory, so it shows up like
+/// any installed application.
+fn integrate(app: AppImage) -> Box<ActionType> {
+    Box::new(move |_| {
+        let Ok(base_dirs) = BaseDirectories::new() else {
+            return ActionResult::Error;
+        };
+
+        let stem = app
+            .path
+            .file_stem()
+            .map(|x| x.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let quoted = format!("\"{}\"", app.path.display());
+
+        // A copy of the icon, since the extracted one goes away with the cache
+        let icon = app.icon.as_ref().and_then(|icon| {
+            let extension = icon
+                .extension()
+                .map(|x| x.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "png".into());
+            let target = base_dirs
+                .place_data_file(format!("icons/appimage-{stem}.{extension}"))
+                .ok()?;
+            std::fs::copy(icon, &target).ok()?;
+            Some(target)
+        });
+
+        let desktop = match &app.desktop {
+            Some(desktop) => desktop
+                .lines()
+                .filter(|x| !x.starts_with("TryExec="))
+                .map(|line| {
+                    if let Some(exec) = line.strip_prefix("Exec=") {
+                        // Keeps the field codes, like `%U`
+                        let args = exec.split_once(' ').map_or("", |(_, x)| x);
+                        format!("Exec={quoted} {args}").trim_end().to_owned()
+                    } else if line.starts_with("Icon=")
+                        && let Some(icon) = &icon
+                    {
+                        format!("Icon={}", icon.display())
+                    } else {
+                        line.to_owned()
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+            None => format!(
+                "[Desktop Entry]\nType=Application\nName={}\nExec={quoted}\n{}",
+                app.name,
+                icon.as_ref()
+                    .map(|x| format!("Icon={}\n", x.display()))
+                    .unwrap_or_default()
+            ),
+        };
+
+        base_dirs
+            .place_data_file(format!("applications/appimage-{stem}.desktop"))
+            .and_then(|x| std::fs::write(x, desktop + "\n"))
+            .is_ok()
+            .into()
+    })
+}
+
+#[derive(Debug)]
+pub struct AppImages {
+    /// Filled in the background, since reading an image means running it.
+    apps: Arc<Mutex<Vec<AppImage>>>,
+}
+
+impl AppImages {
+    pub fn new(_: &Context, options: &toml::Table) -> Self {
+        let config: AppImagesConfig = parse_options(options);
+        let apps = Arc::new(Mutex::new(Vec::new()));
+
+        let directories = config
+            .directories
+            .iter()
+            .filter_map(|x| expanduser::expanduser(x).ok())
+            .collect::<Vec<_>>();
+        let cache = BaseDirectories::with_prefix("jogger")
+            .ok()
+            .map(|x| x.get_cache_home().join("appimages"));
+
+        let shared = apps.clone();
+        std::thread::spawn(move || {
+            let locales = get_languages_from_env();
+
+            for directory in directories {
+                let Ok(entries) = std::fs::read_dir(directory) else {
+                    continue;
+                };
+
+                for path in entries.filter_map(|x| Some(x.ok()?.path())) {
+                    if is_appimage(&path) {
+                        let app = read_appimage(path, cache.as_deref(), &locales);
+                        shared.lock().push(app);
+                    }
+                }
+            }
+        });
+
+        Self { apps }
+    }
+}
+
+impl Plugin for AppImages {
+    fn name(&self) -> &str {
+        "AppImages"
+    }
+
+    fn icon(&self) -> Option<&str> {
+        Some("application-x-executable")
+    }
+
+    fn search(&self, query: &str, _: &Context, _: &CancellationToken) -> Vec<Entry> {
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let matcher = SkimMatcherV2::default();
+
+        self.apps
+            .lock()
+            .iter()
+            .filter_map(|app| {
+                let (score, indices) = matcher.fuzzy_indices(&app.name, query)?;
+
+                Some(Entry {
+                    name: FormattedString::from_indices(&app.name, indices),
+                    tag: Some(FormattedString::plain("AppImage")),
+                    description: Some(FormattedString::plain(
+                        app.comment
+                            .clone()
+                            .unwrap_or_else(|| app.path.display().to_string()),
+                    )),
+                    icon: match &app.icon {
+                        Some(x) => EntryIcon::Path(x.clone()),
+                        None => EntryIcon::Name("application-x-executable".into()),
+                    },
+                    actions: vec![
+                        EntryAction {
+                            icon: "media-playback-start".into(),
+                            name: "Launch".into(),
+                            function: EntryAction::command(
+                                app.path.to_string_lossy().into_owned(),
+                                Vec::new(),
+                                app.path.parent().map(Path::to_path_buf),
+                            ),
+                            ..Default::default()
+                        },
+                        EntryAction {
+                            icon: "list-add".into(),
+                            name: "Integrate".into(),
+                            function: integrate(app.clone()),
+                            key: Key::i,
+                            modifier: ModifierType::CONTROL_MASK,
+                        },
+                    ],
+                    id: format!("appimages:{}", app.path.display()),
+                    score: score.try_into().ok()?,
+                    ..Default::default()
+                })
+            })
+            .collect()
+    }
+}
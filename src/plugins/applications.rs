@@ -1,18 +1,20 @@
-use std::cmp::Ordering;
+use std::collections::HashSet;
 use std::fs::File;
 use std::io::BufRead;
 use std::path::PathBuf;
 use std::process::Command;
+use std::time::SystemTime;
 
 use freedesktop_desktop_entry::{default_paths, get_languages_from_env};
 use fuzzy_matcher::FuzzyMatcher;
 use fuzzy_matcher::skim::SkimMatcherV2;
 use gtk::gdk::{Key, ModifierType};
 use itertools::Itertools;
+use tokio_util::sync::CancellationToken;
 use xdg::BaseDirectories;
 
-use crate::interface::{ActionType, Context, EntryAction, FormattedString};
-use crate::utils::CommandExt;
+use crate::interface::{ActionResult, ActionType, Context, EntryAction, FormattedString};
+use crate::utils::{CommandExt, SearchCache};
 use crate::xdg_database::ExecParser;
 use crate::{Entry, Plugin, interface::EntryIcon};
 
@@ -30,6 +32,8 @@ pub struct DesktopEntry {
     exec: Option<String>,
     pub terminal: bool,
     pub terminal_args: TerminalArgs,
+    /// The class of its windows, when it's not the ID.
+    pub wm_class: Option<String>,
     pub(crate) mime_types: Vec<String>,
     pub display: bool,
     frequency: u32,
@@ -42,6 +46,12 @@ impl DesktopEntry {
             None => "image-missing",
         }
     }
+
+    /// When the desktop file was created, or last changed when that isn't known.
+    pub fn installed(&self) -> Option<SystemTime> {
+        let metadata = std::fs::metadata(&self.file_path).ok()?;
+        metadata.created().or_else(|_| metadata.modified()).ok()
+    }
 }
 
 impl PartialEq for DesktopEntry {
@@ -136,6 +146,7 @@ impl DesktopEntry {
             working_directory: value.path().map(PathBuf::from),
             exec: value.exec().map(str::to_owned),
             terminal: value.terminal(),
+            wm_class: value.startup_wm_class().map(str::to_owned),
             terminal_args: TerminalArgs {
                 exec: value.desktop_entry("X-TerminalArgExec").map(str::to_owned),
                 app_id: value.desktop_entry("X-TerminalArgAppId").map(str::to_owned),
@@ -202,6 +213,7 @@ impl DesktopEntry {
                             description: self.description.as_ref().map(FormattedString::plain),
                             icon: EntryIcon::from(self.icon.clone()),
                             actions,
+                            id: self.id.clone(),
                             score,
                             ..Default::default()
                         },
@@ -217,6 +229,7 @@ impl DesktopEntry {
                                 .map(|x| FormattedString::from_indices(x, indices)),
                             icon: EntryIcon::from(self.icon.clone()),
                             actions,
+                            id: self.id.clone(),
                             score,
                             ..Default::default()
                         },
@@ -233,6 +246,7 @@ impl DesktopEntry {
                             description: self.description.as_ref().map(FormattedString::plain),
                             icon: EntryIcon::from(self.icon.clone()),
                             actions,
+                            id: self.id.clone(),
                             score,
                             ..Default::default()
                         },
@@ -249,6 +263,7 @@ impl DesktopEntry {
                             description: self.description.as_ref().map(FormattedString::plain),
                             icon: EntryIcon::from(self.icon.clone()),
                             actions,
+                            id: self.id.clone(),
                             score,
                             ..Default::default()
                         },
@@ -353,6 +368,7 @@ impl DesktopEntry {
             .max_by_key(|x| x.1.0)
             .and_then(|(kind, (score, indices))| {
                 let score = score.try_into().ok()?;
+                let action_id = format!("{}#{}", self.id, action.id);
 
                 let actions = vec![EntryAction {
                     icon: self.icon().into(),
@@ -373,6 +389,7 @@ impl DesktopEntry {
                                 action.icon.clone().unwrap_or("emblem-added".into()),
                             ),
                             actions,
+                            id: action_id.clone(),
                             score,
                             ..Default::default()
                         },
@@ -388,6 +405,7 @@ impl DesktopEntry {
                                 action.icon.clone().unwrap_or("emblem-added".into()),
                             ),
                             actions,
+                            id: action_id.clone(),
                             score,
                             ..Default::default()
                         },
@@ -407,6 +425,7 @@ impl DesktopEntry {
                                 action.icon.clone().unwrap_or("emblem-added".into()),
                             ),
                             actions,
+                            id: action_id.clone(),
                             score,
                             ..Default::default()
                         },
@@ -426,6 +445,7 @@ impl DesktopEntry {
                                 action.icon.clone().unwrap_or("emblem-added".into()),
                             ),
                             actions,
+                            id: action_id.clone(),
                             score,
                             ..Default::default()
                         },
@@ -467,6 +487,8 @@ impl DesktopEntry {
 #[derive(Debug)]
 pub struct Applications {
     desktop_file_opener: String,
+    /// The IDs of the apps matched by the last query.
+    cache: SearchCache<String>,
 }
 
 pub fn read_desktop_entries() -> Vec<DesktopEntry> {
@@ -526,11 +548,17 @@ impl Applications {
 
         Self {
             desktop_file_opener: opener.id.clone(),
+            cache: SearchCache::default(),
         }
     }
 }
 
 impl Plugin for Applications {
+    fn open(&mut self) {
+        // The apps may have changed since
+        self.cache.clear();
+    }
+
     fn name(&self) -> &str {
         "Applications"
     }
@@ -539,16 +567,23 @@ impl Plugin for Applications {
         Some("applications-all")
     }
 
-    fn search(&self, query: &str, context: &Context) -> Vec<Entry> {
+    fn search(&self, query: &str, context: &Context, _: &CancellationToken) -> Vec<Entry> {
         if query.is_empty() {
             context
                 .apps
                 .app_map
                 .values()
                 .filter(|x| x.display)
-                .sorted_by(|a, b| match b.frequency.cmp(&a.frequency) {
-                    Ordering::Equal => a.name.cmp(&b.name),
-                    x => x,
+                .sorted_by(|a, b| {
+                    b.frequency
+                        .cmp(&a.frequency)
+                        .then_with(|| {
+                            context
+                                .frecency
+                                .score(&b.id)
+                                .cmp(&context.frecency.score(&a.id))
+                        })
+                        .then_with(|| a.name.cmp(&b.name))
                 })
                 .map(|x| Entry {
                     name: FormattedString::plain(&x.name),
@@ -557,7 +592,7 @@ impl Plugin for Applications {
                     icon: EntryIcon::from(x.icon.clone()),
                     small_icon: EntryIcon::None,
                     actions: x.get_actions(&self.desktop_file_opener),
-                    id: "".to_owned(),
+                    id: x.id.clone(),
                     ..Default::default()
                 })
                 .collect()
@@ -570,23 +605,39 @@ impl Plugin for Applications {
             //     Normalization::Smart,
             //     AtomKind::Fuzzy,
             // );
-            context
+            let candidates = self.cache.take(query);
+            let mut matched = HashSet::new();
+
+            let entries = context
                 .apps
                 .app_map
                 .values()
                 .filter(|x| x.display)
+                .filter(|x| candidates.as_ref().is_none_or(|y| y.contains(&x.id)))
                 .sorted_by_key(|x| x.id.clone())
                 .flat_map(|entry| {
-                    entry
+                    let results = entry
                         .actions
                         .iter()
                         .flat_map(|action| entry.get_action_score(action, query, &matcher))
                         .chain(entry.get_score(query, &matcher, &self.desktop_file_opener))
+                        .collect_vec();
+
+                    if !results.is_empty() {
+                        matched.insert(entry.id.clone());
+                    }
+                    results
+                })
+                .map(|(priority, mut x)| {
+                    x.score += context.frecency.score(&x.id);
+                    (priority, x)
                 })
                 .sorted_by(|a, b| b.1.score.cmp(&a.1.score).then(b.0.cmp(&a.0)))
-                .take(20)
                 .map(|(_, x)| x)
-                .collect()
+                .collect();
+
+            self.cache.store(query, matched);
+            entries
         }
     }
 }
@@ -594,6 +645,14 @@ impl Plugin for Applications {
 fn test(app: String, action: Option<String>, path: Option<PathBuf>) -> Box<ActionType> {
     Box::new(move |context| {
         if let Some(app) = context.apps.app_map.get(&app) {
+            if context.focus_or_launch
+                && action.is_none()
+                && path.is_none()
+                && crate::plugins::hyprland::focus_app(app)
+            {
+                return ActionResult::Ok;
+            }
+
             let args = match &path {
                 Some(path) => vec![path.to_string_lossy().to_string()],
                 None => vec![],
@@ -620,7 +679,7 @@ fn test2(app: String, action: Option<String>) -> Box<ActionType> {
             let exec = app.parse_exec(&[], false);
 
             if exec.is_empty() {
-                println!("No program to start the app");
+                tracing::warn!(app = %app.id, "No program to start the app");
                 return false.into();
             }
 
@@ -643,7 +702,7 @@ fn test2(app: String, action: Option<String>) -> Box<ActionType> {
                 }
 
                 if let Err(error) = command.spawn_detached() {
-                    println!("Failed to start app {:?} {:?}", command.get_args(), error);
+                    tracing::error!(args = ?command.get_args(), ?error, "Failed to start app");
                     return false.into();
                 }
 
@@ -0,0 +1,158 @@
+use std::collections::HashMap;
+use std::process::Command;
+use std::sync::Arc;
+
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use parking_lot::Mutex;
+use serde::Deserialize;
+use tokio_util::sync::CancellationToken;
+
+use crate::interface::{Context, Entry, EntryAction, EntryIcon, FormattedString, Plugin};
+
+#[derive(Debug, Deserialize)]
+struct Card {
+    name: String,
+    #[serde(default)]
+    properties: HashMap<String, String>,
+    #[serde(default)]
+    profiles: HashMap<String, Profile>,
+    active_profile: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Profile {
+    description: String,
+    #[serde(default)]
+    priority: u64,
+    #[serde(default = "default_available")]
+    available: bool,
+}
+
+fn default_available() -> bool {
+    true
+}
+
+impl Card {
+    fn is_bluetooth(&self) -> bool {
+        self.properties
+            .get("device.bus")
+            .is_some_and(|x| x == "bluetooth")
+            || self.name.starts_with("bluez_card.")
+    }
+
+    fn description(&self) -> &str {
+        self.properties
+            .get("device.description")
+            .or_else(|| self.properties.get("device.alias"))
+            .unwrap_or(&self.name)
+    }
+}
+
+/// Switches connected Bluetooth headsets between their profiles through PipeWire's PulseAudio
+/// interface, typically between high quality playback (A2DP) and a headset with a microphone (HFP).
+#[derive(Debug)]
+pub struct Audio {
+    /// Read when the launcher is shown, since devices come and go.
+    cards: Arc<Mutex<Vec<Card>>>,
+}
+
+impl Audio {
+    pub fn new(_: &Context) -> Self {
+        Self {
+            cards: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+}
+
+fn list_cards() -> Vec<Card> {
+    let output = match Command::new("pactl")
+        .args(["--format=json", "list", "cards"])
+        .output()
+    {
+        Ok(x) => x,
+        Err(error) => {
+            tracing::debug!(?error, "Failed to run pactl");
+            return Vec::new();
+        }
+    };
+
+    serde_json::from_slice::<Vec<Card>>(&output.stdout)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(Card::is_bluetooth)
+        .collect()
+}
+
+impl Plugin for Audio {
+    fn open(&mut self) {
+        let cards = self.cards.clone();
+        std::thread::spawn(move || {
+            let loaded = list_cards();
+            *cards.lock() = loaded;
+        });
+    }
+
+    fn name(&self) -> &str {
+        "Audio profiles"
+    }
+
+    fn icon(&self) -> Option<&str> {
+        Some("audio-headset")
+    }
+
+    fn search(&self, query: &str, _: &Context, _: &CancellationToken) -> Vec<Entry> {
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let matcher = SkimMatcherV2::default().ignore_case();
+
+        self.cards
+            .lock()
+            .iter()
+            .flat_map(|card| {
+                let active = card
+                    .active_profile
+                    .as_ref()
+                    .and_then(|x| card.profiles.get(x))
+                    .map_or("Off", |x| x.description.as_str());
+
+                let mut profiles = card
+                    .profiles
+                    .iter()
+                    .filter(|(name, x)| {
+                        x.available && Some(*name) != card.active_profile.as_ref() && *name != "off"
+                    })
+                    .collect::<Vec<_>>();
+                profiles.sort_by_key(|(_, x)| std::cmp::Reverse(x.priority));
+
+                let matcher = &matcher;
+                profiles.into_iter().filter_map(move |(name, profile)| {
+                    let title = format!("{}: {}", card.description(), profile.description);
+                    // Also found by what they are, not only by the name of the device
+                    let score = matcher
+                        .fuzzy_match(&format!("{title} bluetooth headset audio profile"), query)?;
+
+                    Some(Entry {
+                        name: FormattedString::plain(title),
+                        description: Some(FormattedString::plain(format!("Currently {active}"))),
+                        icon: EntryIcon::Name("audio-headset".into()),
+                        actions: vec![EntryAction {
+                            icon: "audio-headset".into(),
+                            name: "Switch".into(),
+                            function: EntryAction::command(
+                                "pactl".into(),
+                                vec!["set-card-profile".into(), card.name.clone(), name.clone()],
+                                None,
+                            ),
+                            ..Default::default()
+                        }],
+                        score: score.try_into().ok()?,
+                        ..Default::default()
+                    })
+                })
+            })
+            .collect()
+    }
+}
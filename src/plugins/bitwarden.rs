@@ -0,0 +1,326 @@
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::Arc;
+
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use gtk::gdk::{Key, ModifierType};
+use parking_lot::Mutex;
+use serde::Deserialize;
+use tokio_util::sync::CancellationToken;
+
+use crate::interface::{
+    ActionResult, ActionType, Context, Entry, EntryAction, EntryIcon, FormattedString, Plugin,
+    parse_options,
+};
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum Backend {
+    Rbw,
+    Bw,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct BitwardenConfig {
+    /// Picks `rbw` when it's installed, `bw` otherwise.
+    backend: Option<Backend>,
+}
+
+#[derive(Debug, Clone)]
+struct Item {
+    id: String,
+    name: String,
+    username: Option<String>,
+    folder: Option<String>,
+}
+
+#[derive(Debug)]
+enum Vault {
+    Loading,
+    /// Neither client works.
+    Unavailable,
+    Locked,
+    Unlocked(Vec<Item>),
+}
+
+#[derive(Debug, Deserialize)]
+struct BwStatus {
+    status: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BwItem {
+    id: String,
+    name: String,
+    login: Option<BwLogin>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BwLogin {
+    username: Option<String>,
+}
+
+/// Where `bw unlock` run from the launcher leaves the session key, since
+/// the daemon doesn't see the `BW_SESSION` of shells.
+fn session_file() -> Option<PathBuf> {
+    std::env::var_os("XDG_RUNTIME_DIR").map(|x| PathBuf::from(x).join("jogger-bw-session"))
+}
+
+fn run(backend: Backend, args: &[&str]) -> Option<String> {
+    let mut command = match backend {
+        Backend::Rbw => Command::new("rbw"),
+        Backend::Bw => Command::new("bw"),
+    };
+    command.args(args);
+
+    if backend == Backend::Bw
+        && let Some(session) = session_file()
+            .and_then(|x| std::fs::read_to_string(x).ok())
+            .map(|x| x.trim().to_owned())
+            .filter(|x| !x.is_empty())
+    {
+        command.env("BW_SESSION", session);
+    }
+
+    match command.output() {
+        Ok(x) if x.status.success() => Some(String::from_utf8_lossy(&x.stdout).into_owned()),
+        Ok(_) => None,
+        Err(error) => {
+            tracing::debug!(?error, ?backend, "Failed to run the Bitwarden client");
+            None
+        }
+    }
+}
+
+fn load(backend: Backend) -> Vault {
+    match backend {
+        Backend::Rbw => {
+            // Fails while locked, without asking for the password
+            if run(backend, &["unlocked"]).is_none() {
+                return Vault::Locked;
+            }
+
+            let Some(output) = run(backend, &["list", "--fields", "id,name,user,folder"]) else {
+                return Vault::Unavailable;
+            };
+
+            let non_empty = |x: &str| (!x.is_empty()).then(|| x.to_owned());
+            Vault::Unlocked(
+                output
+                    .lines()
+                    .filter_map(|x| {
+                        let mut fields = x.split('\t');
+                        Some(Item {
+                            id: fields.next()?.to_owned(),
+                            name: fields.next()?.to_owned(),
+                            username: fields.next().and_then(non_empty),
+                            folder: fields.next().and_then(non_empty),
+                        })
+                    })
+                    .collect(),
+            )
+        }
+        Backend::Bw => {
+            let status =
+                run(backend, &["status"]).and_then(|x| serde_json::from_str::<BwStatus>(&x).ok());
+            match status {
+                Some(x) if x.status == "unlocked" => {}
+                Some(_) => return Vault::Locked,
+                None => return Vault::Unavailable,
+            }
+
+            let Some(items) = run(backend, &["list", "items"])
+                .and_then(|x| serde_json::from_str::<Vec<BwItem>>(&x).ok())
+            else {
+                return Vault::Unavailable;
+            };
+
+            Vault::Unlocked(
+                items
+                    .into_iter()
+                    .map(|x| Item {
+                        id: x.id,
+                        name: x.name,
+                        username: x.login.and_then(|x| x.username),
+                        folder: None,
+                    })
+                    .collect(),
+            )
+        }
+    }
+}
+
+/// Fetches the secret only when the action runs, so it's never kept around.
+fn copy_secret(backend: Backend, args: Vec<String>) -> Box<ActionType> {
+    Box::new(move |context| {
+        let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+        match run(backend, &args) {
+            Some(secret) => EntryAction::copy_secret(secret.trim_end_matches('\n'))(context),
+            None => ActionResult::Error,
+        }
+    })
+}
+
+/// Searches a Bitwarden vault through `rbw` or the official `bw` client.
+#[derive(Debug)]
+pub struct Bitwarden {
+    backend: Backend,
+    vault: Arc<Mutex<Vault>>,
+}
+
+impl Bitwarden {
+    pub fn new(_: &Context, options: &toml::Table) -> Self {
+        let config: BitwardenConfig = parse_options(options);
+
+        let rbw = || {
+            Command::new("rbw")
+                .arg("--version")
+                .output()
+                .is_ok_and(|x| x.status.success())
+        };
+        let backend = match config.backend {
+            Some(backend) => backend,
+            None if rbw() => Backend::Rbw,
+            None => Backend::Bw,
+        };
+
+        Self {
+            backend,
+            vault: Arc::new(Mutex::new(Vault::Loading)),
+        }
+    }
+
+    fn unlock_entry(&self) -> Option<Entry> {
+        let function = match self.backend {
+            // Asks for the password with pinentry
+            Backend::Rbw => EntryAction::command("rbw".into(), vec!["unlock".into()], None),
+            Backend::Bw => {
+                let file = session_file()?;
+
+                EntryAction::launch_terminal(
+                    Some("sh".into()),
+                    vec![
+                        "-c".into(),
+                        "umask 077 && bw unlock --raw > \"$1\"".into(),
+                        "sh".into(),
+                        file.to_string_lossy().into_owned(),
+                    ],
+                    None,
+                )
+            }
+        };
+
+        Some(Entry {
+            name: FormattedString::plain("Unlock vault"),
+            description: Some(FormattedString::plain("Bitwarden is locked")),
+            icon: EntryIcon::Name("changes-prevent".into()),
+            actions: vec![EntryAction {
+                icon: "changes-allow".into(),
+                name: "Unlock".into(),
+                function,
+                ..Default::default()
+            }],
+            ..Default::default()
+        })
+    }
+
+    fn item_to_entry(&self, item: &Item, name: FormattedString, score: u64) -> Entry {
+        let mut actions = vec![EntryAction {
+            icon: "dialog-password".into(),
+            name: "Copy password".into(),
+            function: copy_secret(
+                self.backend,
+                match self.backend {
+                    Backend::Rbw => vec!["get".into(), item.id.clone()],
+                    Backend::Bw => vec!["get".into(), "password".into(), item.id.clone()],
+                },
+            ),
+            ..Default::default()
+        }];
+
+        if let Some(username) = &item.username {
+            actions.push(EntryAction {
+                icon: "avatar-default".into(),
+                name: "Copy username".into(),
+                function: EntryAction::copy(username),
+                key: Key::Return,
+                modifier: ModifierType::SHIFT_MASK,
+            });
+        }
+
+        actions.push(EntryAction {
+            icon: "appointment-soon".into(),
+            name: "Copy TOTP".into(),
+            function: copy_secret(
+                self.backend,
+                match self.backend {
+                    Backend::Rbw => vec!["code".into(), item.id.clone()],
+                    Backend::Bw => vec!["get".into(), "totp".into(), item.id.clone()],
+                },
+            ),
+            key: Key::t,
+            modifier: ModifierType::CONTROL_MASK,
+        });
+
+        Entry {
+            name,
+            tag: item.folder.as_ref().map(FormattedString::plain),
+            description: item.username.as_ref().map(FormattedString::plain),
+            icon: EntryIcon::Name("dialog-password".into()),
+            actions,
+            id: format!("bitwarden:{}", item.id),
+            score,
+            ..Default::default()
+        }
+    }
+}
+
+impl Plugin for Bitwarden {
+    fn open(&mut self) {
+        // Listing the vault takes a while with `bw`
+        let backend = self.backend;
+        let vault = self.vault.clone();
+        std::thread::spawn(move || {
+            let loaded = load(backend);
+            *vault.lock() = loaded;
+        });
+    }
+
+    fn name(&self) -> &str {
+        "Bitwarden"
+    }
+
+    fn icon(&self) -> Option<&str> {
+        Some("dialog-password")
+    }
+
+    fn search(&self, query: &str, _: &Context, _: &CancellationToken) -> Vec<Entry> {
+        let query = query
+            .strip_prefix("pw")
+            .filter(|x| x.is_empty() || x.starts_with(char::is_whitespace))
+            .unwrap_or(query)
+            .trim();
+
+        let items = match &*self.vault.lock() {
+            Vault::Loading | Vault::Unavailable => return Vec::new(),
+            Vault::Locked => return self.unlock_entry().into_iter().collect(),
+            Vault::Unlocked(items) => items.clone(),
+        };
+
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let matcher = SkimMatcherV2::default().ignore_case();
+        items
+            .iter()
+            .filter_map(|x| {
+                let (score, indices) = matcher.fuzzy_indices(&x.name, query)?;
+                let name = FormattedString::from_indices(&x.name, indices);
+                Some(self.item_to_entry(x, name, score.try_into().ok()?))
+            })
+            .collect()
+    }
+}
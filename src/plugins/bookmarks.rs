@@ -0,0 +1,269 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Arc;
+
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use gtk::gdk::{Key, ModifierType};
+use parking_lot::Mutex;
+use serde::Deserialize;
+use tokio_util::sync::CancellationToken;
+use xdg::BaseDirectories;
+
+use crate::interface::{
+    ActionType, Context, Entry, EntryAction, EntryIcon, FormattedString, Plugin,
+};
+
+const FIREFOX_DIRS: [&str; 2] = [".mozilla/firefox", ".librewolf"];
+const CHROMIUM_DIRS: [&str; 5] = [
+    "chromium",
+    "google-chrome",
+    "BraveSoftware/Brave-Browser",
+    "vivaldi",
+    "microsoft-edge",
+];
+
+const FIREFOX_QUERY: &str = "SELECT b.title AS title, p.url AS url, f.title AS folder \
+    FROM moz_bookmarks b \
+    JOIN moz_places p ON b.fk = p.id \
+    LEFT JOIN moz_bookmarks f ON b.parent = f.id \
+    WHERE b.type = 1 AND p.url NOT LIKE 'place:%'";
+
+#[derive(Debug, Clone, Deserialize)]
+struct Bookmark {
+    title: Option<String>,
+    url: String,
+    folder: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChromiumBookmarks {
+    roots: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum ChromiumNode {
+    Url {
+        name: String,
+        url: String,
+    },
+    Folder {
+        name: String,
+        #[serde(default)]
+        children: Vec<ChromiumNode>,
+    },
+}
+
+/// The files of every profile of the browsers found.
+fn bookmark_files() -> (Vec<PathBuf>, Vec<PathBuf>) {
+    let profiles = |dir: PathBuf| {
+        std::fs::read_dir(dir)
+            .into_iter()
+            .flatten()
+            .filter_map(Result::ok)
+            .map(|x| x.path())
+            .collect::<Vec<_>>()
+    };
+
+    let firefox = std::env::home_dir()
+        .into_iter()
+        .flat_map(|home| FIREFOX_DIRS.map(|x| home.join(x)))
+        .flat_map(profiles)
+        .map(|x| x.join("places.sqlite"))
+        .filter(|x| x.is_file())
+        .collect();
+
+    let chromium = BaseDirectories::new()
+        .ok()
+        .into_iter()
+        .flat_map(|base| CHROMIUM_DIRS.map(|x| base.get_config_home().join(x)))
+        .flat_map(profiles)
+        .map(|x| x.join("Bookmarks"))
+        .filter(|x| x.is_file())
+        .collect();
+
+    (firefox, chromium)
+}
+
+fn load_firefox(path: &Path) -> Vec<Bookmark> {
+    // Firefox keeps the database locked while running, immutable skips the lock
+    let output = Command::new("sqlite3")
+        .arg("-readonly")
+        .arg("-json")
+        .arg(format!("file:{}?immutable=1", path.display()))
+        .arg(FIREFOX_QUERY)
+        .output();
+
+    match output {
+        Ok(x) if x.status.success() => {
+            // Nothing at all is printed when there are no bookmarks
+            serde_json::from_slice(&x.stdout).unwrap_or_default()
+        }
+        Ok(x) => {
+            let error = String::from_utf8_lossy(&x.stderr);
+            tracing::warn!(%error, ?path, "Failed to read Firefox bookmarks");
+            Vec::new()
+        }
+        Err(error) => {
+            tracing::debug!(?error, "Failed to run sqlite3");
+            Vec::new()
+        }
+    }
+}
+
+fn flatten_chromium(node: ChromiumNode, folder: Option<&str>, bookmarks: &mut Vec<Bookmark>) {
+    match node {
+        ChromiumNode::Url { name, url } => bookmarks.push(Bookmark {
+            title: Some(name),
+            url,
+            folder: folder.map(str::to_owned),
+        }),
+        ChromiumNode::Folder { name, children } => {
+            for child in children {
+                flatten_chromium(child, Some(&name), bookmarks);
+            }
+        }
+    }
+}
+
+fn load_chromium(path: &Path) -> Vec<Bookmark> {
+    let bookmarks = std::fs::read_to_string(path)
+        .map_err(|x| x.to_string())
+        .and_then(|x| serde_json::from_str::<ChromiumBookmarks>(&x).map_err(|x| x.to_string()));
+
+    match bookmarks {
+        Ok(bookmarks) => {
+            let mut result = Vec::new();
+            // Also holds the sync metadata, which isn't a node
+            for node in bookmarks
+                .roots
+                .into_values()
+                .filter_map(|x| serde_json::from_value::<ChromiumNode>(x).ok())
+            {
+                flatten_chromium(node, None, &mut result);
+            }
+            result
+        }
+        Err(error) => {
+            tracing::warn!(%error, ?path, "Failed to read Chromium bookmarks");
+            Vec::new()
+        }
+    }
+}
+
+fn load() -> Vec<Bookmark> {
+    let (firefox, chromium) = bookmark_files();
+    let mut urls = HashSet::new();
+
+    firefox
+        .iter()
+        .flat_map(|x| load_firefox(x))
+        .chain(chromium.iter().flat_map(|x| load_chromium(x)))
+        .filter(|x| urls.insert(x.url.clone()))
+        .collect()
+}
+
+/// Searches the bookmarks of Firefox and Chromium based browsers.
+#[derive(Debug)]
+pub struct Bookmarks {
+    handler: Option<String>,
+    bookmarks: Arc<Mutex<Vec<Bookmark>>>,
+}
+
+impl Bookmarks {
+    pub fn new(context: &Context) -> Self {
+        Self {
+            handler: context
+                .apps
+                .default_for_mime(&"x-scheme-handler/https".parse().unwrap())
+                .map(|x| x.id.clone()),
+            bookmarks: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    fn open(&self, url: &str) -> Box<ActionType> {
+        match &self.handler {
+            Some(handler) => EntryAction::open(handler.clone(), None, Some(PathBuf::from(url))),
+            None => EntryAction::command("xdg-open".into(), vec![url.to_owned()], None),
+        }
+    }
+}
+
+impl Plugin for Bookmarks {
+    fn open(&mut self) {
+        // Bookmarks may have been added in the browser since last time
+        let bookmarks = self.bookmarks.clone();
+        std::thread::spawn(move || {
+            let loaded = load();
+            *bookmarks.lock() = loaded;
+        });
+    }
+
+    fn name(&self) -> &str {
+        "Bookmarks"
+    }
+
+    fn icon(&self) -> Option<&str> {
+        Some("bookmark-new")
+    }
+
+    fn search(&self, query: &str, _: &Context, _: &CancellationToken) -> Vec<Entry> {
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let matcher = SkimMatcherV2::default().ignore_case();
+        self.bookmarks
+            .lock()
+            .iter()
+            .filter_map(|x| {
+                let title = x.title.as_deref().filter(|x| !x.is_empty());
+                let title_match = title.and_then(|title| {
+                    let (score, indices) = matcher.fuzzy_indices(title, query)?;
+                    Some((score, FormattedString::from_indices(title, indices)))
+                });
+                // The title is what people remember, the address is only a fallback
+                let url_match = matcher
+                    .fuzzy_match(&x.url, query)
+                    .map(|score| (score / 2, FormattedString::plain(title.unwrap_or(&x.url))));
+
+                let (score, name) = match (title_match, url_match) {
+                    (Some(title), Some(url)) if url.0 > title.0 => url,
+                    (Some(title), _) => title,
+                    (None, url) => url?,
+                };
+
+                Some(Entry {
+                    name,
+                    tag: x
+                        .folder
+                        .as_ref()
+                        .filter(|x| !x.is_empty())
+                        .map(FormattedString::plain),
+                    description: Some(FormattedString::plain(&x.url)),
+                    icon: EntryIcon::Name("bookmark-new".into()),
+                    actions: vec![
+                        EntryAction {
+                            icon: "web-browser".into(),
+                            name: "Open".into(),
+                            function: self.open(&x.url),
+                            ..Default::default()
+                        },
+                        EntryAction {
+                            icon: "edit-copy".into(),
+                            name: "Copy link".into(),
+                            function: EntryAction::copy(&x.url),
+                            key: Key::c,
+                            modifier: ModifierType::CONTROL_MASK,
+                        },
+                    ],
+                    id: format!("bookmarks:{}", x.url),
+                    score: score.try_into().ok()?,
+                    ..Default::default()
+                })
+            })
+            .collect()
+    }
+}
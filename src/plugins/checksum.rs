@@ -0,0 +1,164 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use parking_lot::Mutex;
+use tokio_util::sync::CancellationToken;
+
+use crate::interface::{
+    ActionResult, ActionType, Context, Entry, EntryAction, EntryIcon, FormatStyle, FormattedString,
+    Plugin,
+};
+
+#[derive(Debug)]
+struct CachedHash {
+    path: PathBuf,
+    program: &'static str,
+    modified: SystemTime,
+    hash: String,
+}
+
+/// Only hashes once asked to, files can be large, then keeps the last hash for the result.
+#[derive(Debug)]
+pub struct Checksum {
+    last: Arc<Mutex<Option<CachedHash>>>,
+}
+
+impl Checksum {
+    pub fn new(_: &Context) -> Self {
+        Self {
+            last: Arc::default(),
+        }
+    }
+
+    /// The hash computed last for the file, unless it was modified since.
+    fn cached(&self, path: &Path, program: &'static str) -> Option<String> {
+        let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+
+        self.last
+            .lock()
+            .as_ref()
+            .filter(|x| x.path == path && x.program == program && x.modified == modified)
+            .map(|x| x.hash.clone())
+    }
+}
+
+fn hash(path: &Path, program: &'static str) -> Option<CachedHash> {
+    let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+
+    let output = Command::new(program).arg(path).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let hash = String::from_utf8_lossy(&output.stdout)
+        .split_whitespace()
+        .next()?
+        .trim_start_matches('\\')
+        .to_lowercase();
+
+    Some(CachedHash {
+        path: path.to_owned(),
+        program,
+        modified,
+        hash,
+    })
+}
+
+/// Hashes the file, then searches again to show the result.
+fn verify(
+    last: Arc<Mutex<Option<CachedHash>>>,
+    path: PathBuf,
+    program: &'static str,
+) -> Box<ActionType> {
+    Box::new(move |_| match hash(&path, program) {
+        Some(hash) => {
+            *last.lock() = Some(hash);
+            ActionResult::Refresh
+        }
+        None => ActionResult::Error,
+    })
+}
+
+fn algorithm(hash: &str) -> Option<(&'static str, &'static str)> {
+    if !hash.chars().all(|x| x.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    match hash.len() {
+        32 => Some(("MD5", "md5sum")),
+        40 => Some(("SHA-1", "sha1sum")),
+        56 => Some(("SHA-224", "sha224sum")),
+        64 => Some(("SHA-256", "sha256sum")),
+        96 => Some(("SHA-384", "sha384sum")),
+        128 => Some(("SHA-512", "sha512sum")),
+        _ => None,
+    }
+}
+
+impl Plugin for Checksum {
+    fn name(&self) -> &str {
+        "Checksum"
+    }
+
+    fn icon(&self) -> Option<&str> {
+        Some("security-high")
+    }
+
+    fn search(&self, query: &str, _: &Context, _: &CancellationToken) -> Vec<Entry> {
+        let Some((path, expected)) = query.trim().rsplit_once(char::is_whitespace) else {
+            return Vec::new();
+        };
+
+        let Some((name, program)) = algorithm(expected) else {
+            return Vec::new();
+        };
+
+        let Ok(path) = expanduser::expanduser(path.trim()) else {
+            return Vec::new();
+        };
+
+        if !path.is_file() {
+            return Vec::new();
+        }
+
+        let Some(actual) = self.cached(&path, program) else {
+            return vec![Entry {
+                name: FormattedString::plain("Verify checksum"),
+                tag: Some(FormattedString::plain(name)),
+                description: Some(FormattedString::plain(path.to_string_lossy())),
+                icon: EntryIcon::Name("security-high".into()),
+                actions: vec![EntryAction {
+                    icon: "security-high".into(),
+                    name: "Verify".into(),
+                    function: verify(self.last.clone(), path.clone(), program),
+                    ..Default::default()
+                }],
+                drag_file: Some(path),
+                ..Default::default()
+            }];
+        };
+
+        let matches = actual.eq_ignore_ascii_case(expected);
+
+        vec![Entry {
+            name: FormattedString::plain(if matches {
+                "Checksum matches"
+            } else {
+                "Checksum does not match"
+            }),
+            tag: Some(FormattedString::plain(name)),
+            description: Some(FormattedString::from_style(&actual, FormatStyle::Monospace)),
+            icon: EntryIcon::Name(if matches { "emblem-ok" } else { "dialog-error" }.into()),
+            actions: vec![EntryAction {
+                icon: "edit-copy".into(),
+                name: "Copy checksum".into(),
+                function: EntryAction::copy(actual),
+                ..Default::default()
+            }],
+            drag_file: Some(path),
+            ..Default::default()
+        }]
+    }
+}
@@ -0,0 +1,171 @@
+use std::collections::HashSet;
+use std::io::Read;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use wl_clipboard_rs::paste::{ClipboardType, MimeType, Seat, get_contents, get_mime_types};
+use xdg::BaseDirectories;
+
+use crate::interface::SECRET_HINT_MIME;
+
+const MAX_ITEMS: usize = 100;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ClipboardItem {
+    pub id: String,
+    pub mime: String,
+    /// The content of text items, images are stored in their own file.
+    pub text: Option<String>,
+    pub pinned: bool,
+    pub time: i64,
+}
+
+#[derive(Debug)]
+pub struct History {
+    directory: PathBuf,
+    pub items: Vec<ClipboardItem>,
+}
+
+impl History {
+    pub fn load() -> Self {
+        let directory = BaseDirectories::with_prefix("jogger")
+            .unwrap()
+            .create_data_directory("clipboard")
+            .unwrap();
+
+        let items = std::fs::read_to_string(directory.join("history.json"))
+            .ok()
+            .and_then(|x| serde_json::from_str(&x).ok())
+            .unwrap_or_default();
+
+        Self { directory, items }
+    }
+
+    fn save(&self) {
+        match serde_json::to_string(&self.items) {
+            Ok(content) => {
+                if let Err(error) = std::fs::write(self.directory.join("history.json"), content) {
+                    tracing::error!(?error, "Failed to save clipboard history");
+                }
+            }
+            Err(error) => tracing::error!(?error, "Failed to serialize clipboard history"),
+        }
+    }
+
+    pub fn image_path(&self, item: &ClipboardItem) -> PathBuf {
+        self.directory.join(&item.id)
+    }
+
+    /// Adds the current content of the clipboard to the top of the history.
+    pub fn capture(&mut self) {
+        let Ok(mime_types) = get_mime_types(ClipboardType::Regular, Seat::Unspecified) else {
+            return;
+        };
+
+        if mime_types.contains(SECRET_HINT_MIME) {
+            return;
+        }
+
+        let image = mime_types
+            .iter()
+            .find(|x| *x == "image/png")
+            .or_else(|| mime_types.iter().find(|x| x.starts_with("image/")));
+
+        let mime = match image {
+            Some(mime) => MimeType::Specific(mime),
+            None => MimeType::Text,
+        };
+
+        let Ok((mut pipe, mime)) = get_contents(ClipboardType::Regular, Seat::Unspecified, mime)
+        else {
+            return;
+        };
+
+        let mut content = Vec::new();
+        if pipe.read_to_end(&mut content).is_err() || content.is_empty() {
+            return;
+        }
+
+        let id = format!("{:016x}", fnv1a(&content));
+        let time = chrono::Local::now().timestamp();
+
+        if let Some(index) = self.items.iter().position(|x| x.id == id) {
+            let mut item = self.items.remove(index);
+            item.time = time;
+            self.items.insert(0, item);
+        } else {
+            let text = if image.is_some() {
+                if std::fs::write(self.directory.join(&id), &content).is_err() {
+                    return;
+                }
+                None
+            } else {
+                Some(String::from_utf8_lossy(&content).into_owned())
+            };
+
+            self.items.insert(
+                0,
+                ClipboardItem {
+                    id,
+                    mime,
+                    text,
+                    pinned: false,
+                    time,
+                },
+            );
+        }
+
+        self.trim();
+        self.save();
+    }
+
+    /// Drops the oldest items past the limit, pinned items are always kept.
+    fn trim(&mut self) {
+        let mut unpinned = 0;
+        let mut removed = HashSet::new();
+
+        self.items.retain(|x| {
+            if x.pinned {
+                return true;
+            }
+
+            unpinned += 1;
+            if unpinned > MAX_ITEMS {
+                removed.insert(x.id.clone());
+                false
+            } else {
+                true
+            }
+        });
+
+        for id in removed {
+            let _ = std::fs::remove_file(self.directory.join(id));
+        }
+    }
+
+    pub fn toggle_pin(&mut self, id: &str) {
+        if let Some(item) = self.items.iter_mut().find(|x| x.id == id) {
+            item.pinned = !item.pinned;
+            self.trim();
+            self.save();
+        }
+    }
+
+    pub fn remove(&mut self, id: &str) {
+        if let Some(index) = self.items.iter().position(|x| x.id == id) {
+            let item = self.items.remove(index);
+            if item.text.is_none() {
+                let _ = std::fs::remove_file(self.image_path(&item));
+            }
+            self.save();
+        }
+    }
+}
+
+/// A 64-bit FNV-1a hash. Unlike the one of the standard library it doesn't change
+/// between releases, so the ids saved with the history keep matching.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+    })
+}
@@ -1,15 +1,130 @@
-use crate::interface::{Context, Plugin};
+mod history;
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use gtk::gdk::{Key, ModifierType};
+use parking_lot::Mutex;
+use tokio_util::sync::CancellationToken;
+
+use crate::interface::{
+    ActionResult, ActionType, Context, Entry, EntryAction, EntryIcon, FormattedString, Plugin,
+};
+use crate::plugins::clipboard::history::{ClipboardItem, History};
 
 #[derive(Debug)]
-pub struct Clipboard {}
+pub struct Clipboard {
+    history: Arc<Mutex<History>>,
+}
 
 impl Clipboard {
     pub fn new(_: &Context) -> Self {
-        Self {}
+        Self {
+            history: Arc::new(Mutex::new(History::load())),
+        }
     }
+
+    fn item_to_entry(&self, item: &ClipboardItem, image: PathBuf) -> Entry {
+        let time = chrono::DateTime::from_timestamp(item.time, 0)
+            .map(|x| {
+                x.with_timezone(&chrono::Local)
+                    .format("%d %b %H:%M")
+                    .to_string()
+            })
+            .unwrap_or_default();
+
+        let (name, icon, copy) = match &item.text {
+            Some(text) => (
+                text.lines()
+                    .map(str::trim)
+                    .find(|x| !x.is_empty())
+                    .unwrap_or_default()
+                    .chars()
+                    .take(256)
+                    .collect::<String>(),
+                EntryIcon::Name("edit-paste".into()),
+                EntryAction::copy(text),
+            ),
+            None => (
+                "Image".to_owned(),
+                EntryIcon::Path(image.clone()),
+                copy_file(image, item.mime.clone()),
+            ),
+        };
+
+        Entry {
+            name: FormattedString::plain(name),
+            tag: item.pinned.then(|| FormattedString::plain("Pinned")),
+            description: Some(FormattedString::plain(format!("{}  ·  {time}", item.mime))),
+            icon,
+            small_icon: EntryIcon::from(item.pinned.then(|| "pin".to_owned())),
+            actions: vec![
+                EntryAction {
+                    icon: "edit-copy".into(),
+                    name: "Copy".into(),
+                    function: copy,
+                    ..Default::default()
+                },
+                EntryAction {
+                    icon: "pin".into(),
+                    name: if item.pinned { "Unpin" } else { "Pin" }.into(),
+                    key: Key::p,
+                    modifier: ModifierType::CONTROL_MASK,
+                    function: toggle_pin(self.history.clone(), item.id.clone()),
+                },
+                EntryAction {
+                    icon: "edit-delete".into(),
+                    name: "Delete".into(),
+                    key: Key::Delete,
+                    modifier: ModifierType::CONTROL_MASK,
+                    function: EntryAction::confirm(
+                        "Delete this item from the history?",
+                        remove(self.history.clone(), item.id.clone()),
+                    ),
+                },
+            ],
+            id: item.id.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+fn copy_file(path: PathBuf, mime: String) -> Box<ActionType> {
+    Box::new(move |_| {
+        let Ok(content) = std::fs::read(&path) else {
+            return ActionResult::Error;
+        };
+
+        let mut opts = wl_clipboard_rs::copy::Options::new();
+        opts.foreground(true);
+        opts.copy(
+            wl_clipboard_rs::copy::Source::Bytes(content.into()),
+            wl_clipboard_rs::copy::MimeType::Specific(mime.clone()),
+        )
+        .is_ok()
+        .into()
+    })
+}
+
+fn toggle_pin(history: Arc<Mutex<History>>, id: String) -> Box<ActionType> {
+    Box::new(move |_| {
+        history.lock().toggle_pin(&id);
+        ActionResult::Refresh
+    })
+}
+
+fn remove(history: Arc<Mutex<History>>, id: String) -> Box<ActionType> {
+    Box::new(move |_| {
+        history.lock().remove(&id);
+        ActionResult::Refresh
+    })
 }
 
 impl Plugin for Clipboard {
+    fn open(&mut self) {
+        self.history.lock().capture();
+    }
+
     fn name(&self) -> &str {
         "Clipboard"
     }
@@ -17,4 +132,24 @@ impl Plugin for Clipboard {
     fn icon(&self) -> Option<&str> {
         Some("clipboard")
     }
+
+    fn search(&self, query: &str, _: &Context, _: &CancellationToken) -> Vec<Entry> {
+        let query = query.to_lowercase();
+        let history = self.history.lock();
+
+        let (pinned, unpinned): (Vec<_>, Vec<_>) = history
+            .items
+            .iter()
+            .filter(|x| match &x.text {
+                Some(text) => text.to_lowercase().contains(&query),
+                None => "image".contains(&query) || x.mime.contains(&query),
+            })
+            .partition(|x| x.pinned);
+
+        pinned
+            .into_iter()
+            .chain(unpinned)
+            .map(|x| self.item_to_entry(x, history.image_path(x)))
+            .collect()
+    }
 }
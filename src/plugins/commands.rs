@@ -1,3 +1,5 @@
+use tokio_util::sync::CancellationToken;
+
 use crate::Plugin;
 use crate::interface::{Context, EntryAction, EntryIcon, FormatStyle, FormattedString};
 
@@ -26,7 +28,7 @@ impl Plugin for Commands {
         Some("terminal")
     }
 
-    fn search(&self, query: &str, _: &Context) -> Vec<Entry> {
+    fn search(&self, query: &str, _: &Context, _: &CancellationToken) -> Vec<Entry> {
         vec![Entry {
             name: FormattedString::from_style(query.trim(), FormatStyle::Monospace),
             description: self
@@ -0,0 +1,339 @@
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use gtk::gdk::{Key, ModifierType};
+use parking_lot::Mutex;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use tokio_util::sync::CancellationToken;
+
+use crate::interface::{
+    ActionResult, ActionType, Context, Entry, EntryAction, EntryIcon, FormatStyle, FormattedString,
+    Plugin, parse_options,
+};
+
+/// Stopping waits for the container to exit, which can take a while.
+const TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ContainersConfig {
+    /// Found from `DOCKER_HOST` or the usual places of Docker and Podman when not set.
+    socket: Option<String>,
+    /// The client used to open a shell, guessed from the socket when not set.
+    command: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Container {
+    id: String,
+    names: Vec<String>,
+    image: String,
+    state: String,
+    /// Like "Up 2 hours" or "Exited (0) 3 days ago".
+    status: String,
+}
+
+impl Container {
+    fn name(&self) -> &str {
+        self.names
+            .first()
+            .map_or(self.id.as_str(), |x| x.trim_start_matches('/'))
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Image {
+    id: String,
+    #[serde(default)]
+    repo_tags: Option<Vec<String>>,
+}
+
+#[derive(Debug, Default, Clone)]
+struct State {
+    containers: Vec<Container>,
+    images: Vec<Image>,
+}
+
+fn find_socket() -> Option<PathBuf> {
+    if let Some(host) = std::env::var("DOCKER_HOST").ok()
+        && let Some(path) = host.strip_prefix("unix://")
+    {
+        return Some(path.into());
+    }
+
+    let runtime = std::env::var("XDG_RUNTIME_DIR").unwrap_or_default();
+    [
+        "/var/run/docker.sock".to_owned(),
+        format!("{runtime}/podman/podman.sock"),
+        format!("{runtime}/docker.sock"),
+        "/run/podman/podman.sock".to_owned(),
+    ]
+    .into_iter()
+    .map(PathBuf::from)
+    .find(|x| x.exists())
+}
+
+/// Sends a bare HTTP/1.0 request over the socket, so the body isn't chunked.
+fn request(socket: &PathBuf, method: &str, path: &str) -> std::io::Result<(u16, String)> {
+    let mut stream = UnixStream::connect(socket)?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    write!(
+        stream,
+        "{method} {path} HTTP/1.0\r\nHost: localhost\r\nContent-Length: 0\r\n\r\n"
+    )?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+
+    let (head, body) = response.split_once("\r\n\r\n").unwrap_or((&response, ""));
+    let status = head
+        .split_whitespace()
+        .nth(1)
+        .and_then(|x| x.parse().ok())
+        .unwrap_or(0);
+
+    Ok((status, body.to_owned()))
+}
+
+fn get<T: DeserializeOwned>(socket: &PathBuf, path: &str) -> Option<T> {
+    match request(socket, "GET", path) {
+        Ok((200, body)) => serde_json::from_str(&body)
+            .inspect_err(|error| tracing::debug!(%error, path, "Invalid container API response"))
+            .ok(),
+        Ok((status, body)) => {
+            tracing::debug!(status, %body, path, "Container API request failed");
+            None
+        }
+        Err(error) => {
+            tracing::debug!(?error, ?socket, "Failed to reach the container engine");
+            None
+        }
+    }
+}
+
+fn load(socket: &PathBuf) -> State {
+    State {
+        containers: get(socket, "/containers/json?all=true").unwrap_or_default(),
+        images: get(socket, "/images/json").unwrap_or_default(),
+    }
+}
+
+/// The containers and images of Docker or Podman, through the API socket.
+#[derive(Debug)]
+pub struct Containers {
+    socket: Option<PathBuf>,
+    command: String,
+    state: Arc<Mutex<State>>,
+}
+
+impl Containers {
+    pub fn new(_: &Context, options: &toml::Table) -> Self {
+        let config: ContainersConfig = parse_options(options);
+
+        let socket = config
+            .socket
+            .and_then(|x| expanduser::expanduser(x).ok())
+            .or_else(find_socket);
+
+        let podman = socket
+            .as_ref()
+            .is_some_and(|x| x.to_string_lossy().contains("podman"));
+        let command = config
+            .command
+            .unwrap_or_else(|| if podman { "podman" } else { "docker" }.into());
+
+        Self {
+            socket,
+            command,
+            state: Arc::new(Mutex::new(State::default())),
+        }
+    }
+
+    /// Posts to the API, then reloads what's shown.
+    fn manage(&self, path: String) -> Box<ActionType> {
+        let socket = self.socket.clone();
+        let state = self.state.clone();
+
+        Box::new(move |_| {
+            let Some(socket) = &socket else {
+                return ActionResult::Error;
+            };
+
+            match request(socket, "POST", &path) {
+                // Not modified, when it's already in that state
+                Ok((200..=299 | 304, _)) => {
+                    *state.lock() = load(socket);
+                    ActionResult::Refresh
+                }
+                Ok((status, body)) => {
+                    tracing::warn!(status, %body, %path, "Failed to manage the container");
+                    ActionResult::Error
+                }
+                Err(error) => {
+                    tracing::warn!(?error, "Failed to reach the container engine");
+                    ActionResult::Error
+                }
+            }
+        })
+    }
+
+    fn container_to_entry(
+        &self,
+        container: &Container,
+        name: FormattedString,
+        score: u64,
+    ) -> Entry {
+        let id = &container.id;
+        let running = container.state == "running";
+
+        let mut actions = Vec::new();
+        if running {
+            actions.push(EntryAction {
+                icon: "media-playback-stop".into(),
+                name: "Stop".into(),
+                function: self.manage(format!("/containers/{id}/stop")),
+                ..Default::default()
+            });
+            actions.push(EntryAction {
+                icon: "view-refresh".into(),
+                name: "Restart".into(),
+                function: self.manage(format!("/containers/{id}/restart")),
+                key: Key::u,
+                modifier: ModifierType::CONTROL_MASK,
+            });
+            actions.push(EntryAction {
+                icon: "terminal".into(),
+                name: "Open shell".into(),
+                function: EntryAction::launch_terminal(
+                    Some(self.command.clone()),
+                    vec!["exec".into(), "-it".into(), id.clone(), "sh".into()],
+                    None,
+                ),
+                key: Key::t,
+                modifier: ModifierType::CONTROL_MASK,
+            });
+        } else {
+            actions.push(EntryAction {
+                icon: "media-playback-start".into(),
+                name: "Start".into(),
+                function: self.manage(format!("/containers/{id}/start")),
+                ..Default::default()
+            });
+        }
+
+        actions.push(EntryAction {
+            icon: "edit-copy".into(),
+            name: "Copy ID".into(),
+            function: EntryAction::copy(id),
+            key: Key::c,
+            modifier: ModifierType::CONTROL_MASK,
+        });
+
+        Entry {
+            name,
+            tag: Some(if running {
+                FormattedString::from_style(&container.status, FormatStyle::Special)
+            } else {
+                FormattedString::plain(&container.status)
+            }),
+            description: Some(FormattedString::plain(&container.image)),
+            icon: EntryIcon::Name("package-x-generic".into()),
+            actions,
+            id: format!("container:{}", container.name()),
+            score,
+            ..Default::default()
+        }
+    }
+
+    fn image_to_entry(&self, image: &Image, tag: &str, name: FormattedString, score: u64) -> Entry {
+        let id = image.id.trim_start_matches("sha256:");
+
+        Entry {
+            name,
+            tag: Some(FormattedString::plain("Image")),
+            description: Some(FormattedString::from_style(
+                id.get(..12).unwrap_or(id),
+                FormatStyle::Monospace,
+            )),
+            icon: EntryIcon::Name("media-optical".into()),
+            actions: vec![
+                EntryAction {
+                    icon: "terminal".into(),
+                    name: "Run".into(),
+                    function: EntryAction::launch_terminal(
+                        Some(self.command.clone()),
+                        vec!["run".into(), "--rm".into(), "-it".into(), tag.to_owned()],
+                        None,
+                    ),
+                    ..Default::default()
+                },
+                EntryAction {
+                    icon: "edit-copy".into(),
+                    name: "Copy ID".into(),
+                    function: EntryAction::copy(id),
+                    key: Key::c,
+                    modifier: ModifierType::CONTROL_MASK,
+                },
+            ],
+            id: format!("image:{tag}"),
+            score,
+            ..Default::default()
+        }
+    }
+}
+
+impl Plugin for Containers {
+    fn open(&mut self) {
+        // The engine can be slow to answer, or not running at all
+        let Some(socket) = self.socket.clone() else {
+            return;
+        };
+        let state = self.state.clone();
+        std::thread::spawn(move || {
+            let loaded = load(&socket);
+            *state.lock() = loaded;
+        });
+    }
+
+    fn name(&self) -> &str {
+        "Containers"
+    }
+
+    fn icon(&self) -> Option<&str> {
+        Some("package-x-generic")
+    }
+
+    fn search(&self, query: &str, _: &Context, _: &CancellationToken) -> Vec<Entry> {
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let state = self.state.lock().clone();
+        let matcher = SkimMatcherV2::default().smart_case();
+
+        let containers = state.containers.iter().filter_map(|x| {
+            let (score, indices) = matcher.fuzzy_indices(x.name(), query)?;
+            let name = FormattedString::from_indices(x.name(), indices);
+            Some(self.container_to_entry(x, name, score.try_into().ok()?))
+        });
+
+        // Untagged images are left out, they're only layers of others
+        let images = state.images.iter().flat_map(|image| {
+            image.repo_tags.iter().flatten().filter_map(|tag| {
+                let (score, indices) = matcher.fuzzy_indices(tag, query)?;
+                let name = FormattedString::from_indices(tag, indices);
+                Some(self.image_to_entry(image, tag, name, score.try_into().ok()?))
+            })
+        });
+
+        containers.chain(images).collect()
+    }
+}
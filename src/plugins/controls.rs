@@ -0,0 +1,225 @@
+use std::process::Command;
+use std::sync::Arc;
+
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use parking_lot::Mutex;
+use serde::Deserialize;
+use tokio_util::sync::CancellationToken;
+
+use crate::interface::{
+    ActionResult, ActionType, Context, Entry, EntryAction, EntryIcon, FormattedString, Plugin,
+    parse_options,
+};
+
+const SINK: &str = "@DEFAULT_AUDIO_SINK@";
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+struct ControlsConfig {
+    /// In percent.
+    step: u32,
+}
+
+impl Default for ControlsConfig {
+    fn default() -> Self {
+        Self { step: 10 }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Control {
+    Volume = 0,
+    Brightness = 1,
+}
+
+/// The current values, indexed by control, `None` where it can't be controlled here.
+type Values = Arc<Mutex<[Option<u32>; 2]>>;
+
+impl Control {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Volume => "Volume",
+            Self::Brightness => "Brightness",
+        }
+    }
+
+    fn icon(self) -> &'static str {
+        match self {
+            Self::Volume => "audio-volume-medium",
+            Self::Brightness => "display-brightness",
+        }
+    }
+
+    /// The current value in percent, `None` if it can't be controlled here.
+    fn get(self) -> Option<u32> {
+        match self {
+            Self::Volume => {
+                // Like `Volume: 0.45 [MUTED]`
+                let output = run("wpctl", &["get-volume", SINK])?;
+                let volume = output.split_whitespace().nth(1)?.parse::<f64>().ok()?;
+                Some((volume * 100.0).round() as u32)
+            }
+            Self::Brightness => {
+                // Like `intel_backlight,backlight,120,50%,240`
+                let output = run("brightnessctl", &["-m"])?;
+                output
+                    .lines()
+                    .next()?
+                    .split(',')
+                    .nth(3)?
+                    .trim_end_matches('%')
+                    .parse()
+                    .ok()
+            }
+        }
+    }
+
+    /// Takes the value as understood by the tools, like `10%+` or `50%`.
+    fn set(self, value: String, values: Values) -> Box<ActionType> {
+        Box::new(move |_| {
+            let result = match self {
+                // Limited to 100%, past that it distorts
+                Self::Volume => run("wpctl", &["set-volume", "-l", "1.0", SINK, &value]),
+                Self::Brightness => run("brightnessctl", &["set", &value]),
+            };
+
+            // The launcher stays open to keep adjusting, showing the new value
+            match result {
+                Some(_) => {
+                    values.lock()[self as usize] = self.get();
+                    ActionResult::Refresh
+                }
+                None => ActionResult::Error,
+            }
+        })
+    }
+}
+
+fn run(command: &str, args: &[&str]) -> Option<String> {
+    match Command::new(command).args(args).output() {
+        Ok(x) if x.status.success() => Some(String::from_utf8_lossy(&x.stdout).into_owned()),
+        Ok(_) => None,
+        Err(error) => {
+            tracing::debug!(?error, command, "Failed to run");
+            None
+        }
+    }
+}
+
+/// Entries adjusting the volume and the brightness, like `vol 40` or `brightness`.
+#[derive(Debug)]
+pub struct Controls {
+    step: u32,
+    values: Values,
+}
+
+impl Controls {
+    pub fn new(_: &Context, options: &toml::Table) -> Self {
+        let config: ControlsConfig = parse_options(options);
+
+        Self {
+            step: config.step.clamp(1, 100),
+            values: Values::default(),
+        }
+    }
+
+    fn entry(
+        &self,
+        control: Control,
+        current: u32,
+        name: String,
+        value: String,
+        score: i64,
+    ) -> Option<Entry> {
+        Some(Entry {
+            name: FormattedString::plain(name),
+            tag: Some(FormattedString::plain(format!("{current}%"))),
+            icon: EntryIcon::Name(control.icon().into()),
+            actions: vec![EntryAction {
+                icon: control.icon().into(),
+                name: "Set".into(),
+                function: control.set(value, self.values.clone()),
+                ..Default::default()
+            }],
+            score: score.try_into().ok()?,
+            ..Default::default()
+        })
+    }
+}
+
+impl Plugin for Controls {
+    fn open(&mut self) {
+        // The tools are too slow to ask on every keystroke
+        let values = self.values.clone();
+        std::thread::spawn(move || {
+            let loaded = [Control::Volume, Control::Brightness].map(Control::get);
+            *values.lock() = loaded;
+        });
+    }
+
+    fn name(&self) -> &str {
+        "Controls"
+    }
+
+    fn icon(&self) -> Option<&str> {
+        Some("preferences-system")
+    }
+
+    fn search(&self, query: &str, _: &Context, _: &CancellationToken) -> Vec<Entry> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        // A number at the end sets the value directly
+        let (words, target) = match query.rsplit_once(' ') {
+            Some((words, value)) => match value.trim_end_matches('%').parse::<u32>() {
+                Ok(value) => (words.trim(), Some(value.min(100))),
+                Err(_) => (query, None),
+            },
+            None => (query, None),
+        };
+
+        let matcher = SkimMatcherV2::default().ignore_case();
+        let values = *self.values.lock();
+        let mut entries = Vec::new();
+
+        for control in [Control::Volume, Control::Brightness] {
+            let Some(score) = matcher.fuzzy_match(control.name(), words) else {
+                continue;
+            };
+            let Some(current) = values[control as usize] else {
+                continue;
+            };
+
+            if let Some(target) = target {
+                entries.extend(self.entry(
+                    control,
+                    current,
+                    format!("{} {target}%", control.name()),
+                    format!("{target}%"),
+                    score,
+                ));
+                continue;
+            }
+
+            entries.extend(self.entry(
+                control,
+                current,
+                format!("{} +{}%", control.name(), self.step),
+                format!("{}%+", self.step),
+                score,
+            ));
+            entries.extend(self.entry(
+                control,
+                current,
+                format!("{} −{}%", control.name(), self.step),
+                format!("{}%-", self.step),
+                score,
+            ));
+        }
+
+        entries
+    }
+}
@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+
+use chrono::{Datelike, Local, NaiveDate, NaiveDateTime, NaiveTime};
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use serde::Deserialize;
+use tokio_util::sync::CancellationToken;
+
+use crate::interface::{
+    Context, Entry, EntryAction, EntryIcon, FormattedString, Plugin, parse_options,
+};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct CountdownConfig {
+    /// Names mapped to `2025-07-01`, `2025-07-01 18:30`, or `07-01` for every year.
+    events: HashMap<String, String>,
+}
+
+#[derive(Debug)]
+struct Event {
+    name: String,
+    date: NaiveDate,
+    time: Option<NaiveTime>,
+    yearly: bool,
+}
+
+impl Event {
+    fn parse(name: String, value: &str) -> Option<Self> {
+        let value = value.trim();
+
+        if let Ok(x) = NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M") {
+            return Some(Self {
+                name,
+                date: x.date(),
+                time: Some(x.time()),
+                yearly: false,
+            });
+        }
+
+        if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+            return Some(Self {
+                name,
+                date,
+                time: None,
+                yearly: false,
+            });
+        }
+
+        // The year is only there to make it a valid date, a leap one so the 29th of February works
+        let date = NaiveDate::parse_from_str(&format!("2000-{value}"), "%Y-%m-%d").ok()?;
+        Some(Self {
+            name,
+            date,
+            time: None,
+            yearly: true,
+        })
+    }
+
+    /// The date the event falls on, the next one for yearly events.
+    fn next(&self, today: NaiveDate) -> NaiveDate {
+        if !self.yearly {
+            return self.date;
+        }
+
+        (today.year()..)
+            .filter_map(|year| self.date.with_year(year))
+            .find(|x| *x >= today)
+            .unwrap_or(self.date)
+    }
+}
+
+fn plural(count: i64, unit: &str) -> String {
+    if count == 1 {
+        format!("1 {unit}")
+    } else {
+        format!("{count} {unit}s")
+    }
+}
+
+/// Like "in 12 days" or "3 days ago", in hours for timed events that are close.
+fn describe(event: &Event, date: NaiveDate) -> String {
+    let now = Local::now().naive_local();
+
+    if let Some(time) = event.time {
+        let minutes = (date.and_time(time) - now).num_minutes();
+        if minutes.abs() < 24 * 60 {
+            let amount = if minutes.abs() < 60 {
+                plural(minutes.abs(), "minute")
+            } else {
+                plural(minutes.abs() / 60, "hour")
+            };
+
+            return if minutes < 0 {
+                format!("{amount} ago")
+            } else {
+                format!("in {amount}")
+            };
+        }
+    }
+
+    match (date - now.date()).num_days() {
+        0 => "Today".to_owned(),
+        1 => "Tomorrow".to_owned(),
+        -1 => "Yesterday".to_owned(),
+        x if x < 0 => format!("{} ago", plural(-x, "day")),
+        x => format!("in {}", plural(x, "day")),
+    }
+}
+
+#[derive(Debug)]
+pub struct Countdown {
+    events: Vec<Event>,
+}
+
+impl Countdown {
+    pub fn new(_: &Context, options: &toml::Table) -> Self {
+        let config: CountdownConfig = parse_options(options);
+
+        let events = config
+            .events
+            .into_iter()
+            .filter_map(|(name, value)| {
+                let event = Event::parse(name, &value);
+                if event.is_none() {
+                    tracing::warn!(%value, "Invalid countdown date");
+                }
+                event
+            })
+            .collect();
+
+        Self { events }
+    }
+
+    fn event_to_entry(&self, event: &Event, name: FormattedString, score: u64) -> Entry {
+        let today = Local::now().date_naive();
+        let date = event.next(today);
+        let remaining = describe(event, date);
+
+        let mut formatted = date.format("%a %-d %b %Y").to_string();
+        if let Some(time) = event.time {
+            formatted.push_str(&time.format(" %H:%M").to_string());
+        }
+
+        Entry {
+            name,
+            tag: event.yearly.then(|| FormattedString::plain("Yearly")),
+            description: Some(FormattedString::plain(format!(
+                "{remaining}  ·  {formatted}"
+            ))),
+            icon: EntryIcon::Name("x-office-calendar".into()),
+            actions: vec![EntryAction {
+                icon: "edit-copy".into(),
+                name: "Copy".into(),
+                function: EntryAction::copy(remaining),
+                ..Default::default()
+            }],
+            score,
+            ..Default::default()
+        }
+    }
+}
+
+impl Plugin for Countdown {
+    fn name(&self) -> &str {
+        "Countdowns"
+    }
+
+    fn icon(&self) -> Option<&str> {
+        Some("x-office-calendar")
+    }
+
+    fn search(&self, query: &str, _: &Context, _: &CancellationToken) -> Vec<Entry> {
+        let today = Local::now().date_naive();
+
+        // `days` lists every event, the soonest first
+        if let Some(rest) = query.strip_prefix("days")
+            && rest.trim().is_empty()
+        {
+            let mut events = self.events.iter().collect::<Vec<_>>();
+            events.sort_by_key(|x| {
+                let days = (x.next(today) - today).num_days();
+                (days < 0, days.abs())
+            });
+
+            return events
+                .into_iter()
+                .map(|x| self.event_to_entry(x, FormattedString::plain(&x.name), 0))
+                .collect();
+        }
+
+        // Keeps the events off the home grid
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let matcher = SkimMatcherV2::default();
+        self.events
+            .iter()
+            .filter_map(|x| {
+                let (score, indices) = matcher.fuzzy_indices(&x.name, query)?;
+                let name = FormattedString::from_indices(&x.name, indices);
+                Some(self.event_to_entry(x, name, score.try_into().ok()?))
+            })
+            .collect()
+    }
+}
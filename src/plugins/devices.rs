@@ -0,0 +1,193 @@
+use std::process::Command;
+use std::sync::Arc;
+
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use gtk::gdk::{Key, ModifierType};
+use parking_lot::Mutex;
+use serde::Deserialize;
+use tokio_util::sync::CancellationToken;
+
+use crate::interface::{
+    ActionResult, ActionType, Context, Entry, EntryAction, EntryIcon, FormattedString, Plugin,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Sink,
+    Source,
+}
+
+impl Kind {
+    fn noun(self) -> &'static str {
+        match self {
+            Self::Sink => "sink",
+            Self::Source => "source",
+        }
+    }
+
+    /// The streams that play to or record from devices of this kind.
+    fn streams(self) -> &'static str {
+        match self {
+            Self::Sink => "sink-inputs",
+            Self::Source => "source-outputs",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Device {
+    name: String,
+    description: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Stream {
+    index: u32,
+}
+
+#[derive(Debug)]
+struct Listed {
+    kind: Kind,
+    device: Device,
+    default: bool,
+}
+
+fn pactl(args: &[&str]) -> Option<String> {
+    match Command::new("pactl").args(args).output() {
+        Ok(x) if x.status.success() => Some(String::from_utf8_lossy(&x.stdout).into_owned()),
+        Ok(_) => None,
+        Err(error) => {
+            tracing::debug!(?error, "Failed to run pactl");
+            None
+        }
+    }
+}
+
+fn list(kind: Kind) -> Vec<Listed> {
+    let default = pactl(&[&format!("get-default-{}", kind.noun())])
+        .map(|x| x.trim().to_owned())
+        .unwrap_or_default();
+
+    pactl(&["--format=json", "list", &format!("{}s", kind.noun())])
+        .and_then(|x| serde_json::from_str::<Vec<Device>>(&x).ok())
+        .unwrap_or_default()
+        .into_iter()
+        // What plays on a sink, not an actual input
+        .filter(|x| !x.name.ends_with(".monitor"))
+        .map(|device| Listed {
+            kind,
+            default: device.name == default,
+            device,
+        })
+        .collect()
+}
+
+/// Makes the device the default one, optionally moving the streams already playing or recording.
+fn set_default(kind: Kind, name: String, move_streams: bool) -> Box<ActionType> {
+    Box::new(move |_| {
+        if pactl(&[&format!("set-default-{}", kind.noun()), &name]).is_none() {
+            return ActionResult::Error;
+        }
+
+        if move_streams {
+            let streams = pactl(&["--format=json", "list", kind.streams()])
+                .and_then(|x| serde_json::from_str::<Vec<Stream>>(&x).ok())
+                .unwrap_or_default();
+
+            let command = match kind {
+                Kind::Sink => "move-sink-input",
+                Kind::Source => "move-source-output",
+            };
+            for stream in streams {
+                pactl(&[command, &stream.index.to_string(), &name]);
+            }
+        }
+
+        ActionResult::Ok
+    })
+}
+
+/// Switches the default output and input devices of PipeWire or PulseAudio.
+#[derive(Debug)]
+pub struct Devices {
+    /// Read when the launcher is shown, since devices come and go.
+    devices: Arc<Mutex<Vec<Listed>>>,
+}
+
+impl Devices {
+    pub fn new(_: &Context) -> Self {
+        Self {
+            devices: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+}
+
+impl Plugin for Devices {
+    fn open(&mut self) {
+        // pactl is slow to start, and hangs while the server restarts
+        let devices = self.devices.clone();
+        std::thread::spawn(move || {
+            let mut loaded = list(Kind::Sink);
+            loaded.extend(list(Kind::Source));
+            *devices.lock() = loaded;
+        });
+    }
+
+    fn name(&self) -> &str {
+        "Audio devices"
+    }
+
+    fn icon(&self) -> Option<&str> {
+        Some("audio-card")
+    }
+
+    fn search(&self, query: &str, _: &Context, _: &CancellationToken) -> Vec<Entry> {
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let matcher = SkimMatcherV2::default().ignore_case();
+
+        self.devices
+            .lock()
+            .iter()
+            .filter_map(|x| {
+                let (kind, icon, words) = match x.kind {
+                    Kind::Sink => ("Output", "audio-speakers", "output speakers headphones"),
+                    Kind::Source => ("Input", "audio-input-microphone", "input microphone"),
+                };
+
+                let score = matcher.fuzzy_match(
+                    &format!("{} {words} audio device", x.device.description),
+                    query,
+                )?;
+
+                Some(Entry {
+                    name: FormattedString::plain(&x.device.description),
+                    tag: x.default.then(|| FormattedString::plain("Default")),
+                    description: Some(FormattedString::plain(kind)),
+                    icon: EntryIcon::Name(icon.into()),
+                    actions: vec![
+                        EntryAction {
+                            icon: icon.into(),
+                            name: "Set as default".into(),
+                            function: set_default(x.kind, x.device.name.clone(), false),
+                            ..Default::default()
+                        },
+                        EntryAction {
+                            icon: "media-playlist-shuffle".into(),
+                            name: "Set as default and move streams".into(),
+                            function: set_default(x.kind, x.device.name.clone(), true),
+                            key: Key::Return,
+                            modifier: ModifierType::SHIFT_MASK,
+                        },
+                    ],
+                    id: format!("devices:{}", x.device.name),
+                    score: score.try_into().ok()?,
+                    ..Default::default()
+                })
+            })
+            .collect()
+    }
+}
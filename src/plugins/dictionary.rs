@@ -0,0 +1,264 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::sync::mpsc::RecvTimeoutError;
+use std::time::{Duration, Instant};
+
+use gtk::gdk::{Key, ModifierType};
+use serde::Deserialize;
+use tokio_util::sync::CancellationToken;
+
+use crate::interface::{
+    Context, Entry, EntryAction, EntryIcon, FormattedString, Plugin, parse_options,
+};
+
+/// Requests are only sent once typing pauses for this long.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+struct DictionaryConfig {
+    /// A dictd server, the local one by default.
+    server: String,
+    port: u16,
+    /// The database to look in, `*` for all of them or `wn` for WordNet.
+    database: String,
+}
+
+impl Default for DictionaryConfig {
+    fn default() -> Self {
+        Self {
+            server: "localhost".into(),
+            port: 2628,
+            database: "*".into(),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Definition {
+    word: String,
+    database: String,
+    text: String,
+}
+
+/// Reads a status line, like `150 2 definitions retrieved`.
+fn status(reader: &mut impl BufRead) -> std::io::Result<(u16, String)> {
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+
+    let (code, rest) = line
+        .trim_end()
+        .split_once(' ')
+        .unwrap_or((line.trim_end(), ""));
+    let code = code
+        .parse()
+        .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, line.clone()))?;
+
+    Ok((code, rest.to_owned()))
+}
+
+/// The fields of a status line, quoted or not.
+fn fields(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut rest = line.trim();
+
+    while !rest.is_empty() {
+        if let Some(quoted) = rest.strip_prefix('"') {
+            let (field, next) = quoted.split_once('"').unwrap_or((quoted, ""));
+            fields.push(field.to_owned());
+            rest = next.trim_start();
+        } else {
+            let (field, next) = rest.split_once(' ').unwrap_or((rest, ""));
+            fields.push(field.to_owned());
+            rest = next.trim_start();
+        }
+    }
+
+    fields
+}
+
+/// Looks the word up with the DICT protocol, see RFC 2229.
+fn define(config: &DictionaryConfig, word: &str) -> std::io::Result<Vec<Definition>> {
+    let stream = TcpStream::connect((config.server.as_str(), config.port))?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+
+    // The banner
+    status(&mut reader)?;
+
+    let word = word.replace(['"', '\\'], "");
+    write!(writer, "DEFINE {} \"{word}\"\r\n", config.database)?;
+
+    let mut definitions = Vec::new();
+    loop {
+        let (code, rest) = status(&mut reader)?;
+        match code {
+            // The count of definitions, then each of them
+            150 => {}
+            151 => {
+                let fields = fields(&rest);
+                let mut text = Vec::new();
+                loop {
+                    let mut line = String::new();
+                    if reader.read_line(&mut line)? == 0 {
+                        break;
+                    }
+
+                    let line = line.trim_end_matches(['\r', '\n']);
+                    if line == "." {
+                        break;
+                    }
+                    // Lines starting with a dot have it doubled
+                    text.push(line.strip_prefix('.').unwrap_or(line).to_owned());
+                }
+
+                definitions.push(Definition {
+                    word: fields.first().cloned().unwrap_or_else(|| word.clone()),
+                    database: fields
+                        .get(2)
+                        .or_else(|| fields.get(1))
+                        .cloned()
+                        .unwrap_or_default(),
+                    text: text.join("\n"),
+                });
+            }
+            // No match
+            552 => break,
+            250 => break,
+            _ => {
+                tracing::debug!(code, ?rest, "Unexpected dictd response");
+                break;
+            }
+        }
+    }
+
+    let _ = writer.write_all(b"QUIT\r\n");
+    Ok(definitions)
+}
+
+/// The definition on a single line, without the headword and the `{links}` of WordNet.
+fn summary(definition: &Definition) -> String {
+    let mut lines = definition.text.lines().map(str::trim).peekable();
+    if lines
+        .peek()
+        .is_some_and(|x| x.eq_ignore_ascii_case(&definition.word))
+    {
+        lines.next();
+    }
+
+    lines
+        .filter(|x| !x.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace(['{', '}'], "")
+}
+
+/// Definitions of words from a dictd server, like one serving WordNet locally.
+#[derive(Debug)]
+pub struct Dictionary {
+    config: DictionaryConfig,
+}
+
+impl Dictionary {
+    pub fn new(_: &Context, options: &toml::Table) -> Self {
+        Self {
+            config: parse_options(options),
+        }
+    }
+
+    /// Runs the blocking lookup on its own thread, giving up as soon as the token is cancelled.
+    fn lookup(&self, word: &str, token: &CancellationToken) -> Vec<Definition> {
+        let config = self.config.clone();
+        let word = word.to_owned();
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let result = define(&config, &word);
+            if let Err(error) = &result {
+                tracing::warn!(?error, server = %config.server, "Dictionary lookup failed");
+            }
+            let _ = sender.send(result.unwrap_or_default());
+        });
+
+        loop {
+            if token.is_cancelled() {
+                return Vec::new();
+            }
+
+            match receiver.recv_timeout(Duration::from_millis(20)) {
+                Ok(result) => return result,
+                Err(RecvTimeoutError::Disconnected) => return Vec::new(),
+                Err(RecvTimeoutError::Timeout) => {}
+            }
+        }
+    }
+}
+
+impl Plugin for Dictionary {
+    fn name(&self) -> &str {
+        "Dictionary"
+    }
+
+    fn icon(&self) -> Option<&str> {
+        Some("accessories-dictionary")
+    }
+
+    fn search(&self, query: &str, _: &Context, token: &CancellationToken) -> Vec<Entry> {
+        let query = query
+            .strip_prefix("def")
+            .filter(|x| x.is_empty() || x.starts_with(char::is_whitespace))
+            .unwrap_or(query)
+            .trim();
+
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        // Every keystroke cancels the previous search, so only the last one gets through
+        let start = Instant::now();
+        while start.elapsed() < DEBOUNCE {
+            if token.is_cancelled() {
+                return Vec::new();
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+
+        let definitions = self.lookup(query, token);
+        let count = definitions.len() as u64;
+
+        definitions
+            .into_iter()
+            .enumerate()
+            .map(|(i, x)| {
+                let summary = summary(&x);
+                Entry {
+                    name: FormattedString::plain(&x.word),
+                    tag: Some(FormattedString::plain(&x.database)),
+                    description: Some(FormattedString::plain(&summary)),
+                    icon: EntryIcon::Name("accessories-dictionary".into()),
+                    actions: vec![
+                        EntryAction {
+                            icon: "edit-copy".into(),
+                            name: "Copy".into(),
+                            function: EntryAction::copy(summary),
+                            ..Default::default()
+                        },
+                        EntryAction {
+                            icon: "edit-copy".into(),
+                            name: "Copy full text".into(),
+                            function: EntryAction::copy(x.text),
+                            key: Key::c,
+                            modifier: ModifierType::CONTROL_MASK | ModifierType::SHIFT_MASK,
+                        },
+                    ],
+                    // In the order of the server
+                    score: count - i as u64,
+                    ..Default::default()
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,89 @@
+use std::io::BufRead;
+
+use fuzzy_matcher::FuzzyMatcher;
+use itertools::Itertools;
+use tokio_util::sync::CancellationToken;
+
+use crate::interface::{
+    ActionType, Context, Entry, EntryAction, EntryIcon, FormattedString, Plugin,
+};
+
+#[derive(Debug)]
+pub struct Dmenu {
+    lines: Vec<String>,
+}
+
+impl Dmenu {
+    pub fn new(_: &Context) -> Self {
+        let lines = std::io::stdin()
+            .lock()
+            .lines()
+            .map_while(Result::ok)
+            .filter(|x| !x.is_empty())
+            .collect();
+
+        Self { lines }
+    }
+}
+
+fn select(line: String) -> Box<ActionType> {
+    Box::new(move |_| {
+        println!("{line}");
+        std::process::exit(0)
+    })
+}
+
+fn line_to_entry(line: &str, indices: Vec<usize>, score: u64) -> Entry {
+    Entry {
+        name: FormattedString::from_indices(line, indices),
+        icon: EntryIcon::Name("view-list".into()),
+        actions: vec![EntryAction {
+            icon: "object-select".into(),
+            name: "Select".into(),
+            function: select(line.to_owned()),
+            ..Default::default()
+        }],
+        score,
+        ..Default::default()
+    }
+}
+
+impl Plugin for Dmenu {
+    fn name(&self) -> &str {
+        "dmenu"
+    }
+
+    fn icon(&self) -> Option<&str> {
+        Some("view-list")
+    }
+
+    fn search(&self, query: &str, _: &Context, _: &CancellationToken) -> Vec<Entry> {
+        if query.is_empty() {
+            return self
+                .lines
+                .iter()
+                .map(|x| line_to_entry(x, vec![], 0))
+                .collect();
+        }
+
+        let matcher = fuzzy_matcher::skim::SkimMatcherV2::default().smart_case();
+        let mut entries = self
+            .lines
+            .iter()
+            .filter_map(|x| {
+                let (score, indices) = matcher.fuzzy_indices(x, query)?;
+                Some(line_to_entry(x, indices, score.try_into().ok()?))
+            })
+            .sorted_by(|a, b| b.score.cmp(&a.score))
+            .collect_vec();
+
+        if !self.lines.iter().any(|x| x == query) {
+            entries.push(Entry {
+                description: Some("Custom input".into()),
+                ..line_to_entry(query, vec![], 0)
+            });
+        }
+
+        entries
+    }
+}
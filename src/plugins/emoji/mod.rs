@@ -6,13 +6,18 @@ use std::ops::Range;
 use gtk::gdk::Key;
 use gtk::gdk::ModifierType;
 use itertools::Itertools;
+use tokio_util::sync::CancellationToken;
 
+use crate::favorites::{self, FAVORITE_SCORE};
 use crate::interface::EntryAction;
 use crate::plugins::emoji::data::EMOJIS;
 use crate::plugins::emoji::data::GROUPS;
 use crate::plugins::emoji::data::SUBGROUPS;
 
-use crate::interface::{Context, Entry, EntryIcon, FormatStyle, FormattedString, Plugin};
+use crate::interface::{Context, Entry, EntryIcon, FormatStyle, FormattedString, Plugin, Preview};
+
+/// The kind of the favorites of this plugin.
+const FAVORITES: &str = "emoji";
 
 #[derive(Debug)]
 pub struct Emojis {}
@@ -23,6 +28,47 @@ impl Emojis {
     }
 }
 
+/// The starred emojis, in the order they were added, above the recent ones.
+fn favorite_entries(context: &Context) -> Vec<Entry> {
+    let favorites = context.favorites.get(FAVORITES);
+
+    favorites
+        .iter()
+        .enumerate()
+        .filter_map(|(i, codepoints)| {
+            let emoji = EMOJIS
+                .iter()
+                .find(|x| x.variants.iter().any(|x| x.codepoints == codepoints))?;
+
+            Some(Entry {
+                name: FormattedString::plain(titlecase(emoji.description)),
+                tag: Some(FormattedString::from_style(
+                    "Favorite",
+                    FormatStyle::Special,
+                )),
+                description: Some(FormattedString::plain(format!(
+                    "{}  ·  {}",
+                    titlecase(GROUPS[emoji.group]),
+                    titlecase(SUBGROUPS[emoji.subgroup])
+                ))),
+                icon: EntryIcon::Text(codepoints.clone()),
+                actions: vec![
+                    EntryAction {
+                        icon: "edit-copy".into(),
+                        name: "Copy".into(),
+                        function: EntryAction::copy(codepoints),
+                        ..Default::default()
+                    },
+                    favorites::action(FAVORITES, codepoints, context),
+                ],
+                id: codepoints.clone(),
+                score: FAVORITE_SCORE - i as u64,
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
 pub fn titlecase(s: &str) -> String {
     let mut last = ' ';
     let mut result = String::new();
@@ -48,7 +94,7 @@ impl Plugin for Emojis {
         Some("face-smile-big")
     }
 
-    fn search(&self, query: &str, _: &Context) -> Vec<Entry> {
+    fn search(&self, query: &str, context: &Context, _: &CancellationToken) -> Vec<Entry> {
         if query.chars().next().is_some_and(|x| x >= '\x7F') {
             for emoji in EMOJIS {
                 if emoji.variants.iter().any(|x| x.codepoints == query) {
@@ -60,12 +106,17 @@ impl Plugin for Emojis {
                             // tag: Some(FormattedString::plain(x.version.to_string())),
                             description: Some(FormattedString::plain(x.attributes.join(", "))),
                             icon: EntryIcon::Text(x.codepoints.to_owned()),
-                            actions: vec![EntryAction {
-                                icon: "edit-copy".into(),
-                                name: "Copy".into(),
-                                function: EntryAction::copy(x.codepoints),
-                                ..Default::default()
-                            }],
+                            actions: vec![
+                                EntryAction {
+                                    icon: "edit-copy".into(),
+                                    name: "Copy".into(),
+                                    function: EntryAction::copy(x.codepoints),
+                                    ..Default::default()
+                                },
+                                favorites::action(FAVORITES, x.codepoints, context),
+                            ],
+                            id: x.codepoints.to_owned(),
+                            score: context.frecency.score(x.codepoints),
                             ..Default::default()
                         })
                         .collect();
@@ -74,6 +125,11 @@ impl Plugin for Emojis {
         }
 
         let query = query.to_lowercase();
+        let favorites = if query.is_empty() {
+            favorite_entries(context)
+        } else {
+            Vec::new()
+        };
 
         let mut emojis = EMOJIS
             .iter()
@@ -132,6 +188,9 @@ impl Plugin for Emojis {
             }
         }
 
+        // Shown once, as a favorite
+        emojis.retain(|(x, ..)| !favorites.iter().any(|y| y.id == x.variants[0].codepoints));
+
         emojis
             .into_iter()
             .map(|(x, ranges, group_ranges, tag_ranges, subgroup_ranges)| {
@@ -204,19 +263,35 @@ impl Plugin for Emojis {
                                 modifier: ModifierType::SHIFT_MASK,
                                 function: EntryAction::write(first.codepoints),
                             },
+                            favorites::action(FAVORITES, first.codepoints, context),
                         ]
                     } else {
-                        vec![EntryAction {
-                            icon: "edit-copy".into(),
-                            name: "Copy".into(),
-                            function: EntryAction::copy(first.codepoints),
-                            ..Default::default()
-                        }]
+                        vec![
+                            EntryAction {
+                                icon: "edit-copy".into(),
+                                name: "Copy".into(),
+                                function: EntryAction::copy(first.codepoints),
+                                ..Default::default()
+                            },
+                            favorites::action(FAVORITES, first.codepoints, context),
+                        ]
                     },
+                    id: first.codepoints.to_owned(),
+                    score: context.frecency.score(first.codepoints),
                     ..Default::default()
                 }
             })
-            .take(128)
+            .chain(favorites)
             .collect()
     }
+
+    fn preview(&self, entry: &Entry) -> Option<Preview> {
+        let emoji = EMOJIS
+            .iter()
+            .find(|x| x.variants.iter().any(|x| x.codepoints == entry.id))?;
+
+        Some(Preview::Symbols(
+            emoji.variants.iter().map(|x| x.codepoints).join(" "),
+        ))
+    }
 }
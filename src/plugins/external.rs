@@ -0,0 +1,289 @@
+//! Plugins implemented by user provided executables.
+//!
+//! The executable is spawned once and kept alive, receiving one JSON request
+//! per line on its standard input and answering with one JSON response per
+//! line on its standard output:
+//!
+//! ```text
+//! > {"method":"search","query":"foo"}
+//! < {"entries":[{"id":"1","name":"Foo","actions":[{"id":"open","name":"Open"}]}]}
+//! > {"method":"activate","entry":"1","action":"open"}
+//! < {"result":"ok"}
+//! ```
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::Arc;
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tokio_util::sync::CancellationToken;
+
+use crate::interface::{
+    ActionResult, ActionType, Context, Entry, EntryAction, EntryIcon, FormattedString, Plugin,
+    parse_options,
+};
+
+/// How long the process has to answer, after which it's restarted.
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+struct ExternalConfig {
+    name: String,
+    icon: Option<String>,
+    command: String,
+    args: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub(crate) enum Request<'a> {
+    Search { query: &'a str },
+    Activate { entry: &'a str, action: &'a str },
+}
+
+#[derive(Deserialize)]
+pub(crate) struct SearchResponse {
+    #[serde(default)]
+    pub entries: Vec<ExternalEntry>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct ExternalEntry {
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub tag: Option<String>,
+    pub icon: Option<String>,
+    #[serde(default)]
+    pub actions: Vec<ExternalAction>,
+    #[serde(default)]
+    pub score: u64,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct ExternalAction {
+    pub id: String,
+    pub name: String,
+    pub icon: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct ActivateResponse {
+    pub result: ExternalResult,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ExternalResult {
+    Ok,
+    Error,
+    SetText(String),
+    Refresh,
+}
+
+impl From<ExternalResult> for ActionResult {
+    fn from(value: ExternalResult) -> Self {
+        match value {
+            ExternalResult::Ok => ActionResult::Ok,
+            ExternalResult::Error => ActionResult::Error,
+            ExternalResult::SetText(text) => ActionResult::SetText(text),
+            ExternalResult::Refresh => ActionResult::Refresh,
+        }
+    }
+}
+
+impl ExternalEntry {
+    /// Converts the entry, building its actions with `activate`, which
+    /// receives the entry and action ids.
+    pub(crate) fn into_entry(self, activate: impl Fn(String, String) -> Box<ActionType>) -> Entry {
+        let icon = |icon: Option<String>| match icon {
+            Some(icon) if icon.starts_with('/') => EntryIcon::Path(PathBuf::from(icon)),
+            icon => EntryIcon::from(icon),
+        };
+
+        Entry {
+            name: FormattedString::plain(self.name),
+            tag: self.tag.map(FormattedString::plain),
+            description: self.description.map(FormattedString::plain),
+            icon: icon(self.icon),
+            actions: self
+                .actions
+                .into_iter()
+                .map(|action| EntryAction {
+                    icon: action.icon.unwrap_or("system-run".into()),
+                    name: action.name,
+                    function: activate(self.id.clone(), action.id),
+                    ..Default::default()
+                })
+                .collect(),
+            id: self.id,
+            score: self.score,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Process {
+    child: Child,
+    stdin: ChildStdin,
+    /// The lines of its standard output, read on their own thread so they can be waited on.
+    lines: Receiver<io::Result<String>>,
+}
+
+impl Process {
+    fn spawn(config: &ExternalConfig) -> io::Result<Self> {
+        let mut child = Command::new(expanduser::expanduser(&config.command)?)
+            .args(&config.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+
+        let stdin = child.stdin.take().ok_or(io::ErrorKind::BrokenPipe)?;
+        let stdout = child.stdout.take().ok_or(io::ErrorKind::BrokenPipe)?;
+
+        // Ends once the process is killed and its output closed
+        let (sender, lines) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(Self {
+            child,
+            stdin,
+            lines,
+        })
+    }
+
+    fn request<T: DeserializeOwned>(&mut self, request: &Request) -> io::Result<T> {
+        let mut line = serde_json::to_string(request)?;
+        line.push('\n');
+        self.stdin.write_all(line.as_bytes())?;
+        self.stdin.flush()?;
+
+        let response = match self.lines.recv_timeout(TIMEOUT) {
+            Ok(line) => line?,
+            Err(RecvTimeoutError::Timeout) => return Err(io::ErrorKind::TimedOut.into()),
+            Err(RecvTimeoutError::Disconnected) => return Err(io::ErrorKind::UnexpectedEof.into()),
+        };
+
+        Ok(serde_json::from_str(&response)?)
+    }
+}
+
+impl Drop for Process {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[derive(Debug)]
+struct Connection {
+    config: ExternalConfig,
+    process: Option<Process>,
+}
+
+impl Connection {
+    /// Sends a request, (re)spawning the process if it isn't running.
+    fn request<T: DeserializeOwned>(&mut self, request: &Request) -> Option<T> {
+        if self.process.is_none() {
+            match Process::spawn(&self.config) {
+                Ok(process) => self.process = Some(process),
+                Err(error) => {
+                    tracing::error!(
+                        command = ?self.config.command,
+                        ?error,
+                        "Failed to start plugin"
+                    );
+                    return None;
+                }
+            }
+        }
+
+        match self.process.as_mut()?.request(request) {
+            Ok(response) => Some(response),
+            Err(error) => {
+                // Killed when dropped, a late answer would be taken for the next one
+                tracing::error!(command = ?self.config.command, ?error, "Plugin failed");
+                self.process = None;
+                None
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct External {
+    name: String,
+    icon: Option<String>,
+    connection: Arc<Mutex<Connection>>,
+}
+
+impl External {
+    pub fn new(_: &Context, options: &toml::Table) -> Self {
+        let config: ExternalConfig = parse_options(options);
+
+        Self {
+            name: config.name.clone(),
+            icon: config.icon.clone(),
+            connection: Arc::new(Mutex::new(Connection {
+                config,
+                process: None,
+            })),
+        }
+    }
+}
+
+fn activate(connection: Arc<Mutex<Connection>>, entry: String, action: String) -> Box<ActionType> {
+    Box::new(move |_| {
+        let request = Request::Activate {
+            entry: &entry,
+            action: &action,
+        };
+
+        match connection.lock().request::<ActivateResponse>(&request) {
+            Some(response) => response.result.into(),
+            None => ActionResult::Error,
+        }
+    })
+}
+
+impl Plugin for External {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn icon(&self) -> Option<&str> {
+        self.icon.as_deref()
+    }
+
+    fn search(&self, query: &str, _: &Context, _: &CancellationToken) -> Vec<Entry> {
+        let Some(response) = self
+            .connection
+            .lock()
+            .request::<SearchResponse>(&Request::Search { query })
+        else {
+            return Vec::new();
+        };
+
+        response
+            .entries
+            .into_iter()
+            .map(|entry| {
+                entry.into_entry(|entry, action| activate(self.connection.clone(), entry, action))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,244 @@
+mod store;
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use gtk::gdk::{Key, ModifierType};
+use parking_lot::Mutex;
+use serde::Deserialize;
+use tokio_util::sync::CancellationToken;
+
+use crate::interface::{
+    ActionResult, ActionType, Context, Entry, EntryAction, EntryIcon, FormattedString, Plugin,
+    parse_options,
+};
+use crate::plugins::feeds::store::{Article, Store};
+
+#[derive(Debug, Clone, Deserialize)]
+struct Feed {
+    url: String,
+    /// Shown as the tag of the articles, defaults to the title of the feed.
+    name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+struct FeedsConfig {
+    feeds: Vec<Feed>,
+    /// Minutes between fetches, checked when the launcher is shown.
+    refresh: i64,
+}
+
+impl Default for FeedsConfig {
+    fn default() -> Self {
+        Self {
+            feeds: Vec::new(),
+            refresh: 30,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Feeds {
+    feeds: Vec<Feed>,
+    refresh: i64,
+    handler: Option<String>,
+    store: Arc<Mutex<Store>>,
+    fetching: Arc<AtomicBool>,
+}
+
+impl Feeds {
+    pub fn new(context: &Context, options: &toml::Table) -> Self {
+        let config: FeedsConfig = parse_options(options);
+
+        Self {
+            feeds: config.feeds,
+            refresh: config.refresh,
+            handler: context
+                .apps
+                .default_for_mime(&"x-scheme-handler/https".parse().unwrap())
+                .map(|x| x.id.clone()),
+            store: Arc::new(Mutex::new(Store::load())),
+            fetching: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    fn article_to_entry(&self, article: &Article) -> Entry {
+        let time = chrono::DateTime::from_timestamp(article.time, 0)
+            .map(|x| {
+                x.with_timezone(&chrono::Local)
+                    .format("%d %b %H:%M")
+                    .to_string()
+            })
+            .unwrap_or_default();
+
+        let mut actions = Vec::new();
+
+        if let Some(link) = &article.link {
+            actions.push(EntryAction {
+                icon: "web-browser".into(),
+                name: "Open".into(),
+                function: self.open_article(article.id.clone(), link.clone()),
+                ..Default::default()
+            });
+            actions.push(EntryAction {
+                icon: "edit-copy".into(),
+                name: "Copy link".into(),
+                function: EntryAction::copy(link),
+                key: Key::c,
+                modifier: ModifierType::CONTROL_MASK,
+            });
+        }
+
+        actions.push(EntryAction {
+            icon: "mail-mark-read".into(),
+            name: "Mark as read".into(),
+            function: mark_read(self.store.clone(), article.id.clone()),
+            key: Key::m,
+            modifier: ModifierType::CONTROL_MASK,
+        });
+        actions.push(EntryAction {
+            icon: "mail-mark-read".into(),
+            name: "Mark all as read".into(),
+            function: mark_all_read(self.store.clone()),
+            key: Key::m,
+            modifier: ModifierType::CONTROL_MASK | ModifierType::SHIFT_MASK,
+        });
+
+        Entry {
+            name: FormattedString::plain(&article.title),
+            tag: Some(FormattedString::plain(&article.source)),
+            description: Some(FormattedString::plain(match &article.link {
+                Some(link) => format!("{time}  ·  {link}"),
+                None => time,
+            })),
+            icon: EntryIcon::Name("application-rss+xml".into()),
+            actions,
+            id: article.id.clone(),
+            ..Default::default()
+        }
+    }
+
+    fn open_article(&self, id: String, link: String) -> Box<ActionType> {
+        let store = self.store.clone();
+        let open = match &self.handler {
+            Some(handler) => EntryAction::open(handler.clone(), None, Some(PathBuf::from(link))),
+            None => EntryAction::command("xdg-open".into(), vec![link], None),
+        };
+
+        EntryAction::map_result(open, move |result| {
+            if !matches!(result, ActionResult::Error) {
+                store.lock().mark_read(&id);
+            }
+            result
+        })
+    }
+
+    /// Fetches every feed on a background thread, unless it was done recently.
+    fn fetch(&self) {
+        let now = chrono::Utc::now().timestamp();
+        if let Some(fetched) = self.store.lock().fetched
+            && now - fetched < self.refresh * 60
+        {
+            return;
+        }
+
+        if self.feeds.is_empty() || self.fetching.swap(true, Ordering::AcqRel) {
+            return;
+        }
+
+        let feeds = self.feeds.clone();
+        let store = self.store.clone();
+        let fetching = self.fetching.clone();
+
+        std::thread::spawn(move || {
+            for feed in feeds {
+                match fetch_feed(&feed) {
+                    Ok((source, articles)) => store.lock().update(&source, articles),
+                    Err(error) => tracing::warn!(url = %feed.url, %error, "Failed to fetch feed"),
+                }
+            }
+
+            store.lock().fetched = Some(now);
+            fetching.store(false, Ordering::Release);
+        });
+    }
+}
+
+fn fetch_feed(feed: &Feed) -> Result<(String, Vec<Article>), Box<dyn std::error::Error>> {
+    let content = reqwest::blocking::get(&feed.url)?
+        .error_for_status()?
+        .bytes()?;
+    let parsed = feed_rs::parser::parse(content.as_ref())?;
+
+    let source = feed
+        .name
+        .clone()
+        .or_else(|| parsed.title.map(|x| x.content))
+        .unwrap_or_else(|| feed.url.clone());
+
+    let articles = parsed
+        .entries
+        .into_iter()
+        .map(|x| Article {
+            // Ids are only unique within a feed
+            id: format!("{}\n{}", feed.url, x.id),
+            source: source.clone(),
+            title: x
+                .title
+                .map(|x| x.content.trim().to_owned())
+                .unwrap_or_default(),
+            link: x.links.into_iter().next().map(|x| x.href),
+            time: x
+                .published
+                .or(x.updated)
+                .map(|x| x.timestamp())
+                .unwrap_or_default(),
+        })
+        .collect();
+
+    Ok((source, articles))
+}
+
+fn mark_read(store: Arc<Mutex<Store>>, id: String) -> Box<ActionType> {
+    Box::new(move |_| {
+        store.lock().mark_read(&id);
+        ActionResult::Refresh
+    })
+}
+
+fn mark_all_read(store: Arc<Mutex<Store>>) -> Box<ActionType> {
+    Box::new(move |_| {
+        store.lock().mark_all_read();
+        ActionResult::Refresh
+    })
+}
+
+impl Plugin for Feeds {
+    fn open(&mut self) {
+        self.fetch();
+    }
+
+    fn name(&self) -> &str {
+        "Feeds"
+    }
+
+    fn icon(&self) -> Option<&str> {
+        Some("application-rss+xml")
+    }
+
+    fn search(&self, query: &str, _: &Context, _: &CancellationToken) -> Vec<Entry> {
+        let query = query.strip_prefix("news").unwrap_or(query).trim();
+        let query = query.to_lowercase();
+
+        self.store
+            .lock()
+            .unread()
+            .filter(|x| {
+                x.title.to_lowercase().contains(&query) || x.source.to_lowercase().contains(&query)
+            })
+            .map(|x| self.article_to_entry(x))
+            .collect()
+    }
+}
@@ -0,0 +1,121 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use xdg::BaseDirectories;
+
+/// Only the newest articles of each feed are kept.
+const MAX_ARTICLES: usize = 50;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Article {
+    pub id: String,
+    pub source: String,
+    pub title: String,
+    pub link: Option<String>,
+    pub time: i64,
+}
+
+#[derive(Debug)]
+pub struct Store {
+    cache: Option<PathBuf>,
+    state: Option<PathBuf>,
+    /// Newest first.
+    pub articles: Vec<Article>,
+    pub read: HashSet<String>,
+    /// When the feeds were last fetched, not persisted so they are fetched on startup.
+    pub fetched: Option<i64>,
+}
+
+impl Store {
+    pub fn load() -> Self {
+        let directories = BaseDirectories::with_prefix("jogger").ok();
+        let cache = directories
+            .as_ref()
+            .and_then(|x| x.place_cache_file("feeds.json").ok());
+        let state = directories
+            .as_ref()
+            .and_then(|x| x.place_data_file("feeds.json").ok());
+
+        let articles = cache
+            .as_ref()
+            .and_then(|x| std::fs::read_to_string(x).ok())
+            .and_then(|x| serde_json::from_str(&x).ok())
+            .unwrap_or_default();
+        let read = state
+            .as_ref()
+            .and_then(|x| std::fs::read_to_string(x).ok())
+            .and_then(|x| serde_json::from_str(&x).ok())
+            .unwrap_or_default();
+
+        Self {
+            cache,
+            state,
+            articles,
+            read,
+            fetched: None,
+        }
+    }
+
+    fn save_articles(&self) {
+        let Some(path) = &self.cache else {
+            return;
+        };
+
+        match serde_json::to_string(&self.articles) {
+            Ok(content) => {
+                if let Err(error) = std::fs::write(path, content) {
+                    tracing::error!(?error, "Failed to save feeds cache");
+                }
+            }
+            Err(error) => tracing::error!(?error, "Failed to serialize feeds cache"),
+        }
+    }
+
+    fn save_read(&self) {
+        let Some(path) = &self.state else {
+            return;
+        };
+
+        match serde_json::to_string(&self.read) {
+            Ok(content) => {
+                if let Err(error) = std::fs::write(path, content) {
+                    tracing::error!(?error, "Failed to save read articles");
+                }
+            }
+            Err(error) => tracing::error!(?error, "Failed to serialize read articles"),
+        }
+    }
+
+    /// Replaces the articles of a source with freshly fetched ones.
+    pub fn update(&mut self, source: &str, mut articles: Vec<Article>) {
+        articles.sort_by_key(|x| -x.time);
+        articles.truncate(MAX_ARTICLES);
+
+        self.articles.retain(|x| x.source != source);
+        self.articles.extend(articles);
+        self.articles.sort_by_key(|x| -x.time);
+
+        // Forget the read state of articles that left the feeds
+        let ids = self.articles.iter().map(|x| &x.id).collect::<HashSet<_>>();
+        self.read.retain(|x| ids.contains(x));
+
+        self.save_articles();
+        self.save_read();
+    }
+
+    pub fn unread(&self) -> impl Iterator<Item = &Article> {
+        self.articles.iter().filter(|x| !self.read.contains(&x.id))
+    }
+
+    pub fn mark_read(&mut self, id: &str) {
+        if self.read.insert(id.to_owned()) {
+            self.save_read();
+        }
+    }
+
+    pub fn mark_all_read(&mut self) {
+        self.read.extend(self.articles.iter().map(|x| x.id.clone()));
+        self.save_read();
+    }
+}
@@ -1,19 +1,100 @@
-use std::{fmt::Debug, fs::DirEntry, ops::Range, os::unix::fs::MetadataExt, path::Path};
+use std::{
+    fmt::Debug,
+    fs::DirEntry,
+    io::Read,
+    ops::Range,
+    os::unix::fs::MetadataExt,
+    path::{Path, PathBuf},
+};
 
 use gtk::gdk::{Key, ModifierType};
+use gtk::gio::prelude::FileExt;
+use gtk::gio::{self, Cancellable, FileCopyFlags, FileQueryInfoFlags};
 use itertools::Itertools;
+use tokio_util::sync::CancellationToken;
 
 use crate::{
-    interface::{Context, Entry, EntryAction, EntryIcon, FormatStyle, FormattedString, Plugin},
+    interface::{
+        ActionResult, ActionType, Context, Entry, EntryAction, EntryIcon, FormatStyle,
+        FormattedString, Plugin, Preview,
+    },
     plugins::applications::DesktopEntry,
     xdg_database::XdgAppDatabase,
 };
 
+const PREVIEW_BYTES: u64 = 16 * 1024;
+const IMAGE_EXTENSIONS: [&str; 8] = ["png", "jpg", "jpeg", "gif", "webp", "svg", "bmp", "avif"];
+
 #[derive(Debug)]
 pub struct Files {
     home_dir: String,
 }
 
+fn trash(path: PathBuf) -> Box<ActionType> {
+    Box::new(
+        move |_| match gio::File::for_path(&path).trash(None::<&Cancellable>) {
+            Ok(()) => ActionResult::Ok,
+            Err(error) => {
+                tracing::warn!(%error, ?path, "Failed to move the file to the trash");
+                ActionResult::Error
+            }
+        },
+    )
+}
+
+/// Moves the file last trashed from the path back there.
+fn restore(path: PathBuf) -> Box<ActionType> {
+    Box::new(move |_| {
+        let restore = || {
+            let trash = gio::File::for_uri("trash:///");
+            let info = trash
+                .enumerate_children(
+                    "standard::name,trash::orig-path,trash::deletion-date",
+                    FileQueryInfoFlags::NONE,
+                    None::<&Cancellable>,
+                )?
+                .filter_map(Result::ok)
+                .filter(|x| {
+                    x.attribute_byte_string("trash::orig-path")
+                        .is_some_and(|x| Path::new(x.as_str()) == path)
+                })
+                .max_by_key(|x| x.attribute_string("trash::deletion-date"))
+                .ok_or_else(|| {
+                    gtk::glib::Error::new(gio::IOErrorEnum::NotFound, "Not in the trash")
+                })?;
+
+            trash.child(info.name()).move_(
+                &gio::File::for_path(&path),
+                FileCopyFlags::NONE,
+                None::<&Cancellable>,
+                None,
+            )
+        };
+
+        match restore() {
+            Ok(()) => ActionResult::Refresh,
+            Err(error) => {
+                tracing::warn!(%error, ?path, "Failed to restore the file from the trash");
+                ActionResult::Error
+            }
+        }
+    })
+}
+
+fn trash_action(path: &Path, name: &str) -> EntryAction {
+    EntryAction {
+        icon: "user-trash".into(),
+        name: "Move to trash".into(),
+        function: EntryAction::undoable(
+            format!("Moved {name} to the trash"),
+            trash(path.to_owned()),
+            restore(path.to_owned()),
+        ),
+        key: Key::Delete,
+        modifier: ModifierType::CONTROL_MASK,
+    }
+}
+
 fn reduce_tilde(path: &Path, home_dir: &str) -> String {
     let path = path.to_string_lossy();
     match path.strip_prefix(home_dir) {
@@ -32,8 +113,10 @@ impl Files {
     fn search_inner(
         &self,
         query: &str,
-        app_database: &XdgAppDatabase,
+        context: &Context,
+        token: &CancellationToken,
     ) -> std::io::Result<Vec<Entry>> {
+        let app_database = &context.apps;
         let query = if query.starts_with('~') && !query.starts_with("~/") {
             &("~/".to_owned() + &query[1..])
         } else {
@@ -104,6 +187,7 @@ impl Files {
                     .chain(
                         std::fs::read_dir(&path)?
                             .flatten()
+                            .take_while(|_| !token.is_cancelled())
                             .flat_map(|x| self.file_to_entry(app_database, file_manager, x, None))
                             .take(255)
                             .sorted_by_cached_key(|x| x.name.clone()),
@@ -126,6 +210,7 @@ impl Files {
 
             return Ok(std::fs::read_dir(path)?
                 .flatten()
+                .take_while(|_| !token.is_cancelled())
                 .filter_map(move |x| {
                     let name = x.file_name();
                     let name = name.to_string_lossy();
@@ -136,6 +221,10 @@ impl Files {
                 .flat_map(|(x, range)| {
                     self.file_to_entry(app_database, file_manager, x, Some(range))
                 })
+                .map(|mut x| {
+                    x.score = context.frecency.score(&x.id);
+                    x
+                })
                 .take(255)
                 .collect());
         }
@@ -253,6 +342,19 @@ impl Files {
                     modifier: ModifierType::CONTROL_MASK,
                 });
 
+                vec.push(EntryAction {
+                    icon: "system-search".into(),
+                    name: "Search contents".into(),
+                    function: EntryAction::write(format!(
+                        "grep {}/ ",
+                        reduce_tilde(&path, &self.home_dir)
+                    )),
+                    key: Key::g,
+                    modifier: ModifierType::CONTROL_MASK,
+                });
+
+                vec.push(trash_action(&path, &name));
+
                 vec
             } else {
                 let mut vec = Vec::new();
@@ -303,8 +405,11 @@ impl Files {
                     modifier: ModifierType::NO_MODIFIER_MASK,
                 });
 
+                vec.push(trash_action(&path, &name));
+
                 vec
             },
+            id: path.to_string_lossy().into_owned(),
             drag_file: Some(path),
             ..Default::default()
         })
@@ -320,7 +425,47 @@ impl Plugin for Files {
         Some("system-file-manager")
     }
 
-    fn search(&self, query: &str, context: &Context) -> Vec<Entry> {
-        self.search_inner(query, &context.apps).unwrap_or_default()
+    fn search(&self, query: &str, context: &Context, token: &CancellationToken) -> Vec<Entry> {
+        self.search_inner(query, context, token).unwrap_or_default()
+    }
+
+    fn preview(&self, entry: &Entry) -> Option<Preview> {
+        let path = entry.drag_file.as_ref()?;
+
+        if path.is_dir() {
+            let names = std::fs::read_dir(path)
+                .ok()?
+                .flatten()
+                .map(|x| x.file_name().to_string_lossy().into_owned())
+                .filter(|x| !x.starts_with('.'))
+                .sorted()
+                .take(100)
+                .join("\n");
+
+            return Some(Preview::Code(names));
+        }
+
+        let extension = path.extension()?.to_string_lossy().to_lowercase();
+        if IMAGE_EXTENSIONS.contains(&extension.as_str()) {
+            return Some(Preview::Image(path.clone()));
+        }
+
+        let mut content = Vec::new();
+        std::fs::File::open(path)
+            .ok()?
+            .take(PREVIEW_BYTES)
+            .read_to_end(&mut content)
+            .ok()?;
+
+        // The read may end in the middle of a character, binary files fail earlier
+        let text = match std::str::from_utf8(&content) {
+            Ok(text) => text,
+            Err(error) if error.error_len().is_none() => {
+                std::str::from_utf8(&content[..error.valid_up_to()]).ok()?
+            }
+            Err(_) => return None,
+        };
+
+        (!text.contains('\0')).then(|| Preview::Code(text.to_owned()))
     }
 }
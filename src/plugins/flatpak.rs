@@ -0,0 +1,273 @@
+use std::process::Command;
+use std::sync::Arc;
+
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use gtk::gdk::{Key, ModifierType};
+use parking_lot::Mutex;
+use tokio_util::sync::CancellationToken;
+
+use crate::interface::{
+    ActionResult, ActionType, Context, Entry, EntryAction, EntryIcon, FormattedString, Plugin,
+};
+
+#[derive(Debug, Clone)]
+struct Installed {
+    id: String,
+    name: String,
+    version: String,
+    origin: String,
+}
+
+#[derive(Debug)]
+struct Available {
+    id: String,
+    name: String,
+    description: String,
+    remote: String,
+}
+
+/// Runs flatpak with the given columns, splitting the tab separated output.
+fn flatpak(args: &[&str]) -> Vec<Vec<String>> {
+    match Command::new("flatpak").args(args).output() {
+        Ok(x) if x.status.success() => String::from_utf8_lossy(&x.stdout)
+            .lines()
+            .map(|x| x.split('\t').map(str::to_owned).collect())
+            .collect(),
+        Ok(x) => {
+            let error = String::from_utf8_lossy(&x.stderr);
+            tracing::debug!(%error, ?args, "flatpak failed");
+            Vec::new()
+        }
+        Err(error) => {
+            tracing::debug!(?error, "Failed to run flatpak");
+            Vec::new()
+        }
+    }
+}
+
+fn list_installed() -> Vec<Installed> {
+    flatpak(&["list", "--app", "--columns=application,name,version,origin"])
+        .into_iter()
+        .filter_map(|x| match x.as_slice() {
+            [id, name, version, origin] => Some(Installed {
+                id: id.clone(),
+                name: name.clone(),
+                version: version.clone(),
+                origin: origin.clone(),
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Searches the appstream data of the remotes, as last updated.
+fn search_remotes(query: &str) -> Vec<Available> {
+    flatpak(&[
+        "search",
+        "--columns=application,name,description,remotes",
+        query,
+    ])
+    .into_iter()
+    .filter_map(|x| match x.as_slice() {
+        [id, name, description, remotes] => Some(Available {
+            id: id.clone(),
+            name: name.clone(),
+            description: description.clone(),
+            // Like "flathub,flathub-beta"
+            remote: remotes.split(',').next().unwrap_or_default().to_owned(),
+        }),
+        _ => None,
+    })
+    .collect()
+}
+
+/// The installed Flatpak apps, and those of the remotes to install.
+#[derive(Debug)]
+pub struct Flatpak {
+    installed: Arc<Mutex<Vec<Installed>>>,
+}
+
+impl Flatpak {
+    pub fn new(_: &Context) -> Self {
+        Self {
+            installed: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Uninstalls the app, then reloads what's shown.
+    fn uninstall(&self, id: String) -> Box<ActionType> {
+        let installed = self.installed.clone();
+
+        Box::new(move |_| {
+            match Command::new("flatpak")
+                .args(["uninstall", "--noninteractive", &id])
+                .output()
+            {
+                Ok(x) if x.status.success() => {
+                    *installed.lock() = list_installed();
+                    ActionResult::Refresh
+                }
+                Ok(x) => {
+                    let error = String::from_utf8_lossy(&x.stderr);
+                    tracing::warn!(%error, %id, "Failed to uninstall the Flatpak");
+                    ActionResult::Error
+                }
+                Err(error) => {
+                    tracing::warn!(?error, "Failed to run flatpak");
+                    ActionResult::Error
+                }
+            }
+        })
+    }
+
+    fn installed_to_entry(
+        &self,
+        app: &Installed,
+        name: FormattedString,
+        score: u64,
+        context: &Context,
+    ) -> Entry {
+        let id = &app.id;
+        let icon = context
+            .apps
+            .app_map
+            .get(id)
+            .map_or("application-x-executable", |x| x.icon());
+
+        Entry {
+            name,
+            tag: (!app.version.is_empty()).then(|| FormattedString::plain(&app.version)),
+            description: Some(FormattedString::plain(format!("{id} · {}", app.origin))),
+            icon: EntryIcon::Name(icon.into()),
+            actions: vec![
+                EntryAction {
+                    icon: "media-playback-start".into(),
+                    name: "Run".into(),
+                    function: EntryAction::command(
+                        "flatpak".into(),
+                        vec!["run".into(), id.clone()],
+                        None,
+                    ),
+                    ..Default::default()
+                },
+                // In a terminal, to follow the download
+                EntryAction {
+                    icon: "software-update-available".into(),
+                    name: "Update".into(),
+                    function: EntryAction::launch_terminal(
+                        Some("flatpak".into()),
+                        vec!["update".into(), id.clone()],
+                        None,
+                    ),
+                    key: Key::u,
+                    modifier: ModifierType::CONTROL_MASK,
+                },
+                EntryAction {
+                    icon: "user-trash".into(),
+                    name: "Uninstall".into(),
+                    function: EntryAction::confirm(
+                        format!("Uninstall {}?", app.name),
+                        self.uninstall(id.clone()),
+                    ),
+                    key: Key::Delete,
+                    modifier: ModifierType::CONTROL_MASK,
+                },
+                EntryAction {
+                    icon: "edit-copy".into(),
+                    name: "Copy ID".into(),
+                    function: EntryAction::copy(id),
+                    key: Key::c,
+                    modifier: ModifierType::CONTROL_MASK,
+                },
+            ],
+            id: format!("flatpak:{id}"),
+            score,
+            ..Default::default()
+        }
+    }
+
+    fn available_to_entry(&self, app: &Available) -> Entry {
+        Entry {
+            name: FormattedString::plain(&app.name),
+            tag: Some(FormattedString::plain(&app.remote)),
+            description: Some(FormattedString::plain(&app.description)),
+            icon: EntryIcon::Name("system-software-install".into()),
+            actions: vec![
+                EntryAction {
+                    icon: "system-software-install".into(),
+                    name: "Install".into(),
+                    function: EntryAction::launch_terminal(
+                        Some("flatpak".into()),
+                        vec!["install".into(), app.remote.clone(), app.id.clone()],
+                        None,
+                    ),
+                    ..Default::default()
+                },
+                EntryAction {
+                    icon: "edit-copy".into(),
+                    name: "Copy ID".into(),
+                    function: EntryAction::copy(&app.id),
+                    key: Key::c,
+                    modifier: ModifierType::CONTROL_MASK,
+                },
+            ],
+            ..Default::default()
+        }
+    }
+}
+
+impl Plugin for Flatpak {
+    fn open(&mut self) {
+        let installed = self.installed.clone();
+        std::thread::spawn(move || {
+            let loaded = list_installed();
+            *installed.lock() = loaded;
+        });
+    }
+
+    fn name(&self) -> &str {
+        "Flatpak"
+    }
+
+    fn icon(&self) -> Option<&str> {
+        Some("system-software-install")
+    }
+
+    fn search(&self, query: &str, context: &Context, token: &CancellationToken) -> Vec<Entry> {
+        let query = query
+            .strip_prefix("flatpak")
+            .filter(|x| x.is_empty() || x.starts_with(char::is_whitespace))
+            .unwrap_or(query)
+            .trim();
+
+        let installed = self.installed.lock().clone();
+        let matcher = SkimMatcherV2::default().smart_case();
+        let mut entries = installed
+            .iter()
+            .filter_map(|x| {
+                if query.is_empty() {
+                    let name = FormattedString::plain(&x.name);
+                    return Some(self.installed_to_entry(x, name, 0, context));
+                }
+
+                let (score, indices) = matcher.fuzzy_indices(&x.name, query)?;
+                let name = FormattedString::from_indices(&x.name, indices);
+                Some(self.installed_to_entry(x, name, score.try_into().ok()?, context))
+            })
+            .collect::<Vec<_>>();
+
+        if query.is_empty() || token.is_cancelled() {
+            return entries;
+        }
+
+        entries.extend(
+            search_remotes(query)
+                .iter()
+                .filter(|x| !installed.iter().any(|y| y.id == x.id))
+                .map(|x| self.available_to_entry(x)),
+        );
+
+        entries
+    }
+}
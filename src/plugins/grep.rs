@@ -0,0 +1,203 @@
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use gtk::gdk::{Key, ModifierType};
+use itertools::Itertools;
+use serde::Deserialize;
+use tokio_util::sync::CancellationToken;
+
+use crate::interface::{
+    Context, Entry, EntryAction, EntryIcon, FormatStyle, FormattedString, Plugin, parse_options,
+};
+
+const MAX_RESULTS: usize = 200;
+const BATCH_SIZE: usize = 25;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct GrepConfig {
+    /// Searched when the query doesn't start with a directory, defaults to the home.
+    directory: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct Grep {
+    directory: PathBuf,
+}
+
+impl Grep {
+    pub fn new(_: &Context, options: &toml::Table) -> Self {
+        let config: GrepConfig = parse_options(options);
+
+        #[allow(deprecated)]
+        let directory = config
+            .directory
+            .and_then(|x| expanduser::expanduser(x).ok())
+            .or_else(std::env::home_dir)
+            .unwrap_or_else(|| PathBuf::from("/"));
+
+        Self { directory }
+    }
+
+    /// Splits a leading directory off the query, if there is one.
+    fn split_query<'a>(&self, query: &'a str) -> (PathBuf, &'a str) {
+        if let Some((first, rest)) = query.split_once(char::is_whitespace)
+            && (first.starts_with('~') || first.starts_with('/'))
+            && let Ok(path) = expanduser::expanduser(first)
+            && path.is_dir()
+        {
+            return (path, rest.trim_start());
+        }
+
+        (self.directory.clone(), query)
+    }
+}
+
+struct Match {
+    path: PathBuf,
+    line: usize,
+    text: String,
+}
+
+/// Runs ripgrep, handing out the matches in small batches as they are printed.
+fn run_ripgrep(
+    directory: &Path,
+    pattern: &str,
+    token: &CancellationToken,
+    mut on_batch: impl FnMut(Vec<Match>),
+) -> std::io::Result<()> {
+    let mut child = Command::new("rg")
+        .args([
+            "--null",
+            "--line-number",
+            "--no-heading",
+            "--color=never",
+            "--smart-case",
+            "--max-columns=300",
+            "--max-count=20",
+            "--regexp",
+        ])
+        .arg(pattern)
+        .arg(directory)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()?;
+
+    let stdout = child.stdout.take().ok_or(std::io::ErrorKind::BrokenPipe)?;
+    let matches = BufReader::new(stdout)
+        .lines()
+        .take_while(|_| !token.is_cancelled())
+        .map_while(Result::ok)
+        .filter_map(|x| {
+            let (path, rest) = x.split_once('\0')?;
+            let (line, text) = rest.split_once(':')?;
+
+            Some(Match {
+                path: PathBuf::from(path),
+                line: line.parse().ok()?,
+                text: text.trim().to_owned(),
+            })
+        })
+        .take(MAX_RESULTS);
+
+    for batch in &matches.chunks(BATCH_SIZE) {
+        on_batch(batch.collect());
+    }
+
+    let _ = child.kill();
+    let _ = child.wait();
+
+    Ok(())
+}
+
+impl Plugin for Grep {
+    fn name(&self) -> &str {
+        "Search file contents"
+    }
+
+    fn icon(&self) -> Option<&str> {
+        Some("system-search")
+    }
+
+    fn search(&self, query: &str, context: &Context, token: &CancellationToken) -> Vec<Entry> {
+        let mut entries = Vec::new();
+        self.search_stream(query, context, token, &mut |x| entries.extend(x));
+        entries
+    }
+
+    fn search_stream(
+        &self,
+        query: &str,
+        context: &Context,
+        token: &CancellationToken,
+        sink: &mut dyn FnMut(Vec<Entry>),
+    ) {
+        let query = query.strip_prefix("grep ").unwrap_or(query).trim_start();
+        let (directory, pattern) = self.split_query(query);
+
+        if pattern.len() < 2 {
+            return;
+        }
+
+        let result = run_ripgrep(&directory, pattern, token, |matches| {
+            sink(
+                matches
+                    .into_iter()
+                    .map(|x| match_to_entry(x, &directory, context))
+                    .collect(),
+            )
+        });
+
+        if let Err(error) = result {
+            tracing::error!(?error, "Failed to run ripgrep");
+        }
+    }
+}
+
+fn match_to_entry(x: Match, directory: &Path, context: &Context) -> Entry {
+    let relative = x.path.strip_prefix(directory).unwrap_or(&x.path);
+    let mime = context.apps.guess(&x.path).mime;
+
+    let mut actions = vec![EntryAction {
+        icon: "document-edit".into(),
+        name: "Open at line".into(),
+        function: EntryAction::edit(x.path.clone(), Some(x.line)),
+        ..Default::default()
+    }];
+
+    if let Some(app) = context.apps.find_associations(mime).into_iter().next() {
+        actions.push(EntryAction {
+            icon: app.icon().into(),
+            name: format!("Open with {}", app.name),
+            function: EntryAction::open(app.id.clone(), None, Some(x.path.clone())),
+            key: Key::Return,
+            modifier: ModifierType::SHIFT_MASK,
+        });
+    }
+
+    actions.push(EntryAction {
+        icon: "edit-copy".into(),
+        name: "Copy location".into(),
+        function: EntryAction::copy(format!("{}:{}", x.path.display(), x.line)),
+        key: Key::c,
+        modifier: ModifierType::CONTROL_MASK,
+    });
+
+    Entry {
+        name: FormattedString::from_style(x.text, FormatStyle::Monospace),
+        tag: Some(FormattedString::plain(format!(
+            "{}:{}",
+            relative
+                .file_name()
+                .map(|x| x.to_string_lossy())
+                .unwrap_or_default(),
+            x.line
+        ))),
+        description: Some(FormattedString::plain(relative.to_string_lossy())),
+        icon: EntryIcon::Name(context.apps.mime_db.lookup_icon_name(mime)),
+        actions,
+        drag_file: Some(x.path),
+        ..Default::default()
+    }
+}
@@ -0,0 +1,202 @@
+use std::collections::{BTreeSet, HashMap};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use chrono::{Days, Local, NaiveDate};
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use parking_lot::Mutex;
+use serde::Deserialize;
+use tokio_util::sync::CancellationToken;
+use xdg::BaseDirectories;
+
+use crate::interface::{
+    ActionResult, ActionType, Context, Entry, EntryAction, EntryIcon, FormattedString, Plugin,
+    parse_options,
+};
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+struct HabitsConfig {
+    habits: Vec<String>,
+}
+
+impl Default for HabitsConfig {
+    fn default() -> Self {
+        Self {
+            habits: vec!["exercise".into(), "read".into(), "meditate".into()],
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Log {
+    path: Option<PathBuf>,
+    /// The days each habit was done, by name.
+    days: HashMap<String, BTreeSet<NaiveDate>>,
+}
+
+impl Log {
+    fn load() -> Self {
+        let path = BaseDirectories::with_prefix("jogger")
+            .ok()
+            .and_then(|x| x.place_data_file("habits.json").ok());
+
+        let days = path
+            .as_ref()
+            .and_then(|x| std::fs::read_to_string(x).ok())
+            .and_then(|x| serde_json::from_str::<HashMap<String, BTreeSet<String>>>(&x).ok())
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(habit, days)| {
+                let days = days
+                    .iter()
+                    .filter_map(|x| NaiveDate::parse_from_str(x, "%Y-%m-%d").ok())
+                    .collect();
+                (habit, days)
+            })
+            .collect();
+
+        Self { path, days }
+    }
+
+    fn record(&mut self, habit: &str, day: NaiveDate) {
+        self.days.entry(habit.to_owned()).or_default().insert(day);
+
+        let Some(path) = &self.path else {
+            return;
+        };
+
+        let days = self
+            .days
+            .iter()
+            .map(|(habit, days)| {
+                let days = days
+                    .iter()
+                    .map(|x| x.format("%Y-%m-%d").to_string())
+                    .collect::<Vec<_>>();
+                (habit, days)
+            })
+            .collect::<HashMap<_, _>>();
+
+        match serde_json::to_string(&days) {
+            Ok(content) => {
+                if let Err(error) = std::fs::write(path, content) {
+                    tracing::error!(?error, "Failed to save habits");
+                }
+            }
+            Err(error) => tracing::error!(?error, "Failed to serialize habits"),
+        }
+    }
+
+    fn done(&self, habit: &str, day: NaiveDate) -> bool {
+        self.days.get(habit).is_some_and(|x| x.contains(&day))
+    }
+
+    /// The days in a row the habit was done, still going if it wasn't done yet today.
+    fn streak(&self, habit: &str, today: NaiveDate) -> u32 {
+        let mut day = if self.done(habit, today) {
+            today
+        } else {
+            today - Days::new(1)
+        };
+
+        let mut streak = 0;
+        while self.done(habit, day) {
+            streak += 1;
+            day = day - Days::new(1);
+        }
+        streak
+    }
+}
+
+/// Logs habits with `did exercise`, `did` alone lists the current streaks.
+#[derive(Debug)]
+pub struct Habits {
+    habits: Vec<String>,
+    log: Arc<Mutex<Log>>,
+}
+
+impl Habits {
+    pub fn new(_: &Context, options: &toml::Table) -> Self {
+        let config: HabitsConfig = parse_options(options);
+
+        Self {
+            habits: config.habits,
+            log: Arc::new(Mutex::new(Log::load())),
+        }
+    }
+
+    fn record(&self, habit: String) -> Box<ActionType> {
+        let log = self.log.clone();
+
+        Box::new(move |_| {
+            log.lock().record(&habit, Local::now().date_naive());
+            ActionResult::Ok
+        })
+    }
+
+    fn habit_to_entry(&self, habit: &str, name: FormattedString, score: u64) -> Entry {
+        let today = Local::now().date_naive();
+        let log = self.log.lock();
+        let done = log.done(habit, today);
+
+        let description = match log.streak(habit, today) {
+            0 => "No streak yet".to_owned(),
+            1 => "1 day streak".to_owned(),
+            x => format!("{x} days streak"),
+        };
+
+        Entry {
+            name,
+            tag: done.then(|| FormattedString::plain("Done today")),
+            description: Some(FormattedString::plain(description)),
+            icon: EntryIcon::Name(if done { "checkbox-checked" } else { "checkbox" }.into()),
+            actions: vec![EntryAction {
+                icon: "object-select".into(),
+                name: "Log".into(),
+                function: self.record(habit.to_owned()),
+                ..Default::default()
+            }],
+            id: format!("habits:{habit}"),
+            score,
+            ..Default::default()
+        }
+    }
+}
+
+impl Plugin for Habits {
+    fn name(&self) -> &str {
+        "Habits"
+    }
+
+    fn icon(&self) -> Option<&str> {
+        Some("checkbox-checked")
+    }
+
+    fn search(&self, query: &str, _: &Context, _: &CancellationToken) -> Vec<Entry> {
+        let query = query
+            .strip_prefix("did")
+            .filter(|x| x.is_empty() || x.starts_with(char::is_whitespace))
+            .unwrap_or(query)
+            .trim();
+
+        if query.is_empty() {
+            return self
+                .habits
+                .iter()
+                .map(|x| self.habit_to_entry(x, FormattedString::plain(x), 0))
+                .collect();
+        }
+
+        let matcher = SkimMatcherV2::default().ignore_case();
+        self.habits
+            .iter()
+            .filter_map(|x| {
+                let (score, indices) = matcher.fuzzy_indices(x, query)?;
+                let name = FormattedString::from_indices(x, indices);
+                Some(self.habit_to_entry(x, name, score.try_into().ok()?))
+            })
+            .collect()
+    }
+}
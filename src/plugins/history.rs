@@ -0,0 +1,275 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::UNIX_EPOCH;
+
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use gtk::gdk::{Key, ModifierType};
+use parking_lot::Mutex;
+use tokio_util::sync::CancellationToken;
+
+use crate::interface::{Context, Entry, EntryAction, EntryIcon, FormattedString, Plugin};
+
+#[derive(Debug, Clone)]
+struct HistoryCommand {
+    command: String,
+    shell: &'static str,
+    /// Seconds since the epoch, guessed from the order of the file when not recorded.
+    when: i64,
+}
+
+/// Zsh escapes some bytes of its history with 0x83, to be xored with 32.
+fn unmetafy(bytes: &[u8]) -> String {
+    let mut result = Vec::with_capacity(bytes.len());
+    let mut bytes = bytes.iter();
+    while let Some(&byte) = bytes.next() {
+        if byte == 0x83 {
+            if let Some(&next) = bytes.next() {
+                result.push(next ^ 32);
+            }
+        } else {
+            result.push(byte);
+        }
+    }
+
+    String::from_utf8_lossy(&result).into_owned()
+}
+
+/// The commands of the file, oldest first, with their time when recorded.
+fn parse_zsh(content: &str) -> Vec<(String, Option<i64>)> {
+    let mut commands: Vec<(String, Option<i64>)> = Vec::new();
+    let mut continued = false;
+
+    for line in content.lines() {
+        if continued && let Some((command, _)) = commands.last_mut() {
+            command.push('\n');
+            command.push_str(line);
+        } else {
+            // Like `: 1700000000:0;ls -la` with the extended format
+            let (command, when) = line
+                .strip_prefix(": ")
+                .and_then(|x| x.split_once(';'))
+                .map_or((line, None), |(meta, command)| {
+                    (command, meta.split(':').next().and_then(|x| x.parse().ok()))
+                });
+            commands.push((command.to_owned(), when));
+        }
+
+        continued = line.ends_with('\\');
+    }
+
+    commands
+}
+
+fn parse_bash(content: &str) -> Vec<(String, Option<i64>)> {
+    let mut commands = Vec::new();
+    let mut when = None;
+
+    for line in content.lines() {
+        // Written before each command when `HISTTIMEFORMAT` is set
+        if let Some(time) = line.strip_prefix('#').and_then(|x| x.parse().ok()) {
+            when = Some(time);
+        } else {
+            commands.push((line.to_owned(), when.take()));
+        }
+    }
+
+    commands
+}
+
+fn parse_fish(content: &str) -> Vec<(String, Option<i64>)> {
+    let mut commands: Vec<(String, Option<i64>)> = Vec::new();
+
+    for line in content.lines() {
+        if let Some(command) = line.strip_prefix("- cmd: ") {
+            let command = command.replace("\\n", "\n").replace("\\\\", "\\");
+            commands.push((command, None));
+        } else if let Some(when) = line.trim_start().strip_prefix("when: ")
+            && let Some((_, time)) = commands.last_mut()
+        {
+            *time = when.parse().ok();
+        }
+    }
+
+    commands
+}
+
+/// `HISTFILE` is only set inside the shell, so the default places are read.
+fn history_file(path: &str) -> Option<PathBuf> {
+    expanduser::expanduser(path).ok().filter(|x| x.exists())
+}
+
+fn read(
+    path: &Path,
+    shell: &'static str,
+    parse: fn(&str) -> Vec<(String, Option<i64>)>,
+) -> Vec<HistoryCommand> {
+    let content = match std::fs::read(path) {
+        Ok(x) if shell == "zsh" => unmetafy(&x),
+        Ok(x) => String::from_utf8_lossy(&x).into_owned(),
+        Err(error) => {
+            tracing::debug!(?error, ?path, "Failed to read the shell history");
+            return Vec::new();
+        }
+    };
+
+    let modified = std::fs::metadata(path)
+        .and_then(|x| x.modified())
+        .ok()
+        .and_then(|x| x.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |x| x.as_secs() as i64);
+
+    let commands = parse(&content);
+    let count = commands.len() as i64;
+    commands
+        .into_iter()
+        .enumerate()
+        .filter(|(_, (command, _))| !command.trim().is_empty())
+        .map(|(i, (command, when))| HistoryCommand {
+            command,
+            shell,
+            when: when.unwrap_or(modified - (count - i as i64)),
+        })
+        .collect()
+}
+
+/// The commands of every shell, the latest first and each only once.
+fn load() -> Vec<HistoryCommand> {
+    let mut commands = Vec::new();
+
+    if let Some(path) = history_file("~/.zsh_history") {
+        commands.extend(read(&path, "zsh", parse_zsh));
+    }
+    if let Some(path) = history_file("~/.bash_history") {
+        commands.extend(read(&path, "bash", parse_bash));
+    }
+    if let Some(path) = history_file("~/.local/share/fish/fish_history") {
+        commands.extend(read(&path, "fish", parse_fish));
+    }
+
+    commands.sort_by(|a, b| b.when.cmp(&a.when));
+
+    let mut seen = HashSet::new();
+    commands.retain(|x| seen.insert(x.command.clone()));
+    commands
+}
+
+/// The commands run in zsh, bash and fish, to run again or copy.
+#[derive(Debug)]
+pub struct History {
+    shell: String,
+    commands: Arc<Mutex<Vec<HistoryCommand>>>,
+}
+
+impl History {
+    pub fn new(_: &Context) -> Self {
+        Self {
+            shell: std::env::var("SHELL").unwrap_or_else(|_| "sh".into()),
+            commands: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    fn command_to_entry(
+        &self,
+        command: &HistoryCommand,
+        name: FormattedString,
+        score: u64,
+    ) -> Entry {
+        let when = chrono::DateTime::from_timestamp(command.when, 0)
+            .map(|x| {
+                x.with_timezone(&chrono::Local)
+                    .format("%d %b %H:%M")
+                    .to_string()
+            })
+            .unwrap_or_default();
+
+        Entry {
+            name,
+            tag: Some(FormattedString::plain(command.shell)),
+            description: Some(FormattedString::plain(when)),
+            icon: EntryIcon::Name("terminal".into()),
+            actions: vec![
+                EntryAction {
+                    icon: "terminal".into(),
+                    name: "Run".into(),
+                    function: EntryAction::command(
+                        "sh".into(),
+                        vec!["-c".into(), command.command.clone()],
+                        None,
+                    ),
+                    ..Default::default()
+                },
+                // The shell is kept open after, to see the output
+                EntryAction {
+                    icon: "utilities-terminal".into(),
+                    name: "Run in terminal".into(),
+                    function: EntryAction::launch_terminal(
+                        Some(self.shell.clone()),
+                        vec![
+                            "-c".into(),
+                            format!("{}; exec {}", command.command, self.shell),
+                        ],
+                        None,
+                    ),
+                    key: Key::Return,
+                    modifier: ModifierType::SHIFT_MASK,
+                },
+                EntryAction {
+                    icon: "edit-copy".into(),
+                    name: "Copy".into(),
+                    function: EntryAction::copy(&command.command),
+                    key: Key::c,
+                    modifier: ModifierType::CONTROL_MASK,
+                },
+            ],
+            score,
+            ..Default::default()
+        }
+    }
+}
+
+impl Plugin for History {
+    fn open(&mut self) {
+        let commands = self.commands.clone();
+        std::thread::spawn(move || {
+            let loaded = load();
+            *commands.lock() = loaded;
+        });
+    }
+
+    fn name(&self) -> &str {
+        "Shell history"
+    }
+
+    fn icon(&self) -> Option<&str> {
+        Some("document-open-recent")
+    }
+
+    fn search(&self, query: &str, _: &Context, token: &CancellationToken) -> Vec<Entry> {
+        let query = query
+            .strip_prefix("hist")
+            .filter(|x| x.is_empty() || x.starts_with(char::is_whitespace))
+            .unwrap_or(query)
+            .trim();
+
+        let commands = self.commands.lock();
+        if query.is_empty() {
+            return commands
+                .iter()
+                .map(|x| self.command_to_entry(x, FormattedString::plain(&x.command), 0))
+                .collect();
+        }
+
+        let matcher = SkimMatcherV2::default().smart_case();
+        commands
+            .iter()
+            .take_while(|_| !token.is_cancelled())
+            .filter_map(|x| {
+                let (score, indices) = matcher.fuzzy_indices(&x.command, query)?;
+                let name = FormattedString::from_indices(&x.command, indices);
+                Some(self.command_to_entry(x, name, score.try_into().ok()?))
+            })
+            .collect()
+    }
+}
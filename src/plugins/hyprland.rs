@@ -2,20 +2,150 @@ use freedesktop_desktop_entry::{default_paths, get_languages_from_env};
 use fuzzy_matcher::FuzzyMatcher;
 use gtk::gdk::{Key, ModifierType};
 use hyprland::{
-    data::{Clients, Workspace},
+    data::{Clients, Monitors, Workspace},
+    dispatch::{Dispatch, DispatchType, WindowIdentifier},
+    event_listener::EventListener,
     shared::{Address, HyprData, HyprDataActive, HyprDataActiveOptional},
 };
 use itertools::Itertools;
-use std::collections::HashMap;
+use parking_lot::Mutex;
+use serde::Deserialize;
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
 
 use crate::interface::{
-    Context, Entry, EntryAction, EntryIcon, FormatStyle, FormattedString, Plugin,
+    ActionResult, ActionType, Context, Entry, EntryAction, EntryIcon, FormatStyle, FormattedString,
+    Plugin, Preview, parse_options,
 };
+use crate::plugins::applications::DesktopEntry;
+
+const MAX_CLOSED: usize = 20;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct HyprlandConfig {
+    /// Also remembers windows closed outside of the launcher, from Hyprland events.
+    track_closed: bool,
+}
+
+/// The desktop entry id, name and icon of an application.
+type App = (String, Option<String>, Option<String>);
+
+/// By window class.
+type Apps = HashMap<String, App>;
+
+/// A window that was closed, offered to be opened again.
+#[derive(Debug, Clone)]
+struct Closed {
+    app_id: String,
+    name: String,
+    icon: Option<String>,
+    title: String,
+}
+
+/// Most recent first, one per application.
+type ClosedWindows = Arc<Mutex<VecDeque<Closed>>>;
+
+fn find_app<'a>(apps: &'a Apps, class: &str) -> Option<&'a App> {
+    let class = class.to_lowercase();
+    apps.get(&class)
+        .or_else(|| class.strip_suffix(".desktop").and_then(|x| apps.get(x)))
+}
+
+/// Focuses the most recently used window of the app, if it has any open.
+pub fn focus_app(app: &DesktopEntry) -> bool {
+    if std::env::var_os("HYPRLAND_INSTANCE_SIGNATURE").is_none() {
+        return false;
+    }
+
+    let Ok(clients) = Clients::get() else {
+        return false;
+    };
+
+    let classes = [Some(&app.id), app.wm_class.as_ref()];
+    let Some(client) = clients
+        .into_iter()
+        .filter(|x| {
+            classes
+                .iter()
+                .flatten()
+                .any(|y| x.class.eq_ignore_ascii_case(y))
+        })
+        .min_by_key(|x| x.focus_history_id)
+    else {
+        return false;
+    };
+
+    match Dispatch::call(DispatchType::FocusWindow(WindowIdentifier::Address(
+        client.address,
+    ))) {
+        Ok(()) => true,
+        Err(error) => {
+            tracing::warn!(?error, app = %app.id, "Failed to focus the window");
+            false
+        }
+    }
+}
+
+fn record_closed(closed: &ClosedWindows, apps: &Apps, class: &str, title: &str) {
+    let Some((app_id, name, icon)) = find_app(apps, class) else {
+        return;
+    };
+
+    let mut closed = closed.lock();
+    closed.retain(|x| x.app_id != *app_id);
+    closed.push_front(Closed {
+        app_id: app_id.clone(),
+        name: name.clone().unwrap_or_else(|| class.to_owned()),
+        icon: icon.clone(),
+        title: title.to_owned(),
+    });
+    closed.truncate(MAX_CLOSED);
+}
+
+/// Follows windows opening and closing, to know what the closed ones were.
+fn track_closed(closed: ClosedWindows, apps: Arc<Apps>) {
+    std::thread::spawn(move || {
+        let windows = Arc::new(Mutex::new(
+            Clients::get()
+                .map(|x| {
+                    x.into_iter()
+                        .map(|x| (x.address.to_string(), (x.class, x.title)))
+                        .collect::<HashMap<_, _>>()
+                })
+                .unwrap_or_default(),
+        ));
+
+        let mut listener = EventListener::new();
+
+        let opened = windows.clone();
+        listener.add_window_opened_handler(move |event| {
+            opened.lock().insert(
+                event.window_address.to_string(),
+                (event.window_class, event.window_title),
+            );
+        });
+
+        listener.add_window_closed_handler(move |address| {
+            if let Some((class, title)) = windows.lock().remove(&address.to_string()) {
+                record_closed(&closed, &apps, &class, &title);
+            }
+        });
+
+        if let Err(error) = listener.start_listener() {
+            tracing::warn!(?error, "Failed to listen to Hyprland events");
+        }
+    });
+}
 
 #[derive(Debug)]
 pub struct Hyprland {
-    entries: HashMap<String, (Option<String>, Option<String>)>,
+    apps: Arc<Apps>,
     clients: Vec<HyprlandClient>,
+    closed: ClosedWindows,
 }
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
@@ -32,13 +162,61 @@ struct HyprlandClient {
     app_name: Option<String>,
     address: Address,
     workspace: i32,
+    workspace_name: String,
+    /// The name of the monitor, like "DP-1".
+    monitor: Option<String>,
+    floating: bool,
     position: (i16, i16),
+    size: (i16, i16),
     path: Option<String>,
     selection_status: SelectionStatus,
 }
 
-impl From<&HyprlandClient> for Entry {
-    fn from(value: &HyprlandClient) -> Self {
+/// Tokens like `ws:3`, `mon:DP-1` or `is:floating`, narrowing the windows before matching.
+#[derive(Debug, Default)]
+struct Filter<'a> {
+    workspace: Option<&'a str>,
+    monitor: Option<&'a str>,
+    floating: Option<bool>,
+}
+
+impl<'a> Filter<'a> {
+    /// Splits the filters from the rest of the query.
+    fn parse(query: &'a str) -> (Self, String) {
+        let mut filter = Self::default();
+        let mut rest = Vec::new();
+
+        for word in query.split_whitespace() {
+            match word.split_once(':') {
+                Some(("ws", x)) if !x.is_empty() => filter.workspace = Some(x),
+                Some(("mon", x)) if !x.is_empty() => filter.monitor = Some(x),
+                Some(("is", "floating")) => filter.floating = Some(true),
+                Some(("is", "tiled")) => filter.floating = Some(false),
+                _ => rest.push(word),
+            }
+        }
+
+        (filter, rest.join(" "))
+    }
+
+    fn is_empty(&self) -> bool {
+        self.workspace.is_none() && self.monitor.is_none() && self.floating.is_none()
+    }
+
+    fn matches(&self, client: &HyprlandClient) -> bool {
+        self.workspace.is_none_or(|x| {
+            x == client.workspace.to_string() || x.eq_ignore_ascii_case(&client.workspace_name)
+        }) && self.monitor.is_none_or(|x| {
+            client
+                .monitor
+                .as_ref()
+                .is_some_and(|y| x.eq_ignore_ascii_case(y))
+        }) && self.floating.is_none_or(|x| x == client.floating)
+    }
+}
+
+impl Hyprland {
+    fn client_to_entry(&self, value: &HyprlandClient) -> Entry {
         Entry {
             name: FormattedString::from_styles(match value.selection_status {
                 SelectionStatus::None => vec![(&value.title, None)],
@@ -105,14 +283,9 @@ impl From<&HyprlandClient> for Entry {
                 EntryAction {
                     icon: "window-close".into(),
                     name: "Close window".into(),
-                    function: EntryAction::command(
-                        "hyprctl".into(),
-                        vec![
-                            "dispatch".into(),
-                            "closewindow".into(),
-                            format!("address:{}", value.address),
-                        ],
-                        None,
+                    function: EntryAction::confirm(
+                        format!("Close \"{}\"?", value.title),
+                        self.close(value),
                     ),
                     key: Key::q,
                     modifier: ModifierType::CONTROL_MASK,
@@ -122,14 +295,68 @@ impl From<&HyprlandClient> for Entry {
             ..Default::default()
         }
     }
-}
 
-impl Hyprland {
-    pub fn new(_: &Context) -> Self {
+    /// Closes the window, remembering it to be reopened, right away with undo too.
+    fn close(&self, value: &HyprlandClient) -> Box<ActionType> {
+        let close = EntryAction::command(
+            "hyprctl".into(),
+            vec![
+                "dispatch".into(),
+                "closewindow".into(),
+                format!("address:{}", value.address),
+            ],
+            None,
+        );
+        let closed = self.closed.clone();
+        let apps = self.apps.clone();
+        let class = value.class.clone();
+        let title = value.title.clone();
+
+        let reopen = find_app(&self.apps, &value.class).map(|(id, name, _)| {
+            let message = format!("Closed {}", name.as_deref().unwrap_or(&value.class));
+            (message, id.clone())
+        });
+
+        let function = EntryAction::map_result(close, move |result| {
+            if !matches!(result, ActionResult::Error) {
+                record_closed(&closed, &apps, &class, &title);
+            }
+            result
+        });
+
+        match reopen {
+            Some((message, id)) => {
+                EntryAction::undoable(message, function, EntryAction::open(id, None, None))
+            }
+            None => function,
+        }
+    }
+
+    fn closed_to_entry(&self, value: &Closed, score: u64) -> Entry {
+        Entry {
+            name: FormattedString::plain(format!("Reopen {}", value.name)),
+            tag: Some(FormattedString::plain("Closed")),
+            description: Some(FormattedString::plain(&value.title)),
+            icon: EntryIcon::Name(value.icon.clone().unwrap_or("image-missing".to_owned())),
+            actions: vec![EntryAction {
+                icon: "edit-undo".into(),
+                name: "Reopen".into(),
+                function: EntryAction::open(value.app_id.clone(), None, None),
+                ..Default::default()
+            }],
+            score,
+            ..Default::default()
+        }
+    }
+
+    pub fn new(_: &Context, options: &toml::Table) -> Self {
+        let config: HyprlandConfig = parse_options(options);
+
         let locales = get_languages_from_env();
-        let entries: HashMap<_, _> = freedesktop_desktop_entry::Iter::new(default_paths())
+        let apps: Apps = freedesktop_desktop_entry::Iter::new(default_paths())
             .entries(Some(&locales))
             .flat_map(|entry| {
+                let id = entry.id().to_owned();
                 let name = entry.name(&locales).map(|x| x.into_owned());
                 let icon = entry.icon().map(|x| x.to_owned());
                 [
@@ -137,21 +364,26 @@ impl Hyprland {
                         .path
                         .file_stem()
                         .and_then(|x| x.to_str())
-                        .map(|x| (x.to_lowercase(), (name.clone(), icon.clone()))),
+                        .map(|x| (x.to_lowercase(), (id.clone(), name.clone(), icon.clone()))),
                     entry
                         .startup_wm_class()
-                        .map(|x| (x.to_lowercase(), (name, icon))),
+                        .map(|x| (x.to_lowercase(), (id, name, icon))),
                 ]
             })
             .flatten()
             .collect();
 
         let mut plugin = Self {
-            entries,
+            apps: Arc::new(apps),
             clients: Vec::new(),
+            closed: Arc::new(Mutex::new(VecDeque::new())),
         };
         plugin.open();
 
+        if config.track_closed {
+            track_closed(plugin.closed.clone(), plugin.apps.clone());
+        }
+
         plugin
     }
 }
@@ -170,24 +402,22 @@ impl Plugin for Hyprland {
             .unwrap()
             .map(|x| x.address)
             .unwrap_or(Address::new(""));
+        let monitors = Monitors::get()
+            .map(|x| x.into_iter().collect_vec())
+            .unwrap_or_default();
         let clients = Clients::get().unwrap();
         let clients = clients
             .into_iter()
             .map(|x| {
-                let class = x.class.to_lowercase();
-                let data = self.entries.get(&class).or_else(|| {
-                    class
-                        .strip_suffix(".desktop")
-                        .and_then(|x| self.entries.get(x))
-                });
+                let data = find_app(&self.apps, &x.class);
 
                 let name = data
-                    .map(|(x, _)| x)
+                    .map(|(_, x, _)| x)
                     .and_then(Option::as_deref)
                     .map(str::to_owned);
 
                 let icon = data
-                    .map(|(_, x)| x)
+                    .map(|(_, _, x)| x)
                     .and_then(Option::as_deref)
                     .map(|x| x.to_string());
 
@@ -204,7 +434,14 @@ impl Plugin for Hyprland {
                     app_name: name,
                     address: x.address,
                     workspace: x.workspace.id,
+                    workspace_name: x.workspace.name,
+                    monitor: x
+                        .monitor
+                        .and_then(|id| monitors.iter().find(|x| x.id == id))
+                        .map(|x| x.name.clone()),
+                    floating: x.floating,
                     position: x.at,
+                    size: x.size,
                     path: icon,
                 }
             })
@@ -217,12 +454,15 @@ impl Plugin for Hyprland {
         Some("window_list")
     }
 
-    fn search(&self, query: &str, _: &Context) -> Vec<Entry> {
+    fn search(&self, query: &str, _: &Context, _: &CancellationToken) -> Vec<Entry> {
+        let (filter, query) = Filter::parse(query);
+        let query = query.as_str();
+        let clients = self.clients.iter().filter(|x| filter.matches(x));
+
         if query.is_empty() {
-            self.clients
-                .iter()
+            clients
                 .sorted_by_cached_key(|x| (x.selection_status, x.workspace, x.position))
-                .map(Entry::from)
+                .map(|x| self.client_to_entry(x))
                 .collect()
         } else {
             let matcher = fuzzy_matcher::skim::SkimMatcherV2::default().smart_case();
@@ -233,8 +473,7 @@ impl Plugin for Hyprland {
             //     Normalization::Smart,
             //     AtomKind::Fuzzy,
             // );
-            self.clients
-                .iter()
+            clients
                 .filter_map(|client| {
                     let mut score = 0;
 
@@ -250,8 +489,49 @@ impl Plugin for Hyprland {
                 })
                 .sorted_by_cached_key(|(x, _)| *x)
                 .rev()
-                .map(|(_, x)| Entry::from(x))
+                .map(|(score, x)| Entry {
+                    // Keeps the weighted score comparable to other plugins
+                    score: (score / 5).try_into().unwrap_or(0),
+                    ..self.client_to_entry(x)
+                })
+                // Closed windows are on no workspace anymore
+                .chain(
+                    self.closed
+                        .lock()
+                        .iter()
+                        .filter(|_| filter.is_empty())
+                        .filter_map(|x| {
+                            let score = matcher
+                                .fuzzy_match(&format!("Reopen {} {}", x.name, x.title), query)?;
+                            Some(self.closed_to_entry(x, score.try_into().ok()?))
+                        }),
+                )
                 .collect()
         }
     }
+
+    /// A screenshot of the window, only windows on a visible workspace can be captured.
+    fn preview(&self, entry: &Entry) -> Option<Preview> {
+        let client = self
+            .clients
+            .iter()
+            .find(|x| x.address.to_string() == entry.id)?;
+
+        let path = std::env::var_os("XDG_RUNTIME_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(std::env::temp_dir)
+            .join("jogger-preview.png");
+
+        let status = Command::new("grim")
+            .arg("-g")
+            .arg(format!(
+                "{},{} {}x{}",
+                client.position.0, client.position.1, client.size.0, client.size.1
+            ))
+            .arg(&path)
+            .status()
+            .ok()?;
+
+        status.success().then_some(Preview::Image(path))
+    }
 }
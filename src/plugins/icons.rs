@@ -1,3 +1,5 @@
+use tokio_util::sync::CancellationToken;
+
 use crate::interface::{Context, Entry, EntryIcon, FormatStyle, FormattedString, Plugin};
 
 #[derive(Debug)]
@@ -18,7 +20,7 @@ impl Plugin for Icons {
         Some("iconthemes")
     }
 
-    fn search(&self, query: &str, context: &Context) -> Vec<Entry> {
+    fn search(&self, query: &str, context: &Context, _: &CancellationToken) -> Vec<Entry> {
         let len = query.len();
 
         if len < 2 {
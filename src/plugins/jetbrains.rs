@@ -0,0 +1,223 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use gtk::gdk::{Key, ModifierType};
+use regex::Regex;
+use tokio_util::sync::CancellationToken;
+use xdg::BaseDirectories;
+
+use crate::interface::{Context, Entry, EntryAction, EntryIcon, FormattedString, Plugin};
+use crate::utils::xml_unescape;
+
+static PROJECT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?s)<entry key="([^"]*)">(.*?)</entry>"#).unwrap());
+static TIMESTAMP: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"name="activationTimestamp" value="(\d+)""#).unwrap());
+
+/// The prefix of the config directory of each IDE, its name and launcher script.
+const IDES: [(&str, &str, &str); 12] = [
+    ("IntelliJIdea", "IntelliJ IDEA", "idea"),
+    ("IdeaIC", "IntelliJ IDEA", "idea"),
+    ("PyCharm", "PyCharm", "pycharm"),
+    ("CLion", "CLion", "clion"),
+    ("GoLand", "GoLand", "goland"),
+    ("WebStorm", "WebStorm", "webstorm"),
+    ("RustRover", "RustRover", "rustrover"),
+    ("Rider", "Rider", "rider"),
+    ("PhpStorm", "PhpStorm", "phpstorm"),
+    ("RubyMine", "RubyMine", "rubymine"),
+    ("DataGrip", "DataGrip", "datagrip"),
+    ("AndroidStudio", "Android Studio", "studio"),
+];
+
+#[derive(Debug)]
+struct Project {
+    name: String,
+    path: PathBuf,
+    ide: &'static str,
+    script: &'static str,
+    /// Milliseconds since the epoch.
+    opened: u64,
+}
+
+/// The projects of every installed version, the newest per IDE when opened in several.
+fn load() -> Vec<Project> {
+    let Ok(base_dirs) = BaseDirectories::new() else {
+        return Vec::new();
+    };
+    let config = base_dirs.get_config_home();
+    let home_dir = std::env::var("HOME").unwrap_or_default();
+
+    let mut projects = Vec::new();
+    // Android Studio keeps its config with the other apps of Google
+    for vendor in ["JetBrains", "Google"] {
+        let Ok(children) = std::fs::read_dir(config.join(vendor)) else {
+            continue;
+        };
+
+        for child in children.filter_map(Result::ok) {
+            let directory = child.file_name();
+            let directory = directory.to_string_lossy();
+            let Some(&(_, ide, script)) = IDES.iter().find(|x| directory.starts_with(x.0)) else {
+                continue;
+            };
+
+            // Rider calls them solutions
+            for file in ["recentProjects.xml", "recentSolutions.xml"] {
+                let Ok(content) = std::fs::read_to_string(child.path().join("options").join(file))
+                else {
+                    continue;
+                };
+
+                for x in PROJECT.captures_iter(&content) {
+                    let path = PathBuf::from(xml_unescape(&x[1]).replace("$USER_HOME$", &home_dir));
+                    if !path.exists() {
+                        continue;
+                    }
+
+                    projects.push(Project {
+                        name: path
+                            .file_name()
+                            .map(|x| x.to_string_lossy().into_owned())
+                            .unwrap_or_default(),
+                        opened: TIMESTAMP
+                            .captures(&x[2])
+                            .and_then(|x| x[1].parse().ok())
+                            .unwrap_or_default(),
+                        path,
+                        ide,
+                        script,
+                    });
+                }
+            }
+        }
+    }
+
+    projects.sort_by(|a, b| b.opened.cmp(&a.opened));
+
+    let mut seen = HashSet::new();
+    projects.retain(|x| seen.insert((x.path.clone(), x.ide)));
+    projects
+}
+
+/// The recent projects of the JetBrains IDEs, opened in the IDE they were last used with.
+#[derive(Debug)]
+pub struct JetBrains {
+    home_dir: String,
+    projects: Vec<Project>,
+}
+
+impl JetBrains {
+    pub fn new(_: &Context) -> Self {
+        Self {
+            home_dir: std::env::var("HOME").unwrap_or_default(),
+            projects: Vec::new(),
+        }
+    }
+
+    fn project_to_entry(
+        &self,
+        project: &Project,
+        name: FormattedString,
+        context: &Context,
+    ) -> Entry {
+        let path = &project.path;
+        let display = path.to_string_lossy().into_owned();
+        let display = match display.strip_prefix(&self.home_dir) {
+            Some(x) if !self.home_dir.is_empty() => format!("~{x}"),
+            _ => display,
+        };
+
+        // Named like "IntelliJ IDEA Ultimate" or "JetBrains Rider" depending on how it was installed
+        let app = context
+            .apps
+            .app_map
+            .values()
+            .find(|x| x.name.contains(project.ide));
+        let icon = app.map_or("applications-development", |x| x.icon());
+
+        let mut actions = vec![EntryAction {
+            icon: icon.into(),
+            name: format!("Open in {}", project.ide),
+            function: match app {
+                Some(app) => EntryAction::open(app.id.clone(), None, Some(path.clone())),
+                None => EntryAction::command(
+                    project.script.into(),
+                    vec![path.to_string_lossy().into_owned()],
+                    None,
+                ),
+            },
+            ..Default::default()
+        }];
+
+        if let Some(browser) = context.apps.file_browser() {
+            actions.push(EntryAction {
+                icon: browser.icon().into(),
+                name: "Open in file manager".into(),
+                function: EntryAction::open(browser.id.clone(), None, Some(path.clone())),
+                key: Key::Return,
+                modifier: ModifierType::SHIFT_MASK,
+            });
+        }
+
+        actions.push(EntryAction {
+            icon: "edit-copy".into(),
+            name: "Copy path".into(),
+            function: EntryAction::copy(path.to_string_lossy()),
+            key: Key::c,
+            modifier: ModifierType::CONTROL_MASK,
+        });
+
+        Entry {
+            name,
+            tag: Some(FormattedString::plain(project.ide)),
+            description: Some(FormattedString::plain(display)),
+            icon: EntryIcon::Name(icon.into()),
+            actions,
+            id: format!("{}:{}", project.script, path.to_string_lossy()),
+            drag_file: Some(path.clone()),
+            ..Default::default()
+        }
+    }
+}
+
+impl Plugin for JetBrains {
+    fn open(&mut self) {
+        self.projects = load();
+    }
+
+    fn name(&self) -> &str {
+        "JetBrains"
+    }
+
+    fn icon(&self) -> Option<&str> {
+        Some("applications-development")
+    }
+
+    fn search(&self, query: &str, context: &Context, _: &CancellationToken) -> Vec<Entry> {
+        if query.is_empty() {
+            return self
+                .projects
+                .iter()
+                .map(|x| self.project_to_entry(x, FormattedString::plain(&x.name), context))
+                .collect();
+        }
+
+        let matcher = SkimMatcherV2::default().smart_case();
+        self.projects
+            .iter()
+            .filter_map(|x| {
+                let (score, indices) = matcher.fuzzy_indices(&x.name, query)?;
+                let name = FormattedString::from_indices(&x.name, indices);
+
+                Some(Entry {
+                    score: score.try_into().ok()?,
+                    ..self.project_to_entry(x, name, context)
+                })
+            })
+            .collect()
+    }
+}
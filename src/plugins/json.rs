@@ -0,0 +1,222 @@
+use std::sync::OnceLock;
+
+use gtk::gdk::{Key, ModifierType};
+use serde_json::Value;
+use tokio_util::sync::CancellationToken;
+
+use crate::interface::{
+    Context, Entry, EntryAction, EntryIcon, FormatStyle, FormattedString, Plugin,
+};
+use crate::utils::read_clipboard;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Json,
+    Yaml,
+}
+
+impl Format {
+    fn print(self, value: &Value) -> Option<String> {
+        match self {
+            Self::Json => serde_json::to_string_pretty(value).ok(),
+            Self::Yaml => serde_yaml::to_string(value).ok(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Json {
+    /// The clipboard content, read and parsed by the first search after the launcher is shown.
+    value: OnceLock<Option<Value>>,
+}
+
+impl Json {
+    pub fn new(_: &Context) -> Self {
+        Self {
+            value: OnceLock::new(),
+        }
+    }
+}
+
+fn parse(content: &str) -> Option<Value> {
+    let content = content.trim();
+    if content.is_empty() {
+        return None;
+    }
+
+    serde_json::from_str(content)
+        .ok()
+        .or_else(|| serde_yaml::from_str(content).ok())
+        // Any plain string is valid YAML, only keep actual documents
+        .filter(|x| matches!(x, Value::Object(_) | Value::Array(_)))
+}
+
+#[derive(Debug)]
+enum Segment {
+    Key(String),
+    Index(isize),
+}
+
+/// Parses a `jq` style path like `.items[0].name` or `.["some key"]`.
+fn parse_path(path: &str) -> Option<Vec<Segment>> {
+    let mut segments = Vec::new();
+    let mut rest = path.trim();
+
+    while !rest.is_empty() {
+        if let Some(x) = rest.strip_prefix('[') {
+            let (inner, after) = x.split_once(']')?;
+            let inner = inner.trim();
+
+            if let Some(key) = inner.strip_prefix('"').and_then(|x| x.strip_suffix('"')) {
+                segments.push(Segment::Key(key.to_owned()));
+            } else {
+                segments.push(Segment::Index(inner.parse().ok()?));
+            }
+
+            rest = after;
+        } else if let Some(x) = rest.strip_prefix('.') {
+            let end = x.find(['.', '[']).unwrap_or(x.len());
+            let key = x[..end].trim();
+
+            if !key.is_empty() {
+                segments.push(Segment::Key(key.to_owned()));
+            }
+
+            rest = &x[end..];
+        } else {
+            return None;
+        }
+    }
+
+    Some(segments)
+}
+
+fn select<'a>(value: &'a Value, path: &[Segment]) -> Option<&'a Value> {
+    path.iter().try_fold(value, |value, segment| match segment {
+        Segment::Key(key) => value.get(key),
+        Segment::Index(index) => {
+            let array = value.as_array()?;
+            let index = if *index < 0 {
+                array.len().checked_sub(index.unsigned_abs())?
+            } else {
+                *index as usize
+            };
+
+            array.get(index)
+        }
+    })
+}
+
+fn describe(value: &Value) -> String {
+    match value {
+        Value::Null => "Null".to_owned(),
+        Value::Bool(_) => "Boolean".to_owned(),
+        Value::Number(_) => "Number".to_owned(),
+        Value::String(x) => format!("String of {} characters", x.chars().count()),
+        Value::Array(x) => format!("Array of {} items", x.len()),
+        Value::Object(x) => format!("Object with {} keys", x.len()),
+    }
+}
+
+impl Plugin for Json {
+    fn open(&mut self) {
+        self.value = OnceLock::new();
+    }
+
+    fn name(&self) -> &str {
+        "JSON"
+    }
+
+    fn icon(&self) -> Option<&str> {
+        Some("text-x-script")
+    }
+
+    fn search(&self, query: &str, _: &Context, _: &CancellationToken) -> Vec<Entry> {
+        let value = self
+            .value
+            .get_or_init(|| read_clipboard().as_deref().and_then(parse));
+        let Some(root) = value else {
+            return Vec::new();
+        };
+
+        let (format, path) = if let Some(x) = query.strip_prefix("yaml") {
+            (Format::Yaml, x)
+        } else {
+            (Format::Json, query.strip_prefix("json").unwrap_or(query))
+        };
+
+        let Some(path) = parse_path(path) else {
+            return Vec::new();
+        };
+
+        let Some(value) = select(root, &path) else {
+            return vec![Entry {
+                name: FormattedString::plain("No value at this path"),
+                description: Some(FormattedString::plain(describe(root))),
+                icon: EntryIcon::Name("dialog-warning".into()),
+                ..Default::default()
+            }];
+        };
+
+        // Strings are copied without quotes, like `jq -r`
+        let text = match value {
+            Value::String(x) => x.clone(),
+            x => format.print(x).unwrap_or_default(),
+        };
+
+        let mut actions = vec![EntryAction {
+            icon: "edit-copy".into(),
+            name: "Copy".into(),
+            function: EntryAction::copy(text.trim_end()),
+            ..Default::default()
+        }];
+
+        let other = match format {
+            Format::Json => Format::Yaml,
+            Format::Yaml => Format::Json,
+        };
+
+        if !value.is_string()
+            && let Some(converted) = other.print(value)
+        {
+            actions.push(EntryAction {
+                icon: "edit-copy".into(),
+                name: match other {
+                    Format::Json => "Copy as JSON",
+                    Format::Yaml => "Copy as YAML",
+                }
+                .into(),
+                function: EntryAction::copy(converted.trim_end()),
+                key: Key::c,
+                modifier: ModifierType::CONTROL_MASK | ModifierType::SHIFT_MASK,
+            });
+        }
+
+        if value.is_object() || value.is_array() {
+            actions.push(EntryAction {
+                icon: "format-justify-fill".into(),
+                name: "Copy minified".into(),
+                function: EntryAction::copy(value.to_string()),
+                key: Key::m,
+                modifier: ModifierType::CONTROL_MASK,
+            });
+        }
+
+        // Rows are a single line, so documents are previewed minified
+        let name = match value {
+            Value::String(x) => x.clone(),
+            x => x.to_string(),
+        }
+        .chars()
+        .take(256)
+        .collect::<String>();
+
+        vec![Entry {
+            name: FormattedString::from_style(name, FormatStyle::Monospace),
+            description: Some(FormattedString::plain(describe(value))),
+            icon: EntryIcon::Name("text-x-script".into()),
+            actions,
+            ..Default::default()
+        }]
+    }
+}
@@ -0,0 +1,279 @@
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::Arc;
+
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use parking_lot::Mutex;
+use serde::Deserialize;
+use tokio_util::sync::CancellationToken;
+
+use crate::interface::{
+    ActionResult, ActionType, Context, Entry, EntryAction, EntryIcon, FormatStyle, FormattedString,
+    Plugin,
+};
+
+/// Opened in a terminal for the current context, watching so the output stays.
+const COMMANDS: [(&str, &[&str]); 5] = [
+    ("Pods", &["get", "pods", "--watch"]),
+    ("Deployments", &["get", "deployments", "--watch"]),
+    ("Services", &["get", "services", "--watch"]),
+    ("Nodes", &["get", "nodes", "--watch"]),
+    ("Events", &["get", "events", "--watch"]),
+];
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct KubeConfig {
+    #[serde(default)]
+    current_context: String,
+    #[serde(default)]
+    contexts: Vec<NamedContext>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct NamedContext {
+    name: String,
+    context: KubeContext,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct KubeContext {
+    cluster: String,
+    namespace: Option<String>,
+}
+
+/// The first file of `KUBECONFIG`, like kubectl writes to.
+fn config_path() -> Option<PathBuf> {
+    if let Some(paths) = std::env::var_os("KUBECONFIG")
+        && let Some(path) = std::env::split_paths(&paths).next()
+    {
+        return Some(path);
+    }
+
+    expanduser::expanduser("~/.kube/config").ok()
+}
+
+fn load_config() -> KubeConfig {
+    let Some(path) = config_path() else {
+        return KubeConfig::default();
+    };
+
+    let Ok(content) = std::fs::read_to_string(&path) else {
+        return KubeConfig::default();
+    };
+
+    serde_yaml::from_str(&content).unwrap_or_else(|error| {
+        tracing::warn!(%error, ?path, "Failed to parse the kubeconfig");
+        KubeConfig::default()
+    })
+}
+
+/// Asks the cluster, so it's only done for the current context.
+fn load_namespaces() -> Vec<String> {
+    match Command::new("kubectl")
+        .args(["get", "namespaces", "--output=name", "--request-timeout=5s"])
+        .output()
+    {
+        Ok(x) if x.status.success() => String::from_utf8_lossy(&x.stdout)
+            .lines()
+            .map(|x| x.trim_start_matches("namespace/").to_owned())
+            .collect(),
+        Ok(x) => {
+            let error = String::from_utf8_lossy(&x.stderr);
+            tracing::debug!(%error, "Failed to list the namespaces");
+            Vec::new()
+        }
+        Err(error) => {
+            tracing::debug!(?error, "Failed to run kubectl");
+            Vec::new()
+        }
+    }
+}
+
+/// Runs a kubectl command that changes the kubeconfig, waiting for it to be written.
+fn kubectl(args: Vec<String>) -> Box<ActionType> {
+    Box::new(
+        move |_| match Command::new("kubectl").args(&args).output() {
+            Ok(x) if x.status.success() => ActionResult::Ok,
+            Ok(x) => {
+                let error = String::from_utf8_lossy(&x.stderr);
+                tracing::warn!(%error, ?args, "kubectl failed");
+                ActionResult::Error
+            }
+            Err(error) => {
+                tracing::warn!(?error, "Failed to run kubectl");
+                ActionResult::Error
+            }
+        },
+    )
+}
+
+/// The contexts and namespaces of the kubeconfig, and common kubectl commands.
+#[derive(Debug)]
+pub struct Kubernetes {
+    config: KubeConfig,
+    namespaces: Arc<Mutex<Vec<String>>>,
+}
+
+impl Kubernetes {
+    pub fn new(_: &Context) -> Self {
+        Self {
+            config: KubeConfig::default(),
+            namespaces: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    fn current_namespace(&self) -> &str {
+        self.config
+            .contexts
+            .iter()
+            .find(|x| x.name == self.config.current_context)
+            .and_then(|x| x.context.namespace.as_deref())
+            .unwrap_or("default")
+    }
+
+    fn context_to_entry(&self, context: &NamedContext, name: FormattedString, score: u64) -> Entry {
+        let current = context.name == self.config.current_context;
+        let namespace = context.context.namespace.as_deref().unwrap_or("default");
+
+        Entry {
+            name,
+            tag: Some(if current {
+                FormattedString::from_style("Current", FormatStyle::Special)
+            } else {
+                FormattedString::plain("Context")
+            }),
+            description: Some(FormattedString::plain(format!(
+                "{} · {namespace}",
+                context.context.cluster
+            ))),
+            icon: EntryIcon::Name("network-server".into()),
+            actions: vec![EntryAction {
+                icon: "go-jump".into(),
+                name: "Switch context".into(),
+                function: kubectl(vec![
+                    "config".into(),
+                    "use-context".into(),
+                    context.name.clone(),
+                ]),
+                ..Default::default()
+            }],
+            id: format!("kubernetes:{}", context.name),
+            score,
+            ..Default::default()
+        }
+    }
+
+    fn namespace_to_entry(&self, namespace: &str, name: FormattedString, score: u64) -> Entry {
+        let current = namespace == self.current_namespace();
+
+        Entry {
+            name,
+            tag: Some(if current {
+                FormattedString::from_style("Current", FormatStyle::Special)
+            } else {
+                FormattedString::plain("Namespace")
+            }),
+            description: Some(FormattedString::plain(&self.config.current_context)),
+            icon: EntryIcon::Name("folder-remote".into()),
+            actions: vec![EntryAction {
+                icon: "go-jump".into(),
+                name: "Switch namespace".into(),
+                function: kubectl(vec![
+                    "config".into(),
+                    "set-context".into(),
+                    "--current".into(),
+                    format!("--namespace={namespace}"),
+                ]),
+                ..Default::default()
+            }],
+            score,
+            ..Default::default()
+        }
+    }
+
+    fn command_to_entry(&self, args: &[&str], name: FormattedString, score: u64) -> Entry {
+        Entry {
+            name,
+            tag: Some(FormattedString::plain("kubectl")),
+            description: Some(FormattedString::from_style(
+                format!("kubectl {}", args.join(" ")),
+                FormatStyle::Monospace,
+            )),
+            icon: EntryIcon::Name("terminal".into()),
+            actions: vec![EntryAction {
+                icon: "terminal".into(),
+                name: "Open in terminal".into(),
+                function: EntryAction::launch_terminal(
+                    Some("kubectl".into()),
+                    args.iter().map(|x| x.to_string()).collect(),
+                    None,
+                ),
+                ..Default::default()
+            }],
+            id: format!("kubernetes:{}", args.join(" ")),
+            score,
+            ..Default::default()
+        }
+    }
+}
+
+impl Plugin for Kubernetes {
+    fn open(&mut self) {
+        self.config = load_config();
+
+        let namespaces = self.namespaces.clone();
+        std::thread::spawn(move || {
+            let loaded = load_namespaces();
+            *namespaces.lock() = loaded;
+        });
+    }
+
+    fn name(&self) -> &str {
+        "Kubernetes"
+    }
+
+    fn icon(&self) -> Option<&str> {
+        Some("network-server")
+    }
+
+    fn search(&self, query: &str, _: &Context, _: &CancellationToken) -> Vec<Entry> {
+        let query = query
+            .strip_prefix("kube")
+            .filter(|x| x.is_empty() || x.starts_with(char::is_whitespace))
+            .unwrap_or(query)
+            .trim();
+
+        let matcher = SkimMatcherV2::default().smart_case();
+        let matched = |text: &str| -> Option<(FormattedString, u64)> {
+            if query.is_empty() {
+                return Some((FormattedString::plain(text), 0));
+            }
+
+            let (score, indices) = matcher.fuzzy_indices(text, query)?;
+            Some((
+                FormattedString::from_indices(text, indices),
+                score.try_into().ok()?,
+            ))
+        };
+
+        let contexts = self.config.contexts.iter().filter_map(|x| {
+            let (name, score) = matched(&x.name)?;
+            Some(self.context_to_entry(x, name, score))
+        });
+
+        let namespaces = self.namespaces.lock().clone();
+        let namespaces = namespaces.iter().filter_map(|x| {
+            let (name, score) = matched(x)?;
+            Some(self.namespace_to_entry(x, name, score))
+        });
+
+        let commands = COMMANDS.iter().filter_map(|(x, args)| {
+            let (name, score) = matched(x)?;
+            Some(self.command_to_entry(args, name, score))
+        });
+
+        contexts.chain(namespaces).chain(commands).collect()
+    }
+}
@@ -0,0 +1,140 @@
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use gtk::gdk::{Key, ModifierType};
+use tokio_util::sync::CancellationToken;
+
+use crate::frecency::Usage;
+use crate::interface::{
+    ActionResult, Context, Entry, EntryAction, EntryIcon, FormatStyle, FormattedString, Plugin,
+};
+
+/// Maintenance of the launcher itself: the usage scores that rank entries,
+/// so those no longer used can be reset and favorites pinned.
+#[derive(Debug)]
+pub struct Launcher {}
+
+impl Launcher {
+    pub fn new(_: &Context) -> Self {
+        Self {}
+    }
+
+    fn usage_to_entry(
+        &self,
+        id: &str,
+        usage: &Usage,
+        score: u64,
+        name: FormattedString,
+        context: &Context,
+    ) -> Entry {
+        let icon = context
+            .apps
+            .app_map
+            .get(id)
+            .map_or("document-open-recent", |x| x.icon());
+        let last = chrono::DateTime::from_timestamp(usage.last, 0)
+            .map(|x| {
+                x.with_timezone(&chrono::Local)
+                    .format("%d %b %H:%M")
+                    .to_string()
+            })
+            .unwrap_or_default();
+
+        let reset_id = id.to_owned();
+        let pin_id = id.to_owned();
+
+        Entry {
+            name,
+            tag: Some(if usage.pinned {
+                FormattedString::from_style("Pinned", FormatStyle::Special)
+            } else {
+                FormattedString::plain(score.to_string())
+            }),
+            description: Some(FormattedString::plain(format!(
+                "Used {} times, last {last}",
+                usage.count
+            ))),
+            icon: EntryIcon::Name(icon.into()),
+            actions: vec![
+                EntryAction {
+                    icon: "edit-clear".into(),
+                    name: "Reset score".into(),
+                    function: Box::new(move |context| {
+                        context.frecency.reset(&reset_id);
+                        ActionResult::Refresh
+                    }),
+                    ..Default::default()
+                },
+                EntryAction {
+                    icon: "view-pin".into(),
+                    name: if usage.pinned { "Unpin" } else { "Pin" }.into(),
+                    function: Box::new(move |context| {
+                        context.frecency.toggle_pin(&pin_id);
+                        ActionResult::Refresh
+                    }),
+                    key: Key::p,
+                    modifier: ModifierType::CONTROL_MASK,
+                },
+            ],
+            score,
+            ..Default::default()
+        }
+    }
+}
+
+impl Plugin for Launcher {
+    fn name(&self) -> &str {
+        "Launcher"
+    }
+
+    fn icon(&self) -> Option<&str> {
+        Some("preferences-system")
+    }
+
+    fn search(&self, query: &str, context: &Context, _: &CancellationToken) -> Vec<Entry> {
+        let matcher = SkimMatcherV2::default().smart_case();
+
+        let mut entries = context
+            .frecency
+            .usages()
+            .filter_map(|(id, usage, score)| {
+                // Apps are recorded by their desktop ID, other entries by whatever they chose
+                let text = context.apps.app_map.get(id).map_or(id, |x| x.name.as_str());
+                let name = if query.is_empty() {
+                    FormattedString::plain(text)
+                } else {
+                    let (_, indices) = matcher.fuzzy_indices(text, query)?;
+                    FormattedString::from_indices(text, indices)
+                };
+
+                Some(self.usage_to_entry(id, usage, score, name, context))
+            })
+            .collect::<Vec<_>>();
+
+        entries.sort_by(|a, b| b.score.cmp(&a.score));
+
+        if query.is_empty() || matcher.fuzzy_match("Reset all usage", query).is_some() {
+            entries.push(Entry {
+                name: FormattedString::plain("Reset all usage"),
+                description: Some(FormattedString::plain(
+                    "Forget how often every entry was used",
+                )),
+                icon: EntryIcon::Name("edit-clear-all".into()),
+                actions: vec![EntryAction {
+                    icon: "edit-clear-all".into(),
+                    name: "Reset".into(),
+                    function: EntryAction::confirm(
+                        "Reset the usage of every entry?",
+                        Box::new(|context| {
+                            context.frecency.reset_all();
+                            ActionResult::Refresh
+                        }),
+                    ),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            });
+        }
+
+        entries
+    }
+}
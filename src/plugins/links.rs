@@ -0,0 +1,254 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::mpsc::RecvTimeoutError;
+use std::time::Duration;
+
+use gtk::gdk::{Key, ModifierType};
+use parking_lot::Mutex;
+use reqwest::Url;
+use reqwest::redirect::Policy;
+use serde::Deserialize;
+use tokio_util::sync::CancellationToken;
+
+use crate::interface::{
+    ActionResult, ActionType, Context, Entry, EntryAction, EntryIcon, FormattedString, Plugin,
+    parse_options,
+};
+use crate::utils::percent_encode;
+
+const MAX_REDIRECTS: usize = 10;
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+struct LinksConfig {
+    /// Returns the short link as plain text, `{url}` is replaced with the encoded link.
+    shortener: String,
+}
+
+impl Default for LinksConfig {
+    fn default() -> Self {
+        Self {
+            shortener: "https://is.gd/create.php?format=simple&url={url}".into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Resolved {
+    destination: String,
+    redirects: usize,
+}
+
+fn client() -> reqwest::Result<reqwest::blocking::Client> {
+    reqwest::blocking::Client::builder()
+        .redirect(Policy::none())
+        .timeout(TIMEOUT)
+        .user_agent("jogger")
+        .build()
+}
+
+/// Follows the redirects one at a time, so the chain stops at the first page that isn't one.
+fn resolve(url: &str) -> Option<Resolved> {
+    let client = client().ok()?;
+    let mut current = Url::parse(url).ok()?;
+    let mut redirects = 0;
+
+    while redirects < MAX_REDIRECTS {
+        // Some shorteners don't answer HEAD requests
+        let response = match client.head(current.clone()).send() {
+            Ok(x) if x.status() != reqwest::StatusCode::METHOD_NOT_ALLOWED => x,
+            _ => client.get(current.clone()).send().ok()?,
+        };
+
+        if !response.status().is_redirection() {
+            break;
+        }
+
+        let Some(next) = response
+            .headers()
+            .get(reqwest::header::LOCATION)
+            .and_then(|x| x.to_str().ok())
+            .and_then(|x| current.join(x).ok())
+        else {
+            break;
+        };
+
+        current = next;
+        redirects += 1;
+    }
+
+    Some(Resolved {
+        destination: current.to_string(),
+        redirects,
+    })
+}
+
+fn shorten(template: String, url: String) -> Box<ActionType> {
+    Box::new(move |context| {
+        let request = template.replace("{url}", &percent_encode(&url));
+
+        let result = client().and_then(|x| {
+            x.get(request)
+                .send()
+                .and_then(|x| x.error_for_status())
+                .and_then(|x| x.text())
+        });
+
+        match result {
+            Ok(short) if short.trim().starts_with("http") => {
+                (EntryAction::copy(short.trim()))(context)
+            }
+            Ok(response) => {
+                tracing::warn!(%response, "Unexpected answer from the shortener");
+                ActionResult::Error
+            }
+            Err(error) => {
+                tracing::warn!(%error, "Failed to shorten link");
+                ActionResult::Error
+            }
+        }
+    })
+}
+
+/// Shows where short links lead before opening them, and shortens long ones.
+#[derive(Debug)]
+pub struct Links {
+    shortener: String,
+    handler: Option<String>,
+    resolved: Arc<Mutex<HashMap<String, Option<Resolved>>>>,
+}
+
+impl Links {
+    pub fn new(context: &Context, options: &toml::Table) -> Self {
+        let config: LinksConfig = parse_options(options);
+
+        Self {
+            shortener: config.shortener,
+            handler: context
+                .apps
+                .default_for_mime(&"x-scheme-handler/https".parse().unwrap())
+                .map(|x| x.id.clone()),
+            resolved: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    fn open(&self, url: &str) -> Box<ActionType> {
+        match &self.handler {
+            Some(handler) => EntryAction::open(handler.clone(), None, Some(PathBuf::from(url))),
+            None => EntryAction::command("xdg-open".into(), vec![url.to_owned()], None),
+        }
+    }
+
+    /// Resolves the link on its own thread, giving up as soon as the token is cancelled.
+    fn resolve(&self, url: &str, token: &CancellationToken) -> Option<Resolved> {
+        if let Some(resolved) = self.resolved.lock().get(url) {
+            return resolved.clone();
+        }
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let cache = self.resolved.clone();
+        let url = url.to_owned();
+        std::thread::spawn(move || {
+            let resolved = resolve(&url);
+            cache.lock().insert(url, resolved.clone());
+            let _ = sender.send(resolved);
+        });
+
+        loop {
+            if token.is_cancelled() {
+                return None;
+            }
+
+            match receiver.recv_timeout(Duration::from_millis(20)) {
+                Ok(result) => return result,
+                Err(RecvTimeoutError::Disconnected) => return None,
+                Err(RecvTimeoutError::Timeout) => {}
+            }
+        }
+    }
+
+    fn shorten_entry(&self, url: &str) -> Entry {
+        let service = Url::parse(&self.shortener)
+            .ok()
+            .and_then(|x| x.host_str().map(str::to_owned))
+            .unwrap_or_default();
+
+        Entry {
+            name: FormattedString::plain("Shorten link"),
+            description: Some(FormattedString::plain(format!(
+                "Copies a short link from {service}"
+            ))),
+            icon: EntryIcon::Name("insert-link".into()),
+            actions: vec![EntryAction {
+                icon: "edit-copy".into(),
+                name: "Shorten and copy".into(),
+                function: shorten(self.shortener.clone(), url.to_owned()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+}
+
+impl Plugin for Links {
+    fn name(&self) -> &str {
+        "Links"
+    }
+
+    fn icon(&self) -> Option<&str> {
+        Some("insert-link")
+    }
+
+    fn search_stream(
+        &self,
+        query: &str,
+        _: &Context,
+        token: &CancellationToken,
+        sink: &mut dyn FnMut(Vec<Entry>),
+    ) {
+        let url = query.trim();
+        if Url::parse(url).is_err() {
+            return;
+        }
+
+        // Shortening only happens when asked, resolving is what takes a while
+        sink(vec![self.shorten_entry(url)]);
+
+        let Some(resolved) = self.resolve(url, token) else {
+            return;
+        };
+
+        let description = match resolved.redirects {
+            0 => "Doesn't redirect anywhere".to_owned(),
+            1 => "After 1 redirect".to_owned(),
+            x => format!("After {x} redirects"),
+        };
+
+        sink(vec![Entry {
+            name: FormattedString::plain(&resolved.destination),
+            tag: Some(FormattedString::plain("Destination")),
+            description: Some(FormattedString::plain(description)),
+            icon: EntryIcon::Name("web-browser".into()),
+            actions: vec![
+                EntryAction {
+                    icon: "web-browser".into(),
+                    name: "Open".into(),
+                    function: self.open(&resolved.destination),
+                    ..Default::default()
+                },
+                EntryAction {
+                    icon: "edit-copy".into(),
+                    name: "Copy".into(),
+                    function: EntryAction::copy(&resolved.destination),
+                    key: Key::c,
+                    modifier: ModifierType::CONTROL_MASK,
+                },
+            ],
+            // Above the shortener, since checking the link is the common case
+            score: 1,
+            ..Default::default()
+        }]);
+    }
+}
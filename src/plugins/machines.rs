@@ -0,0 +1,314 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Arc;
+
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use gtk::gdk::{Key, ModifierType};
+use parking_lot::Mutex;
+use serde::Deserialize;
+use tokio_util::sync::CancellationToken;
+
+use crate::interface::{
+    Context, Entry, EntryAction, EntryIcon, FormattedString, Plugin, parse_options,
+};
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+struct MachinesConfig {
+    /// The session connection is where GNOME Boxes keeps its machines.
+    connections: Vec<String>,
+    /// Directories with the `.conf` files of quickemu machines.
+    quickemu: Vec<String>,
+}
+
+impl Default for MachinesConfig {
+    fn default() -> Self {
+        Self {
+            connections: vec!["qemu:///system".into(), "qemu:///session".into()],
+            quickemu: vec!["~".into(), "~/VMs".into()],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Running,
+    Paused,
+    Stopped,
+}
+
+impl State {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Running => "Running",
+            Self::Paused => "Paused",
+            Self::Stopped => "Stopped",
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Source {
+    Libvirt(String),
+    Quickemu(PathBuf),
+}
+
+#[derive(Debug)]
+struct Machine {
+    name: String,
+    state: State,
+    source: Source,
+}
+
+impl Machine {
+    fn description(&self) -> String {
+        match &self.source {
+            Source::Libvirt(uri) if uri.ends_with("/session") => format!("libvirt session · {uri}"),
+            Source::Libvirt(uri) => format!("libvirt · {uri}"),
+            Source::Quickemu(path) => format!("quickemu · {}", path.display()),
+        }
+    }
+
+    fn actions(&self) -> Vec<EntryAction> {
+        let virsh = |uri: &str, command: &str, name: &str| {
+            EntryAction::command(
+                "virsh".into(),
+                vec!["--connect".into(), uri.into(), command.into(), name.into()],
+                None,
+            )
+        };
+
+        let mut actions = Vec::new();
+
+        match (&self.source, self.state) {
+            (Source::Libvirt(uri), State::Stopped) => {
+                actions.push(EntryAction {
+                    icon: "media-playback-start".into(),
+                    name: "Start".into(),
+                    function: virsh(uri, "start", &self.name),
+                    ..Default::default()
+                });
+            }
+            (Source::Libvirt(uri), state) => {
+                actions.push(EntryAction {
+                    icon: "computer".into(),
+                    name: "Open viewer".into(),
+                    function: EntryAction::command(
+                        "virt-viewer".into(),
+                        vec!["--connect".into(), uri.clone(), self.name.clone()],
+                        None,
+                    ),
+                    ..Default::default()
+                });
+                if state == State::Paused {
+                    actions.push(EntryAction {
+                        icon: "media-playback-start".into(),
+                        name: "Resume".into(),
+                        function: virsh(uri, "resume", &self.name),
+                        key: Key::p,
+                        modifier: ModifierType::CONTROL_MASK,
+                    });
+                }
+                actions.push(EntryAction {
+                    icon: "system-shutdown".into(),
+                    name: "Shut down".into(),
+                    function: virsh(uri, "shutdown", &self.name),
+                    key: Key::Delete,
+                    modifier: ModifierType::CONTROL_MASK,
+                });
+            }
+            (Source::Quickemu(path), State::Stopped) => {
+                actions.push(EntryAction {
+                    icon: "media-playback-start".into(),
+                    name: "Start".into(),
+                    function: EntryAction::command(
+                        "quickemu".into(),
+                        vec!["--vm".into(), path.to_string_lossy().into_owned()],
+                        path.parent().map(Path::to_path_buf),
+                    ),
+                    ..Default::default()
+                });
+            }
+            (Source::Quickemu(path), _) => {
+                actions.push(EntryAction {
+                    icon: "system-shutdown".into(),
+                    name: "Shut down".into(),
+                    function: EntryAction::command(
+                        "quickemu".into(),
+                        vec![
+                            "--vm".into(),
+                            path.to_string_lossy().into_owned(),
+                            "--monitor-cmd".into(),
+                            "system_powerdown".into(),
+                        ],
+                        path.parent().map(Path::to_path_buf),
+                    ),
+                    key: Key::Delete,
+                    modifier: ModifierType::CONTROL_MASK,
+                });
+            }
+        }
+
+        actions
+    }
+}
+
+/// Lists the machines of a libvirt connection from the table printed by `virsh list`.
+fn list_libvirt(uri: &str) -> Vec<Machine> {
+    let output = match Command::new("virsh")
+        .args(["--connect", uri, "list", "--all"])
+        .output()
+    {
+        Ok(x) if x.status.success() => x,
+        Ok(_) => return Vec::new(),
+        Err(error) => {
+            tracing::debug!(?error, "Failed to run virsh");
+            return Vec::new();
+        }
+    };
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        // The header and the line under it
+        .skip(2)
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let _id = parts.next()?;
+            let name = parts.next()?.to_owned();
+            let state = match parts.collect::<Vec<_>>().join(" ").as_str() {
+                "running" | "idle" | "in shutdown" => State::Running,
+                "paused" | "pmsuspended" => State::Paused,
+                _ => State::Stopped,
+            };
+
+            Some(Machine {
+                name,
+                state,
+                source: Source::Libvirt(uri.to_owned()),
+            })
+        })
+        .collect()
+}
+
+/// Whether the process in the file written by quickemu is still alive.
+fn is_running(pid_file: &Path) -> bool {
+    std::fs::read_to_string(pid_file).is_ok_and(|x| {
+        let pid = x.trim();
+        !pid.is_empty() && Path::new("/proc").join(pid).exists()
+    })
+}
+
+fn list_quickemu(directory: &Path) -> Vec<Machine> {
+    let Ok(entries) = std::fs::read_dir(directory) else {
+        return Vec::new();
+    };
+
+    entries
+        .filter_map(|x| Some(x.ok()?.path()))
+        .filter(|x| x.extension().is_some_and(|x| x == "conf"))
+        .filter_map(|path| {
+            let content = std::fs::read_to_string(&path).ok()?;
+            // Tells them apart from the other `.conf` files lying around
+            let disk = content.lines().find_map(|x| {
+                x.strip_prefix("disk_img=")
+                    .map(|x| x.trim_matches('"').to_owned())
+            })?;
+            content.lines().find(|x| x.starts_with("guest_os="))?;
+
+            let name = path.file_stem()?.to_string_lossy().into_owned();
+            let pid_file = directory
+                .join(Path::new(&disk).parent().unwrap_or(Path::new("")))
+                .join(format!("{name}.pid"));
+
+            Some(Machine {
+                name,
+                state: if is_running(&pid_file) {
+                    State::Running
+                } else {
+                    State::Stopped
+                },
+                source: Source::Quickemu(path),
+            })
+        })
+        .collect()
+}
+
+/// Virtual machines from libvirt, including the ones of GNOME Boxes, and quickemu.
+#[derive(Debug)]
+pub struct Machines {
+    connections: Vec<String>,
+    quickemu: Vec<PathBuf>,
+    /// Read when the launcher is shown, since their state changes.
+    machines: Arc<Mutex<Vec<Machine>>>,
+}
+
+impl Machines {
+    pub fn new(_: &Context, options: &toml::Table) -> Self {
+        let config: MachinesConfig = parse_options(options);
+
+        Self {
+            connections: config.connections,
+            quickemu: config
+                .quickemu
+                .iter()
+                .filter_map(|x| expanduser::expanduser(x).ok())
+                .collect(),
+            machines: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+}
+
+impl Plugin for Machines {
+    fn open(&mut self) {
+        // virsh can take a while to answer, more so for the system connection
+        let connections = self.connections.clone();
+        let quickemu = self.quickemu.clone();
+        let machines = self.machines.clone();
+        std::thread::spawn(move || {
+            let loaded = connections
+                .iter()
+                .flat_map(|x| list_libvirt(x))
+                .chain(quickemu.iter().flat_map(|x| list_quickemu(x)))
+                .collect();
+            *machines.lock() = loaded;
+        });
+    }
+
+    fn name(&self) -> &str {
+        "Virtual machines"
+    }
+
+    fn icon(&self) -> Option<&str> {
+        Some("computer")
+    }
+
+    fn search(&self, query: &str, _: &Context, _: &CancellationToken) -> Vec<Entry> {
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let matcher = SkimMatcherV2::default().ignore_case();
+
+        self.machines
+            .lock()
+            .iter()
+            .filter_map(|machine| {
+                // Also found by what they are, not only by their name
+                let score =
+                    matcher.fuzzy_match(&format!("{} virtual machine vm", machine.name), query)?;
+
+                Some(Entry {
+                    name: FormattedString::plain(&machine.name),
+                    tag: Some(FormattedString::plain(machine.state.name())),
+                    description: Some(FormattedString::plain(machine.description())),
+                    icon: EntryIcon::Name("computer".into()),
+                    actions: machine.actions(),
+                    id: format!("machines:{}", machine.name),
+                    score: score.try_into().ok()?,
+                    ..Default::default()
+                })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,202 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use gtk::gdk::{Key, ModifierType};
+use parking_lot::Mutex;
+use tokio_util::sync::CancellationToken;
+use xdg::BaseDirectories;
+
+use crate::interface::{
+    ActionResult, ActionType, Context, Entry, EntryAction, EntryIcon, FormattedString, Plugin,
+};
+use crate::utils::percent_encode;
+
+const MAX_RECIPIENTS: usize = 50;
+
+#[derive(Debug)]
+struct Recipients {
+    path: Option<PathBuf>,
+    /// Most recent first.
+    addresses: Vec<String>,
+}
+
+impl Recipients {
+    fn load() -> Self {
+        let path = BaseDirectories::with_prefix("jogger")
+            .ok()
+            .and_then(|x| x.place_data_file("mail.json").ok());
+
+        let addresses = path
+            .as_ref()
+            .and_then(|x| std::fs::read_to_string(x).ok())
+            .and_then(|x| serde_json::from_str(&x).ok())
+            .unwrap_or_default();
+
+        Self { path, addresses }
+    }
+
+    fn record(&mut self, addresses: &[String]) {
+        for address in addresses.iter().rev() {
+            self.addresses.retain(|x| x != address);
+            self.addresses.insert(0, address.clone());
+        }
+        self.addresses.truncate(MAX_RECIPIENTS);
+
+        let Some(path) = &self.path else {
+            return;
+        };
+
+        match serde_json::to_string(&self.addresses) {
+            Ok(content) => {
+                if let Err(error) = std::fs::write(path, content) {
+                    tracing::error!(?error, "Failed to save mail recipients");
+                }
+            }
+            Err(error) => tracing::error!(?error, "Failed to serialize mail recipients"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Mail {
+    /// The default mail client, `xdg-open` is used when there is none.
+    handler: Option<String>,
+    recipients: Arc<Mutex<Recipients>>,
+}
+
+impl Mail {
+    pub fn new(context: &Context) -> Self {
+        Self {
+            handler: context
+                .apps
+                .default_for_mime(&"x-scheme-handler/mailto".parse().unwrap())
+                .map(|x| x.id.clone()),
+            recipients: Arc::new(Mutex::new(Recipients::load())),
+        }
+    }
+
+    fn compose(&self, recipients: Vec<String>, uri: String) -> Box<ActionType> {
+        let history = self.recipients.clone();
+        let open = match &self.handler {
+            Some(handler) => EntryAction::open(handler.clone(), None, Some(PathBuf::from(uri))),
+            None => EntryAction::command("xdg-open".into(), vec![uri], None),
+        };
+
+        EntryAction::map_result(open, move |result| {
+            if !matches!(result, ActionResult::Error) {
+                history.lock().record(&recipients);
+            }
+            result
+        })
+    }
+}
+
+impl Plugin for Mail {
+    fn name(&self) -> &str {
+        "Mail"
+    }
+
+    fn icon(&self) -> Option<&str> {
+        Some("mail-message-new")
+    }
+
+    fn search(&self, query: &str, _: &Context, _: &CancellationToken) -> Vec<Entry> {
+        let (prefix, query) = match query.strip_prefix("mail ") {
+            Some(rest) => ("mail ", rest.trim_start()),
+            None => ("", query),
+        };
+
+        let (address, rest) = query.split_once(' ').unwrap_or((query, ""));
+
+        // Still typing the address, offer the ones used before
+        if rest.is_empty() && !query.ends_with(' ') {
+            let typed = address.rsplit(',').next().unwrap_or_default();
+            let before = &address[..address.len() - typed.len()];
+            let typed = typed.to_lowercase();
+
+            return self
+                .recipients
+                .lock()
+                .addresses
+                .iter()
+                .filter(|x| {
+                    let x = x.to_lowercase();
+                    x.contains(&typed) && x != typed
+                })
+                .map(|x| Entry {
+                    name: FormattedString::plain(x),
+                    description: Some(FormattedString::plain("Recent recipient")),
+                    icon: EntryIcon::Name("contact-new".into()),
+                    actions: vec![EntryAction {
+                        icon: "go-next".into(),
+                        name: "Complete".into(),
+                        function: EntryAction::write(format!("{prefix}{before}{x} ")),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                })
+                .collect();
+        }
+
+        let recipients = address
+            .split(',')
+            .map(str::trim)
+            .filter(|x| x.contains('@'))
+            .map(str::to_owned)
+            .collect::<Vec<_>>();
+
+        if recipients.is_empty() {
+            return Vec::new();
+        }
+
+        let (subject, body) = rest.split_once('|').unwrap_or((rest, ""));
+        let (subject, body) = (subject.trim(), body.trim());
+
+        let mut uri = format!(
+            "mailto:{}",
+            recipients
+                .iter()
+                .map(|x| percent_encode(x))
+                .collect::<Vec<_>>()
+                .join(",")
+        );
+        let parameters = [("subject", subject), ("body", body)]
+            .into_iter()
+            .filter(|(_, x)| !x.is_empty())
+            .map(|(key, value)| format!("{key}={}", percent_encode(value)))
+            .collect::<Vec<_>>();
+        if !parameters.is_empty() {
+            uri.push('?');
+            uri.push_str(&parameters.join("&"));
+        }
+
+        let description = match (subject.is_empty(), body.is_empty()) {
+            (true, _) => "No subject".to_owned(),
+            (false, true) => subject.to_owned(),
+            (false, false) => format!("{subject}  ·  {body}"),
+        };
+
+        vec![Entry {
+            name: FormattedString::plain(format!("Write to {}", recipients.join(", "))),
+            description: Some(FormattedString::plain(description)),
+            icon: EntryIcon::Name("mail-message-new".into()),
+            actions: vec![
+                EntryAction {
+                    icon: "mail-send".into(),
+                    name: "Compose".into(),
+                    function: self.compose(recipients, uri.clone()),
+                    ..Default::default()
+                },
+                EntryAction {
+                    icon: "edit-copy".into(),
+                    name: "Copy link".into(),
+                    function: EntryAction::copy(uri),
+                    key: Key::c,
+                    modifier: ModifierType::CONTROL_MASK,
+                },
+            ],
+            score: 1000,
+            ..Default::default()
+        }]
+    }
+}
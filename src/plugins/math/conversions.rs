@@ -0,0 +1,284 @@
+/// A value computed from the query, with a short description of what it is.
+pub struct Conversion {
+    pub value: String,
+    pub description: String,
+}
+
+impl Conversion {
+    pub fn new(value: impl Into<String>, description: impl Into<String>) -> Self {
+        Self {
+            value: value.into(),
+            description: description.into(),
+        }
+    }
+}
+
+/// Handles `roman 2024`, `spell 123.45` and `0b1010 in hex` style queries.
+pub fn convert(query: &str) -> Vec<Conversion> {
+    let query = query.trim();
+
+    if let Some(value) = query
+        .strip_prefix("romans ")
+        .or_else(|| query.strip_prefix("roman "))
+    {
+        return roman(value.trim());
+    }
+
+    if let Some(value) = query.strip_prefix("spell ") {
+        return spell(value.trim())
+            .map(|x| Conversion::new(x, "In words"))
+            .into_iter()
+            .collect();
+    }
+
+    let Some((value, radix)) = query
+        .split_once(" in ")
+        .or_else(|| query.split_once(" to "))
+    else {
+        return Vec::new();
+    };
+
+    let Some(value) = parse_integer(value.trim()) else {
+        return Vec::new();
+    };
+
+    let conversion = match radix.trim().to_lowercase().as_str() {
+        "hex" | "hexadecimal" => Conversion::new(format_radix(value, 16, "0x"), "Hexadecimal"),
+        "oct" | "octal" => Conversion::new(format_radix(value, 8, "0o"), "Octal"),
+        "bin" | "binary" => Conversion::new(format_radix(value, 2, "0b"), "Binary"),
+        "dec" | "decimal" => Conversion::new(value.to_string(), "Decimal"),
+        "roman" | "romans" => match to_roman(value) {
+            Some(x) => Conversion::new(x, "Roman numeral"),
+            None => return Vec::new(),
+        },
+        "words" => match spell(&value.to_string()) {
+            Some(x) => Conversion::new(x, "In words"),
+            None => return Vec::new(),
+        },
+        _ => return Vec::new(),
+    };
+
+    vec![conversion]
+}
+
+fn parse_integer(value: &str) -> Option<i128> {
+    let value = value.replace('_', "");
+    let (negative, value) = match value.strip_prefix('-') {
+        Some(value) => (true, value),
+        None => (false, value.as_str()),
+    };
+
+    let prefix = value.get(..2).map(str::to_lowercase);
+    let value = match prefix.as_deref() {
+        Some("0x") => i128::from_str_radix(&value[2..], 16),
+        Some("0o") => i128::from_str_radix(&value[2..], 8),
+        Some("0b") => i128::from_str_radix(&value[2..], 2),
+        _ => value.parse(),
+    }
+    .ok()?;
+
+    Some(if negative { -value } else { value })
+}
+
+fn format_radix(value: i128, radix: u32, prefix: &str) -> String {
+    let sign = if value < 0 { "-" } else { "" };
+    let value = value.unsigned_abs();
+
+    let digits = match radix {
+        16 => format!("{value:X}"),
+        8 => format!("{value:o}"),
+        2 => format!("{value:b}"),
+        _ => value.to_string(),
+    };
+
+    format!("{sign}{prefix}{digits}")
+}
+
+const ROMAN: [(u32, &str); 13] = [
+    (1000, "M"),
+    (900, "CM"),
+    (500, "D"),
+    (400, "CD"),
+    (100, "C"),
+    (90, "XC"),
+    (50, "L"),
+    (40, "XL"),
+    (10, "X"),
+    (9, "IX"),
+    (5, "V"),
+    (4, "IV"),
+    (1, "I"),
+];
+
+/// Converts in whichever direction makes sense for the value.
+fn roman(value: &str) -> Vec<Conversion> {
+    if let Some(value) = parse_integer(value) {
+        return to_roman(value)
+            .map(|x| Conversion::new(x, "Roman numeral"))
+            .into_iter()
+            .collect();
+    }
+
+    from_roman(value)
+        .map(|x| Conversion::new(x.to_string(), "Decimal"))
+        .into_iter()
+        .collect()
+}
+
+fn to_roman(value: i128) -> Option<String> {
+    let mut value = u32::try_from(value)
+        .ok()
+        .filter(|x| (1..4000).contains(x))?;
+    let mut result = String::new();
+
+    for (number, numeral) in ROMAN {
+        while value >= number {
+            result.push_str(numeral);
+            value -= number;
+        }
+    }
+
+    Some(result)
+}
+
+fn from_roman(value: &str) -> Option<u32> {
+    let value = value.to_uppercase();
+    let mut rest = value.as_str();
+    let mut result = 0;
+
+    for (number, numeral) in ROMAN {
+        while let Some(x) = rest.strip_prefix(numeral) {
+            result += number;
+            rest = x;
+        }
+    }
+
+    // Only accept the canonical spelling, so "IIII" or "VX" aren't read as numbers
+    (rest.is_empty() && result > 0 && to_roman(result as i128).as_deref() == Some(&value))
+        .then_some(result)
+}
+
+const ONES: [&str; 20] = [
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+
+const TENS: [&str; 10] = [
+    "", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+
+const SCALES: [&str; 12] = [
+    "",
+    "thousand",
+    "million",
+    "billion",
+    "trillion",
+    "quadrillion",
+    "quintillion",
+    "sextillion",
+    "septillion",
+    "octillion",
+    "nonillion",
+    "decillion",
+];
+
+/// Spells out a decimal number in English words.
+fn spell(value: &str) -> Option<String> {
+    let value = value.replace([',', '_'], "");
+    let (negative, value) = match value.strip_prefix('-') {
+        Some(value) => (true, value),
+        None => (false, value.as_str()),
+    };
+
+    let (integer, fraction) = value.split_once('.').unwrap_or((value, ""));
+    if integer.is_empty() && fraction.is_empty() {
+        return None;
+    }
+
+    let integer: u128 = if integer.is_empty() {
+        0
+    } else {
+        integer.parse().ok()?
+    };
+    let mut words = Vec::new();
+
+    if negative {
+        words.push("minus".to_owned());
+    }
+
+    words.push(spell_integer(integer)?);
+
+    if !fraction.is_empty() {
+        words.push("point".to_owned());
+        for digit in fraction.chars() {
+            words.push(ONES[digit.to_digit(10)? as usize].to_owned());
+        }
+    }
+
+    Some(words.join(" "))
+}
+
+fn spell_integer(mut value: u128) -> Option<String> {
+    if value == 0 {
+        return Some(ONES[0].to_owned());
+    }
+
+    let mut groups = Vec::new();
+    while value > 0 {
+        groups.push((value % 1000) as usize);
+        value /= 1000;
+    }
+
+    if groups.len() > SCALES.len() {
+        return None;
+    }
+
+    let words = groups
+        .into_iter()
+        .enumerate()
+        .rev()
+        .filter(|(_, x)| *x != 0)
+        .map(|(scale, x)| match SCALES[scale] {
+            "" => spell_hundreds(x),
+            scale => format!("{} {scale}", spell_hundreds(x)),
+        })
+        .collect::<Vec<_>>();
+
+    Some(words.join(" "))
+}
+
+fn spell_hundreds(value: usize) -> String {
+    let hundreds = value / 100;
+    let rest = value % 100;
+
+    let rest = match rest {
+        0 => None,
+        1..20 => Some(ONES[rest].to_owned()),
+        _ if rest % 10 == 0 => Some(TENS[rest / 10].to_owned()),
+        _ => Some(format!("{}-{}", TENS[rest / 10], ONES[rest % 10])),
+    };
+
+    match (hundreds, rest) {
+        (0, Some(rest)) => rest,
+        (hundreds, None) => format!("{} hundred", ONES[hundreds]),
+        (hundreds, Some(rest)) => format!("{} hundred {rest}", ONES[hundreds]),
+    }
+}
@@ -0,0 +1,33 @@
+use crate::utils::read_clipboard;
+
+pub struct Counts {
+    pub words: usize,
+    pub characters: usize,
+    pub bytes: usize,
+    pub lines: usize,
+    /// Whether the text was taken from the clipboard rather than the query.
+    pub clipboard: bool,
+}
+
+/// Handles `count some text`, or a bare `count` to count the clipboard.
+pub fn count(query: &str) -> Option<Counts> {
+    let text = query.strip_prefix("count")?;
+
+    let (text, clipboard) = if text.trim().is_empty() {
+        (read_clipboard()?, true)
+    } else {
+        (text.strip_prefix(char::is_whitespace)?.to_owned(), false)
+    };
+
+    if text.is_empty() {
+        return None;
+    }
+
+    Some(Counts {
+        words: text.split_whitespace().count(),
+        characters: text.chars().count(),
+        bytes: text.len(),
+        lines: text.lines().count(),
+        clipboard,
+    })
+}
@@ -0,0 +1,300 @@
+use chrono::{DateTime, Local, Months, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta};
+use itertools::Itertools;
+
+use super::conversions::Conversion;
+
+/// A date from the query, with whether a time was given or added.
+struct Moment {
+    value: NaiveDateTime,
+    time: bool,
+}
+
+impl Moment {
+    fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        let now = Local::now().naive_local();
+        let today = now.date().and_time(NaiveTime::MIN);
+
+        let day = |days| {
+            Some(Self {
+                value: today + TimeDelta::days(days),
+                time: false,
+            })
+        };
+
+        match value.to_lowercase().as_str() {
+            "now" => {
+                return Some(Self {
+                    value: now,
+                    time: true,
+                });
+            }
+            "today" => return day(0),
+            "tomorrow" => return day(1),
+            "yesterday" => return day(-1),
+            _ => {}
+        }
+
+        for format in ["%Y-%m-%d %H:%M", "%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S"] {
+            if let Ok(value) = NaiveDateTime::parse_from_str(value, format) {
+                return Some(Self { value, time: true });
+            }
+        }
+
+        let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()?;
+        Some(Self {
+            value: date.and_time(NaiveTime::MIN),
+            time: false,
+        })
+    }
+
+    fn format(&self) -> String {
+        if self.time {
+            self.value.format("%Y-%m-%d %H:%M").to_string()
+        } else {
+            self.value.format("%Y-%m-%d").to_string()
+        }
+    }
+
+    fn long_format(&self) -> String {
+        if self.time {
+            self.value.format("%a %-d %b %Y %H:%M").to_string()
+        } else {
+            self.value.format("%a %-d %b %Y").to_string()
+        }
+    }
+}
+
+fn plural(count: i64, unit: &str) -> String {
+    if count == 1 {
+        format!("1 {unit}")
+    } else {
+        format!("{count} {unit}s")
+    }
+}
+
+/// Like "in 12 days" or "3 days ago".
+fn relative(date: NaiveDate) -> String {
+    match (date - Local::now().date_naive()).num_days() {
+        0 => "Today".to_owned(),
+        1 => "Tomorrow".to_owned(),
+        -1 => "Yesterday".to_owned(),
+        x if x < 0 => format!("{} ago", plural(-x, "day")),
+        x => format!("in {}", plural(x, "day")),
+    }
+}
+
+/// Adds a single amount, like `6 weeks` or `-2 months`.
+fn add(moment: &mut Moment, amount: i64, unit: &str) -> Option<()> {
+    let shift_months = |value: NaiveDateTime, count: i64| -> Option<NaiveDateTime> {
+        let months = Months::new(count.unsigned_abs().try_into().ok()?);
+        if count < 0 {
+            value.checked_sub_months(months)
+        } else {
+            value.checked_add_months(months)
+        }
+    };
+
+    let (delta, time) = match unit {
+        "y" | "year" | "years" => {
+            moment.value = shift_months(moment.value, amount.checked_mul(12)?)?;
+            return Some(());
+        }
+        "mo" | "month" | "months" => {
+            moment.value = shift_months(moment.value, amount)?;
+            return Some(());
+        }
+        "w" | "week" | "weeks" => (TimeDelta::try_weeks(amount)?, false),
+        "d" | "day" | "days" => (TimeDelta::try_days(amount)?, false),
+        "h" | "hour" | "hours" => (TimeDelta::try_hours(amount)?, true),
+        "m" | "min" | "mins" | "minute" | "minutes" => (TimeDelta::try_minutes(amount)?, true),
+        "s" | "sec" | "secs" | "second" | "seconds" => (TimeDelta::try_seconds(amount)?, true),
+        _ => return None,
+    };
+
+    moment.value = moment.value.checked_add_signed(delta)?;
+    moment.time |= time;
+    Some(())
+}
+
+/// Amounts like `1 week 2 days` or `3d`, negated for subtractions.
+fn apply(moment: &mut Moment, term: &str, negative: bool) -> Option<()> {
+    let mut rest = term.trim();
+    if rest.is_empty() {
+        return None;
+    }
+
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|x: char| !x.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let amount: i64 = rest[..digits].parse().ok()?;
+        rest = rest[digits..].trim_start();
+
+        let letters = rest
+            .find(|x: char| !x.is_alphabetic())
+            .unwrap_or(rest.len());
+        let unit = rest[..letters].to_lowercase();
+        rest = rest[letters..].trim_start();
+
+        add(moment, if negative { -amount } else { amount }, &unit)?;
+    }
+
+    Some(())
+}
+
+/// Handles `today + 6 weeks`, `2025-01-01 - 3 days` or `now + 2h 30m`.
+fn arithmetic(query: &str) -> Option<Conversion> {
+    // A minus is only an operator away from digits, to keep the dashes of dates
+    let operators = query
+        .char_indices()
+        .filter(|&(i, c)| {
+            c == '+'
+                || c == '-'
+                    && query[..i]
+                        .chars()
+                        .next_back()
+                        .is_some_and(|x| !x.is_ascii_digit())
+        })
+        .collect_vec();
+
+    let &(start, _) = operators.first()?;
+    let mut moment = Moment::parse(&query[..start])?;
+
+    for (i, &(position, operator)) in operators.iter().enumerate() {
+        let end = operators.get(i + 1).map_or(query.len(), |x| x.0);
+        apply(&mut moment, &query[position + 1..end], operator == '-')?;
+    }
+
+    Some(Conversion::new(
+        moment.format(),
+        format!(
+            "{}  ·  {}",
+            moment.long_format(),
+            relative(moment.value.date())
+        ),
+    ))
+}
+
+/// Like "10 weeks 3 days", for spans of a few weeks or more.
+fn weeks(days: i64) -> Option<String> {
+    let days = days.abs();
+    (days >= 7).then(|| match days % 7 {
+        0 => plural(days / 7, "week"),
+        x => format!("{} {}", plural(days / 7, "week"), plural(x, "day")),
+    })
+}
+
+fn days_between(from: NaiveDate, to: NaiveDate, description: String) -> Conversion {
+    let days = (to - from).num_days();
+    let description = match weeks(days) {
+        Some(weeks) => format!("{description}  ·  {weeks}"),
+        None => description,
+    };
+
+    Conversion::new(plural(days.abs(), "day"), description)
+}
+
+/// Handles `days until 2025-12-25`, `days since 2024-01-01` and `days between … and …`.
+fn days(query: &str) -> Option<Conversion> {
+    let query = query.strip_prefix("days ")?.trim();
+    let today = Local::now().date_naive();
+
+    if let Some(date) = query
+        .strip_prefix("until ")
+        .or_else(|| query.strip_prefix("to "))
+    {
+        let date = Moment::parse(date)?;
+        return Some(days_between(
+            today,
+            date.value.date(),
+            format!("Until {}", date.long_format()),
+        ));
+    }
+
+    if let Some(date) = query.strip_prefix("since ") {
+        let date = Moment::parse(date)?;
+        return Some(days_between(
+            date.value.date(),
+            today,
+            format!("Since {}", date.long_format()),
+        ));
+    }
+
+    let (from, to) = query.strip_prefix("between ")?.split_once(" and ")?;
+    let (from, to) = (Moment::parse(from)?, Moment::parse(to)?);
+    Some(days_between(
+        from.value.date(),
+        to.value.date(),
+        format!("From {} to {}", from.long_format(), to.long_format()),
+    ))
+}
+
+/// Handles `unix` for the current timestamp, `unix 1717171717` and `unix 2025-12-25`.
+fn unix(query: &str) -> Vec<Conversion> {
+    let Some(value) = query.strip_prefix("unix") else {
+        return Vec::new();
+    };
+    let value = value.trim();
+
+    if value.is_empty() {
+        return vec![Conversion::new(
+            Local::now().timestamp().to_string(),
+            "Now as a Unix timestamp",
+        )];
+    }
+
+    if let Ok(timestamp) = value.parse::<i64>() {
+        // Too far in the future for seconds, those are milliseconds
+        let time = if timestamp.abs() >= 100_000_000_000 {
+            DateTime::from_timestamp_millis(timestamp)
+        } else {
+            DateTime::from_timestamp(timestamp, 0)
+        };
+
+        let Some(time) = time else {
+            return Vec::new();
+        };
+        let local = time.with_timezone(&Local);
+
+        return vec![
+            Conversion::new(
+                local.format("%Y-%m-%d %H:%M:%S").to_string(),
+                format!(
+                    "{}  ·  {}",
+                    local.format("%a %-d %b %Y"),
+                    relative(local.date_naive())
+                ),
+            ),
+            Conversion::new(
+                time.format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+                "UTC, in ISO 8601",
+            ),
+        ];
+    }
+
+    let Some(moment) = Moment::parse(value) else {
+        return Vec::new();
+    };
+    let Some(local) = moment.value.and_local_timezone(Local).earliest() else {
+        return Vec::new();
+    };
+
+    vec![Conversion::new(
+        local.timestamp().to_string(),
+        format!("{} as a Unix timestamp", moment.long_format()),
+    )]
+}
+
+/// Date arithmetic, the days between dates and Unix timestamps.
+pub fn calculate(query: &str) -> Vec<Conversion> {
+    let query = query.trim();
+
+    let mut conversions = unix(query);
+    conversions.extend(days(query));
+    if conversions.is_empty() {
+        conversions.extend(arithmetic(query));
+    }
+
+    conversions
+}
@@ -0,0 +1,192 @@
+mod conversions;
+mod count;
+mod dates;
+
+use std::time::Instant;
+
+use fend_core::SpanKind;
+use itertools::Itertools;
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+    Entry, Plugin,
+    interface::{Context, EntryAction, EntryIcon, FormatStyle, FormattedString, Preview},
+};
+
+#[derive(Debug)]
+pub struct Math {
+    context: fend_core::Context,
+}
+
+impl Math {
+    pub fn new(_: &Context) -> Self {
+        let context = fend_core::Context::new();
+        Self { context }
+    }
+
+    fn evaluate(&self, query: &str) -> Option<Entry> {
+        let val = fend_core::evaluate_preview_with_interrupt(
+            query,
+            &mut self.context.clone(),
+            &CustomInterrupt(Instant::now()),
+        );
+
+        if val.get_main_result().is_empty() && val.is_unit_type() {
+            None
+        } else {
+            let mut spans = val.get_main_result_spans().collect_vec().into_iter();
+            let mut parts = spans
+                .take_while_ref(|x| x.kind() == SpanKind::Ident)
+                .map(|x| {
+                    let x = x.string();
+                    (
+                        x,
+                        if x == "approx. " {
+                            Some(FormatStyle::Highlight)
+                        } else {
+                            None
+                        },
+                    )
+                })
+                .collect_vec();
+            let string: String = spans
+                .take_while_ref(|x| x.kind() != SpanKind::Ident)
+                .filter(|x| !x.string().is_empty())
+                .map(|x| x.string().to_owned())
+                .collect();
+            parts.push((&string, None));
+            let units: String = spans
+                .filter(|x| !x.string().is_empty())
+                .enumerate()
+                .map(|(i, x)| {
+                    // 0xA2C9FEFF
+                    if i == 0 {
+                        x.string().trim_start()
+                    } else {
+                        x.string()
+                    }
+                })
+                .collect();
+
+            let val = Entry {
+                name: FormattedString::from_styles(parts),
+                tag: None,
+                description: Some(FormattedString::plain(units)),
+                icon: EntryIcon::Name("accessories-calculator".to_string()),
+                small_icon: EntryIcon::None,
+                actions: vec![EntryAction {
+                    icon: "edit-copy".into(),
+                    name: "Copy".into(),
+                    function: EntryAction::copy(
+                        val.get_main_result().trim_start_matches("approx. "),
+                    ),
+                    ..Default::default()
+                }],
+                id: "".to_owned(),
+                // Words like "pi" or "day" also evaluate, only rank actual calculations first
+                score: if query.contains(|x: char| x.is_ascii_digit()) {
+                    1000
+                } else {
+                    0
+                },
+                ..Default::default()
+            };
+
+            Some(val)
+        }
+    }
+}
+
+struct CustomInterrupt(Instant);
+
+impl fend_core::Interrupt for CustomInterrupt {
+    fn should_interrupt(&self) -> bool {
+        self.0.elapsed().as_millis() > 500
+    }
+}
+
+impl Plugin for Math {
+    fn name(&self) -> &str {
+        "Calculator"
+    }
+
+    fn icon(&self) -> Option<&str> {
+        Some("accessories-calculator")
+    }
+
+    fn search(&self, query: &str, _: &Context, _: &CancellationToken) -> Vec<Entry> {
+        let dates = dates::calculate(query);
+        // Fend reads dates like `2025-01-01` as subtractions
+        let evaluated = if dates.is_empty() {
+            self.evaluate(query)
+        } else {
+            None
+        };
+
+        conversions::convert(query)
+            .into_iter()
+            .chain(dates)
+            .map(|x| Entry {
+                name: FormattedString::plain(&x.value),
+                description: Some(FormattedString::plain(x.description)),
+                icon: EntryIcon::Name("accessories-calculator".to_string()),
+                actions: vec![EntryAction {
+                    icon: "edit-copy".into(),
+                    name: "Copy".into(),
+                    function: EntryAction::copy(x.value),
+                    ..Default::default()
+                }],
+                score: 1000,
+                ..Default::default()
+            })
+            .chain(count::count(query).map(count_to_entry))
+            .chain(evaluated)
+            .collect()
+    }
+
+    /// The whole result, which may be too long for the row.
+    fn preview(&self, entry: &Entry) -> Option<Preview> {
+        let mut text = entry.name.text.clone();
+        if let Some(description) = &entry.description {
+            text.push('\n');
+            text.push_str(&description.text);
+        }
+
+        Some(Preview::Code(text))
+    }
+}
+
+fn count_to_entry(counts: count::Counts) -> Entry {
+    let copy = |icon: &str, name: &str, value: usize| EntryAction {
+        icon: icon.into(),
+        name: name.into(),
+        function: EntryAction::copy(value.to_string()),
+        ..Default::default()
+    };
+
+    Entry {
+        name: FormattedString::plain(format!(
+            "{} words  ·  {} characters",
+            counts.words, counts.characters
+        )),
+        description: Some(FormattedString::plain(format!(
+            "{} bytes  ·  {} lines{}",
+            counts.bytes,
+            counts.lines,
+            if counts.clipboard {
+                "  ·  Clipboard"
+            } else {
+                ""
+            }
+        ))),
+        icon: EntryIcon::Name("accessories-text-editor".to_string()),
+        actions: vec![
+            copy("edit-copy", "Copy words", counts.words),
+            copy("edit-copy", "Copy characters", counts.characters),
+            copy("edit-copy", "Copy bytes", counts.bytes),
+            copy("edit-copy", "Copy lines", counts.lines),
+        ],
+        score: 1000,
+        ..Default::default()
+    }
+}
@@ -0,0 +1,103 @@
+use std::process::Command;
+use std::sync::Arc;
+
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use parking_lot::Mutex;
+use tokio_util::sync::CancellationToken;
+
+use crate::interface::{Context, Entry, EntryAction, EntryIcon, FormattedString, Plugin};
+
+const SOURCE: &str = "@DEFAULT_AUDIO_SOURCE@";
+
+/// Mutes and unmutes the default microphone through WirePlumber.
+#[derive(Debug)]
+pub struct Microphone {
+    /// Read when the launcher is shown, `None` without a microphone or `wpctl`.
+    muted: Arc<Mutex<Option<bool>>>,
+}
+
+impl Microphone {
+    pub fn new(_: &Context) -> Self {
+        Self {
+            muted: Arc::new(Mutex::new(None)),
+        }
+    }
+}
+
+/// Parses the output of `wpctl get-volume`, like `Volume: 0.40 [MUTED]`.
+fn is_muted() -> Option<bool> {
+    let output = match Command::new("wpctl").args(["get-volume", SOURCE]).output() {
+        Ok(x) if x.status.success() => x,
+        Ok(_) => return None,
+        Err(error) => {
+            tracing::debug!(?error, "Failed to run wpctl");
+            return None;
+        }
+    };
+
+    let output = String::from_utf8_lossy(&output.stdout);
+    output
+        .starts_with("Volume:")
+        .then(|| output.contains("[MUTED]"))
+}
+
+impl Plugin for Microphone {
+    fn open(&mut self) {
+        let muted = self.muted.clone();
+        std::thread::spawn(move || {
+            let loaded = is_muted();
+            *muted.lock() = loaded;
+        });
+    }
+
+    fn name(&self) -> &str {
+        "Microphone"
+    }
+
+    fn icon(&self) -> Option<&str> {
+        Some("audio-input-microphone")
+    }
+
+    fn search(&self, query: &str, _: &Context, _: &CancellationToken) -> Vec<Entry> {
+        let Some(muted) = *self.muted.lock() else {
+            return Vec::new();
+        };
+
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let (title, icon) = if muted {
+            ("Unmute microphone", "microphone-sensitivity-muted")
+        } else {
+            ("Mute microphone", "audio-input-microphone")
+        };
+
+        let Some(score) = SkimMatcherV2::default()
+            .ignore_case()
+            .fuzzy_match(&format!("{title} mic toggle"), query)
+        else {
+            return Vec::new();
+        };
+
+        vec![Entry {
+            name: FormattedString::plain(title),
+            tag: Some(FormattedString::plain(if muted { "Muted" } else { "Live" })),
+            icon: EntryIcon::Name(icon.into()),
+            actions: vec![EntryAction {
+                icon: icon.into(),
+                name: "Toggle".into(),
+                function: EntryAction::command(
+                    "wpctl".into(),
+                    vec!["set-mute".into(), SOURCE.into(), "toggle".into()],
+                    None,
+                ),
+                ..Default::default()
+            }],
+            id: "microphone:toggle".into(),
+            score: score.try_into().unwrap_or_default(),
+            ..Default::default()
+        }]
+    }
+}
@@ -1,11 +1,57 @@
+pub(crate) mod aliases;
+pub(crate) mod appimages;
 pub(crate) mod applications;
+pub(crate) mod audio;
+pub(crate) mod bitwarden;
+pub(crate) mod bookmarks;
+pub(crate) mod checksum;
 pub(crate) mod clipboard;
 pub(crate) mod commands;
+pub(crate) mod containers;
+pub(crate) mod controls;
+pub(crate) mod countdown;
+pub(crate) mod devices;
+pub(crate) mod dictionary;
+pub(crate) mod dmenu;
 pub(crate) mod emoji;
+pub(crate) mod external;
+pub(crate) mod feeds;
 pub(crate) mod files;
+pub(crate) mod flatpak;
+pub(crate) mod grep;
+pub(crate) mod habits;
+pub(crate) mod history;
 pub(crate) mod hyprland;
 pub(crate) mod icons;
+pub(crate) mod jetbrains;
+pub(crate) mod json;
+pub(crate) mod kubernetes;
+pub(crate) mod launcher;
+pub(crate) mod links;
+pub(crate) mod machines;
+pub(crate) mod mail;
 pub(crate) mod math;
+pub(crate) mod microphone;
+pub(crate) mod native;
+pub(crate) mod ocr;
+pub(crate) mod pomodoro;
+pub(crate) mod projects;
+pub(crate) mod recent;
+pub(crate) mod scratchpad;
+pub(crate) mod scripts;
+pub(crate) mod secrets;
+pub(crate) mod shortcuts;
+pub(crate) mod snippets;
+pub(crate) mod spell;
 pub(crate) mod ssh;
+pub(crate) mod stocks;
+pub(crate) mod tabs;
+pub(crate) mod timers;
+pub(crate) mod transit;
 pub(crate) mod unicode;
 pub(crate) mod web;
+pub(crate) mod wifi;
+pub(crate) mod wikipedia;
+pub(crate) mod wine;
+pub(crate) mod zellij;
+pub(crate) mod zoxide;
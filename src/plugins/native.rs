@@ -0,0 +1,239 @@
+//! Plugins compiled to shared libraries, loaded from `$XDG_DATA_HOME/jogger/plugins`.
+//!
+//! A library exports `jogger_plugin_v1`, returning a pointer to a static
+//! `JoggerPlugin`. Its `request` function takes the same JSON requests as
+//! external plugins and returns the JSON response, which is handed back to
+//! `free_string` once it has been read:
+//!
+//! ```c
+//! typedef struct {
+//!     uint32_t abi_version; // 1
+//!     const char *name;
+//!     const char *icon; // Can be NULL
+//!     void *(*create)(const char *options_json);
+//!     char *(*request)(void *state, const char *request_json);
+//!     void (*free_string)(char *response);
+//!     void (*destroy)(void *state);
+//! } JoggerPlugin;
+//!
+//! const JoggerPlugin *jogger_plugin_v1(void);
+//! ```
+
+use std::ffi::{CStr, CString, c_char, c_void};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use tokio_util::sync::CancellationToken;
+use xdg::BaseDirectories;
+
+use crate::interface::{ActionResult, ActionType, Context, Entry, Plugin, parse_options};
+use crate::plugins::external::{ActivateResponse, Request, SearchResponse};
+
+const ABI_VERSION: u32 = 1;
+const ENTRY_POINT: &[u8] = b"jogger_plugin_v1";
+
+#[repr(C)]
+struct PluginVTable {
+    abi_version: u32,
+    name: *const c_char,
+    icon: *const c_char,
+    create: unsafe extern "C" fn(options: *const c_char) -> *mut c_void,
+    request: unsafe extern "C" fn(state: *mut c_void, request: *const c_char) -> *mut c_char,
+    free_string: unsafe extern "C" fn(response: *mut c_char),
+    destroy: unsafe extern "C" fn(state: *mut c_void),
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct NativeConfig {
+    path: PathBuf,
+}
+
+/// The plugin libraries in the data directory, sorted by name.
+pub fn discover() -> Vec<PathBuf> {
+    let Some(directory) = BaseDirectories::with_prefix("jogger")
+        .ok()
+        .map(|x| x.get_data_home().join("plugins"))
+    else {
+        return Vec::new();
+    };
+
+    let Ok(entries) = std::fs::read_dir(directory) else {
+        return Vec::new();
+    };
+
+    let mut paths = entries
+        .filter_map(|x| Some(x.ok()?.path()))
+        .filter(|x| x.extension().is_some_and(|x| x == "so"))
+        .collect::<Vec<_>>();
+    paths.sort();
+    paths
+}
+
+#[derive(Debug)]
+struct Library {
+    vtable: *const PluginVTable,
+    state: *mut c_void,
+    /// Must outlive the pointers above, so it's dropped last.
+    _library: libloading::Library,
+}
+
+// The state is only ever used behind the mutex of the plugin
+unsafe impl Send for Library {}
+
+impl Library {
+    fn load(path: &Path, options: &toml::Table) -> Result<Self, String> {
+        let library = unsafe { libloading::Library::new(path) }.map_err(|x| x.to_string())?;
+
+        let vtable = unsafe {
+            let entry_point = library
+                .get::<unsafe extern "C" fn() -> *const PluginVTable>(ENTRY_POINT)
+                .map_err(|x| x.to_string())?;
+            entry_point()
+        };
+
+        if vtable.is_null() {
+            return Err("the entry point returned nothing".into());
+        }
+
+        let version = unsafe { (*vtable).abi_version };
+        if version != ABI_VERSION {
+            return Err(format!("unsupported ABI version {version}"));
+        }
+
+        let options = CString::new(serde_json::to_string(options).unwrap_or_default())
+            .map_err(|x| x.to_string())?;
+        let state = unsafe { ((*vtable).create)(options.as_ptr()) };
+
+        Ok(Self {
+            vtable,
+            state,
+            _library: library,
+        })
+    }
+
+    fn string(&self, pointer: *const c_char) -> Option<String> {
+        if pointer.is_null() {
+            return None;
+        }
+
+        Some(
+            unsafe { CStr::from_ptr(pointer) }
+                .to_string_lossy()
+                .into_owned(),
+        )
+    }
+
+    fn request<T: DeserializeOwned>(&self, request: &Request) -> Option<T> {
+        let request = CString::new(serde_json::to_string(request).ok()?).ok()?;
+
+        let vtable = unsafe { &*self.vtable };
+        let response = unsafe { (vtable.request)(self.state, request.as_ptr()) };
+        if response.is_null() {
+            return None;
+        }
+
+        let text = self.string(response);
+        unsafe { (vtable.free_string)(response) };
+
+        match serde_json::from_str(&text?) {
+            Ok(x) => Some(x),
+            Err(error) => {
+                tracing::warn!(%error, "Invalid response from native plugin");
+                None
+            }
+        }
+    }
+}
+
+impl Drop for Library {
+    fn drop(&mut self) {
+        unsafe { ((*self.vtable).destroy)(self.state) };
+    }
+}
+
+#[derive(Debug)]
+pub struct Native {
+    name: String,
+    icon: Option<String>,
+    library: Option<Arc<Mutex<Library>>>,
+}
+
+impl Native {
+    pub fn new(_: &Context, options: &toml::Table) -> Self {
+        let config: NativeConfig = parse_options(options);
+        let path = expanduser::expanduser(config.path.to_string_lossy()).unwrap_or(config.path);
+        let fallback = path
+            .file_stem()
+            .map(|x| x.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        let mut options = options.clone();
+        options.remove("path");
+
+        match Library::load(&path, &options) {
+            Ok(library) => {
+                let vtable = unsafe { &*library.vtable };
+                Self {
+                    name: library.string(vtable.name).unwrap_or(fallback),
+                    icon: library.string(vtable.icon),
+                    library: Some(Arc::new(Mutex::new(library))),
+                }
+            }
+            Err(error) => {
+                tracing::error!(path = %path.display(), %error, "Failed to load plugin");
+                Self {
+                    name: fallback,
+                    icon: None,
+                    library: None,
+                }
+            }
+        }
+    }
+}
+
+fn activate(library: Arc<Mutex<Library>>, entry: String, action: String) -> Box<ActionType> {
+    Box::new(move |_| {
+        let request = Request::Activate {
+            entry: &entry,
+            action: &action,
+        };
+
+        match library.lock().request::<ActivateResponse>(&request) {
+            Some(response) => response.result.into(),
+            None => ActionResult::Error,
+        }
+    })
+}
+
+impl Plugin for Native {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn icon(&self) -> Option<&str> {
+        self.icon.as_deref()
+    }
+
+    fn search(&self, query: &str, _: &Context, _: &CancellationToken) -> Vec<Entry> {
+        let Some(library) = &self.library else {
+            return Vec::new();
+        };
+
+        let Some(response) = library
+            .lock()
+            .request::<SearchResponse>(&Request::Search { query })
+        else {
+            return Vec::new();
+        };
+
+        response
+            .entries
+            .into_iter()
+            .map(|entry| entry.into_entry(|entry, action| activate(library.clone(), entry, action)))
+            .collect()
+    }
+}
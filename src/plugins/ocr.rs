@@ -0,0 +1,180 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use serde::Deserialize;
+use tokio_util::sync::CancellationToken;
+
+use crate::interface::{
+    ActionResult, ActionType, Context, Entry, EntryAction, EntryIcon, FormattedString, Plugin,
+    Preview, parse_options,
+};
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+struct OcrConfig {
+    /// Tesseract languages, like `eng` or `eng+deu`.
+    language: String,
+}
+
+impl Default for OcrConfig {
+    fn default() -> Self {
+        Self {
+            language: "eng".into(),
+        }
+    }
+}
+
+/// Lets the user select a region with slurp, and reads the text in it with tesseract.
+fn recognize(language: &str) -> Option<String> {
+    let region = Command::new("slurp").output().ok()?;
+    // Cancelled with Escape
+    if !region.status.success() {
+        return None;
+    }
+
+    let geometry = String::from_utf8_lossy(&region.stdout).trim().to_owned();
+    let image = Command::new("grim")
+        .args(["-g", &geometry, "-"])
+        .output()
+        .ok()?;
+    if !image.status.success() {
+        tracing::warn!(stderr = %String::from_utf8_lossy(&image.stderr), "grim failed");
+        return None;
+    }
+
+    let mut tesseract = Command::new("tesseract")
+        .args(["stdin", "stdout", "-l", language])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+    tesseract.stdin.take()?.write_all(&image.stdout).ok()?;
+    let output = tesseract.wait_with_output().ok()?;
+
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+}
+
+/// Brings the launcher back with the plugin's query, so the text shows up.
+fn show_result() {
+    let result = dbus::blocking::Connection::new_session().and_then(|conn| {
+        conn.with_proxy(
+            "com.psyvern.jogger.jogger",
+            "/com/psyvern/jogger",
+            Duration::from_millis(5000),
+        )
+        .method_call::<(bool,), _, _, _>("com.psyvern.jogger", "ShowWithQuery", ("ocr",))
+    });
+
+    if let Err(error) = result {
+        tracing::warn!(%error, "Failed to show the recognized text");
+    }
+}
+
+fn capture(language: String, last: Arc<Mutex<Option<String>>>) -> Box<ActionType> {
+    Box::new(move |_| {
+        let language = language.clone();
+        let last = last.clone();
+
+        std::thread::spawn(move || {
+            // Gives the window time to hide, so it isn't in the way
+            std::thread::sleep(Duration::from_millis(300));
+
+            let Some(text) = recognize(&language) else {
+                return;
+            };
+
+            *last.lock() = Some(text);
+            show_result();
+        });
+
+        ActionResult::Ok
+    })
+}
+
+#[derive(Debug)]
+pub struct Ocr {
+    language: String,
+    /// The text read by the last capture.
+    last: Arc<Mutex<Option<String>>>,
+}
+
+impl Ocr {
+    pub fn new(_: &Context, options: &toml::Table) -> Self {
+        let config: OcrConfig = parse_options(options);
+
+        Self {
+            language: config.language,
+            last: Arc::new(Mutex::new(None)),
+        }
+    }
+}
+
+impl Plugin for Ocr {
+    fn name(&self) -> &str {
+        "Text recognition"
+    }
+
+    fn icon(&self) -> Option<&str> {
+        Some("scanner")
+    }
+
+    fn search(&self, _: &str, _: &Context, _: &CancellationToken) -> Vec<Entry> {
+        let mut entries = Vec::new();
+
+        if let Some(text) = self.last.lock().as_ref() {
+            let (name, description) = match text.split_once('\n') {
+                Some((first, _)) => (first, Some(format!("{} lines", text.lines().count()))),
+                None => (text.as_str(), None),
+            };
+
+            entries.push(Entry {
+                name: FormattedString::plain(if text.is_empty() {
+                    "No text found"
+                } else {
+                    name
+                }),
+                tag: Some(FormattedString::plain("Recognized")),
+                description: description.map(FormattedString::plain),
+                icon: EntryIcon::Name("edit-copy".into()),
+                actions: vec![EntryAction {
+                    icon: "edit-copy".into(),
+                    name: "Copy".into(),
+                    function: EntryAction::copy(text),
+                    ..Default::default()
+                }],
+                id: "ocr:last".into(),
+                score: 1,
+                ..Default::default()
+            });
+        }
+
+        entries.push(Entry {
+            name: FormattedString::plain("Capture text from screen"),
+            description: Some(FormattedString::plain(
+                "Select a region to read the text in it",
+            )),
+            icon: EntryIcon::Name("scanner".into()),
+            actions: vec![EntryAction {
+                icon: "scanner".into(),
+                name: "Capture".into(),
+                function: capture(self.language.clone(), self.last.clone()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        });
+
+        entries
+    }
+
+    fn preview(&self, entry: &Entry) -> Option<Preview> {
+        if entry.id != "ocr:last" {
+            return None;
+        }
+
+        self.last.lock().clone().map(Preview::Text)
+    }
+}
@@ -0,0 +1,384 @@
+use std::path::PathBuf;
+use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant};
+
+use chrono::Local;
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use gtk::gdk::{Key, ModifierType};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tokio_util::sync::CancellationToken;
+use xdg::BaseDirectories;
+
+use crate::interface::{
+    ActionResult, ActionType, Context, Entry, EntryAction, EntryIcon, FormattedString, Plugin,
+    parse_options,
+};
+use crate::utils::notify;
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+struct PomodoroConfig {
+    /// In minutes.
+    work: u64,
+    short_break: u64,
+    long_break: u64,
+    /// Work sessions before a long break.
+    sessions: u32,
+}
+
+impl Default for PomodoroConfig {
+    fn default() -> Self {
+        Self {
+            work: 25,
+            short_break: 5,
+            long_break: 15,
+            sessions: 4,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    Work,
+    ShortBreak,
+    LongBreak,
+}
+
+impl Phase {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Work => "Focus",
+            Self::ShortBreak => "Short break",
+            Self::LongBreak => "Long break",
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct History {
+    /// Like `2024-05-17`, the sessions of other days don't count.
+    date: String,
+    sessions: u32,
+}
+
+#[derive(Debug)]
+enum Clock {
+    Running { until: Instant },
+    Paused { remaining: Duration },
+}
+
+#[derive(Debug)]
+struct Timer {
+    phase: Phase,
+    /// `None` while stopped.
+    clock: Option<Clock>,
+    /// Work sessions since the last long break.
+    streak: u32,
+    history: History,
+    path: Option<PathBuf>,
+}
+
+impl Timer {
+    fn remaining(&self) -> Option<Duration> {
+        match self.clock.as_ref()? {
+            Clock::Running { until } => Some(until.saturating_duration_since(Instant::now())),
+            Clock::Paused { remaining } => Some(*remaining),
+        }
+    }
+
+    fn sessions_today(&self) -> u32 {
+        if self.history.date == today() {
+            self.history.sessions
+        } else {
+            0
+        }
+    }
+
+    fn record_session(&mut self) {
+        let today = today();
+        if self.history.date != today {
+            self.history = History {
+                date: today,
+                sessions: 0,
+            };
+        }
+        self.history.sessions += 1;
+
+        let Some(path) = &self.path else {
+            return;
+        };
+        match serde_json::to_string(&self.history) {
+            Ok(content) => {
+                if let Err(error) = std::fs::write(path, content) {
+                    tracing::error!(?error, "Failed to save pomodoro sessions");
+                }
+            }
+            Err(error) => tracing::error!(?error, "Failed to serialize pomodoro sessions"),
+        }
+    }
+}
+
+fn today() -> String {
+    Local::now().format("%Y-%m-%d").to_string()
+}
+
+#[derive(Debug)]
+pub struct Pomodoro {
+    work: Duration,
+    short_break: Duration,
+    long_break: Duration,
+    sessions: u32,
+    timer: Arc<Mutex<Timer>>,
+}
+
+impl Pomodoro {
+    pub fn new(_: &Context, options: &toml::Table) -> Self {
+        let config: PomodoroConfig = parse_options(options);
+
+        let path = BaseDirectories::with_prefix("jogger")
+            .ok()
+            .and_then(|x| x.place_state_file("pomodoro.json").ok());
+        let history = path
+            .as_ref()
+            .and_then(|x| std::fs::read_to_string(x).ok())
+            .and_then(|x| serde_json::from_str(&x).ok())
+            .unwrap_or_default();
+
+        let plugin = Self {
+            work: Duration::from_secs(config.work * 60),
+            short_break: Duration::from_secs(config.short_break * 60),
+            long_break: Duration::from_secs(config.long_break * 60),
+            sessions: config.sessions.max(1),
+            timer: Arc::new(Mutex::new(Timer {
+                phase: Phase::Work,
+                clock: None,
+                streak: 0,
+                history,
+                path,
+            })),
+        };
+
+        plugin.watch();
+        plugin
+    }
+
+    fn length(&self, phase: Phase) -> Duration {
+        match phase {
+            Phase::Work => self.work,
+            Phase::ShortBreak => self.short_break,
+            Phase::LongBreak => self.long_break,
+        }
+    }
+
+    /// Moves on to the next phase once the current one is over, until the plugin is dropped.
+    fn watch(&self) {
+        let timer = Arc::downgrade(&self.timer);
+        let lengths = [self.work, self.short_break, self.long_break];
+        let sessions = self.sessions;
+
+        std::thread::spawn(move || {
+            while let Some(timer) = Weak::upgrade(&timer) {
+                {
+                    let mut timer = timer.lock();
+                    if matches!(timer.clock, Some(Clock::Running { .. }))
+                        && timer.remaining().is_some_and(|x| x.is_zero())
+                    {
+                        advance(&mut timer, lengths, sessions, true);
+                    }
+                }
+
+                drop(timer);
+                std::thread::sleep(Duration::from_secs(1));
+            }
+        });
+    }
+
+    fn action(&self, function: fn(&mut Timer, [Duration; 3], u32)) -> Box<ActionType> {
+        let timer = self.timer.clone();
+        let lengths = [self.work, self.short_break, self.long_break];
+        let sessions = self.sessions;
+
+        Box::new(move |_| {
+            function(&mut timer.lock(), lengths, sessions);
+            ActionResult::Refresh
+        })
+    }
+}
+
+/// Starts the phase after the current one, counting the work session if it was finished.
+fn advance(
+    timer: &mut Timer,
+    [work, short_break, long_break]: [Duration; 3],
+    sessions: u32,
+    finished: bool,
+) {
+    let next = match timer.phase {
+        Phase::Work => {
+            if finished {
+                timer.record_session();
+            }
+            timer.streak += 1;
+
+            if timer.streak >= sessions {
+                timer.streak = 0;
+                Phase::LongBreak
+            } else {
+                Phase::ShortBreak
+            }
+        }
+        Phase::ShortBreak | Phase::LongBreak => Phase::Work,
+    };
+
+    let length = match next {
+        Phase::Work => work,
+        Phase::ShortBreak => short_break,
+        Phase::LongBreak => long_break,
+    };
+
+    timer.phase = next;
+    timer.clock = Some(Clock::Running {
+        until: Instant::now() + length,
+    });
+
+    if finished {
+        let body = match next {
+            Phase::Work => "Back to work".to_owned(),
+            _ => format!("{} sessions done today", timer.sessions_today()),
+        };
+        notify(&format!("{} started", next.name()), &body, "alarm");
+    }
+}
+
+fn start(timer: &mut Timer, [work, ..]: [Duration; 3], _: u32) {
+    timer.phase = Phase::Work;
+    timer.clock = Some(Clock::Running {
+        until: Instant::now() + work,
+    });
+}
+
+fn toggle(timer: &mut Timer, _: [Duration; 3], _: u32) {
+    let Some(remaining) = timer.remaining() else {
+        return;
+    };
+
+    timer.clock = Some(match timer.clock {
+        Some(Clock::Running { .. }) => Clock::Paused { remaining },
+        _ => Clock::Running {
+            until: Instant::now() + remaining,
+        },
+    });
+}
+
+fn skip(timer: &mut Timer, lengths: [Duration; 3], sessions: u32) {
+    advance(timer, lengths, sessions, false);
+}
+
+fn stop(timer: &mut Timer, _: [Duration; 3], _: u32) {
+    timer.phase = Phase::Work;
+    timer.clock = None;
+    timer.streak = 0;
+}
+
+impl Plugin for Pomodoro {
+    fn name(&self) -> &str {
+        "Pomodoro"
+    }
+
+    fn icon(&self) -> Option<&str> {
+        Some("alarm")
+    }
+
+    fn search(&self, query: &str, _: &Context, _: &CancellationToken) -> Vec<Entry> {
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let Some(score) = SkimMatcherV2::default()
+            .ignore_case()
+            .fuzzy_match("Pomodoro focus timer", query)
+        else {
+            return Vec::new();
+        };
+        let score = score.try_into().unwrap_or_default();
+
+        let timer = self.timer.lock();
+        let today = match timer.sessions_today() {
+            1 => "1 session today".to_owned(),
+            x => format!("{x} sessions today"),
+        };
+
+        let Some(remaining) = timer.remaining() else {
+            return vec![Entry {
+                name: FormattedString::plain("Start pomodoro"),
+                description: Some(FormattedString::plain(format!(
+                    "{} minutes of focus  ·  {today}",
+                    self.length(Phase::Work).as_secs() / 60
+                ))),
+                icon: EntryIcon::Name("alarm".into()),
+                actions: vec![EntryAction {
+                    icon: "media-playback-start".into(),
+                    name: "Start".into(),
+                    function: self.action(start),
+                    ..Default::default()
+                }],
+                score,
+                ..Default::default()
+            }];
+        };
+
+        let running = matches!(timer.clock, Some(Clock::Running { .. }));
+        let seconds = remaining.as_secs();
+
+        vec![Entry {
+            name: FormattedString::plain(format!(
+                "{}  {:02}:{:02}",
+                timer.phase.name(),
+                seconds / 60,
+                seconds % 60
+            )),
+            tag: Some(FormattedString::plain(if running {
+                "Running"
+            } else {
+                "Paused"
+            })),
+            description: Some(FormattedString::plain(today)),
+            icon: EntryIcon::Name("alarm".into()),
+            actions: vec![
+                EntryAction {
+                    icon: if running {
+                        "media-playback-pause"
+                    } else {
+                        "media-playback-start"
+                    }
+                    .into(),
+                    name: if running { "Pause" } else { "Resume" }.into(),
+                    function: self.action(toggle),
+                    ..Default::default()
+                },
+                EntryAction {
+                    icon: "media-skip-forward".into(),
+                    name: "Skip".into(),
+                    function: self.action(skip),
+                    key: Key::s,
+                    modifier: ModifierType::CONTROL_MASK,
+                },
+                EntryAction {
+                    icon: "media-playback-stop".into(),
+                    name: "Stop".into(),
+                    function: self.action(stop),
+                    key: Key::Delete,
+                    modifier: ModifierType::CONTROL_MASK,
+                },
+            ],
+            score,
+            ..Default::default()
+        }]
+    }
+
+    fn refresh_interval(&self) -> Option<Duration> {
+        matches!(self.timer.lock().clock, Some(Clock::Running { .. }))
+            .then_some(Duration::from_secs(1))
+    }
+}
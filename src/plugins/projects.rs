@@ -0,0 +1,259 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Arc;
+
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use gtk::gdk::{Key, ModifierType};
+use parking_lot::Mutex;
+use serde::Deserialize;
+use tokio_util::sync::CancellationToken;
+
+use crate::interface::{
+    Context, Entry, EntryAction, EntryIcon, FormatStyle, FormattedString, Plugin, parse_options,
+};
+
+/// Directories that never hold projects of their own.
+const SKIPPED: [&str; 3] = ["node_modules", "target", "vendor"];
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+struct ProjectsConfig {
+    /// Searched for repositories, those that don't exist are skipped.
+    roots: Vec<String>,
+    /// How many directories deep repositories are looked for under the roots.
+    depth: usize,
+}
+
+impl Default for ProjectsConfig {
+    fn default() -> Self {
+        Self {
+            roots: ["~/Projects", "~/projects", "~/src", "~/code", "~/dev"]
+                .map(str::to_owned)
+                .to_vec(),
+            depth: 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Project {
+    name: String,
+    path: PathBuf,
+    /// The short commit hash when detached.
+    branch: Option<String>,
+    dirty: bool,
+}
+
+fn find_repositories(directory: &Path, depth: usize, repositories: &mut Vec<PathBuf>) {
+    if directory.join(".git").exists() {
+        repositories.push(directory.to_owned());
+        return;
+    }
+
+    if depth == 0 {
+        return;
+    }
+
+    let Ok(children) = std::fs::read_dir(directory) else {
+        return;
+    };
+
+    for child in children.filter_map(Result::ok) {
+        let name = child.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with('.') || SKIPPED.contains(&name.as_ref()) {
+            continue;
+        }
+
+        if child.file_type().is_ok_and(|x| x.is_dir()) {
+            find_repositories(&child.path(), depth - 1, repositories);
+        }
+    }
+}
+
+/// Read from the files rather than asking git, worktrees point to their git directory.
+fn branch(path: &Path) -> Option<String> {
+    let mut git = path.join(".git");
+    if git.is_file() {
+        let content = std::fs::read_to_string(&git).ok()?;
+        git = path.join(content.strip_prefix("gitdir:")?.trim());
+    }
+
+    let head = std::fs::read_to_string(git.join("HEAD")).ok()?;
+    let head = head.trim();
+
+    match head.strip_prefix("ref: ") {
+        Some(reference) => Some(
+            reference
+                .strip_prefix("refs/heads/")
+                .unwrap_or(reference)
+                .to_owned(),
+        ),
+        None => head.get(..7).map(str::to_owned),
+    }
+}
+
+fn is_dirty(path: &Path) -> bool {
+    Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .args(["status", "--porcelain"])
+        .output()
+        .is_ok_and(|x| x.status.success() && !x.stdout.is_empty())
+}
+
+fn load(roots: &[PathBuf], depth: usize) -> Vec<Project> {
+    let mut repositories = Vec::new();
+    for root in roots {
+        find_repositories(root, depth, &mut repositories);
+    }
+
+    repositories.sort();
+    repositories.dedup();
+
+    repositories
+        .into_iter()
+        .map(|path| Project {
+            name: path
+                .file_name()
+                .map(|x| x.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            branch: branch(&path),
+            dirty: is_dirty(&path),
+            path,
+        })
+        .collect()
+}
+
+/// The git repositories found under the configured directories.
+#[derive(Debug)]
+pub struct Projects {
+    roots: Vec<PathBuf>,
+    depth: usize,
+    home_dir: String,
+    projects: Arc<Mutex<Vec<Project>>>,
+}
+
+impl Projects {
+    pub fn new(_: &Context, options: &toml::Table) -> Self {
+        let config: ProjectsConfig = parse_options(options);
+
+        Self {
+            roots: config
+                .roots
+                .iter()
+                .filter_map(|x| expanduser::expanduser(x).ok())
+                .filter(|x| x.is_dir())
+                .collect(),
+            depth: config.depth,
+            home_dir: std::env::var("HOME").unwrap_or_default(),
+            projects: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    fn project_to_entry(
+        &self,
+        project: &Project,
+        name: FormattedString,
+        score: u64,
+        context: &Context,
+    ) -> Entry {
+        let path = &project.path;
+        let display = path.to_string_lossy().into_owned();
+        let display = match display.strip_prefix(&self.home_dir) {
+            Some(x) if !self.home_dir.is_empty() => format!("~{x}"),
+            _ => display,
+        };
+
+        let mut actions = vec![EntryAction {
+            icon: "document-edit".into(),
+            name: "Open in editor".into(),
+            function: EntryAction::edit(path.clone(), None),
+            ..Default::default()
+        }];
+
+        if let Some(browser) = context.apps.file_browser() {
+            actions.push(EntryAction {
+                icon: browser.icon().into(),
+                name: "Open in file manager".into(),
+                function: EntryAction::open(browser.id.clone(), None, Some(path.clone())),
+                key: Key::Return,
+                modifier: ModifierType::SHIFT_MASK,
+            });
+        }
+
+        actions.push(EntryAction {
+            icon: "terminal".into(),
+            name: "Open in terminal".into(),
+            function: EntryAction::launch_terminal(None, vec![], Some(path.clone())),
+            key: Key::t,
+            modifier: ModifierType::CONTROL_MASK,
+        });
+
+        let tag = project.branch.as_deref().map(|branch| {
+            if project.dirty {
+                FormattedString::from_styles(vec![
+                    (branch, None),
+                    (" ●", Some(FormatStyle::Special)),
+                ])
+            } else {
+                FormattedString::plain(branch)
+            }
+        });
+
+        Entry {
+            name,
+            tag,
+            description: Some(FormattedString::plain(display)),
+            icon: EntryIcon::Name("folder-git".into()),
+            actions,
+            id: path.to_string_lossy().into_owned(),
+            drag_file: Some(path.clone()),
+            score,
+            ..Default::default()
+        }
+    }
+}
+
+impl Plugin for Projects {
+    fn open(&mut self) {
+        // Checking every repository for changes takes a moment
+        let roots = self.roots.clone();
+        let depth = self.depth;
+        let projects = self.projects.clone();
+        std::thread::spawn(move || {
+            let loaded = load(&roots, depth);
+            *projects.lock() = loaded;
+        });
+    }
+
+    fn name(&self) -> &str {
+        "Projects"
+    }
+
+    fn icon(&self) -> Option<&str> {
+        Some("folder-git")
+    }
+
+    fn search(&self, query: &str, context: &Context, _: &CancellationToken) -> Vec<Entry> {
+        let projects = self.projects.lock().clone();
+
+        if query.is_empty() {
+            return projects
+                .iter()
+                .map(|x| self.project_to_entry(x, FormattedString::plain(&x.name), 0, context))
+                .collect();
+        }
+
+        let matcher = SkimMatcherV2::default().smart_case();
+        projects
+            .iter()
+            .filter_map(|x| {
+                let (score, indices) = matcher.fuzzy_indices(&x.name, query)?;
+                let name = FormattedString::from_indices(&x.name, indices);
+                Some(self.project_to_entry(x, name, score.try_into().ok()?, context))
+            })
+            .collect()
+    }
+}
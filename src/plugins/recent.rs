@@ -0,0 +1,183 @@
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use gtk::gdk::{Key, ModifierType};
+use gtk::gio;
+use gtk::gio::prelude::FileExt;
+use regex::Regex;
+use tokio_util::sync::CancellationToken;
+use xdg::BaseDirectories;
+
+use crate::interface::{Context, Entry, EntryAction, EntryIcon, FormattedString, Plugin};
+use crate::utils::xml_unescape;
+
+static BOOKMARK: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<bookmark\s([^>]*)>").unwrap());
+static ATTRIBUTE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"([\w:-]+)="([^"]*)""#).unwrap());
+
+#[derive(Debug)]
+struct Recent {
+    path: PathBuf,
+    /// As written in the file, in ISO 8601 so they sort by date.
+    modified: String,
+}
+
+/// The local files of `recently-used.xbel` that still exist, most recent first.
+fn load() -> Vec<Recent> {
+    let Some(path) = BaseDirectories::new()
+        .ok()
+        .and_then(|x| x.find_data_file("recently-used.xbel"))
+    else {
+        return Vec::new();
+    };
+
+    let content = match std::fs::read_to_string(&path) {
+        Ok(x) => x,
+        Err(error) => {
+            tracing::warn!(?error, ?path, "Failed to read recent files");
+            return Vec::new();
+        }
+    };
+
+    let mut recent = BOOKMARK
+        .captures_iter(&content)
+        .filter_map(|x| {
+            let mut href = None;
+            let mut modified = String::new();
+            for attribute in ATTRIBUTE.captures_iter(&x[1]) {
+                match &attribute[1] {
+                    "href" => href = Some(xml_unescape(&attribute[2])),
+                    "modified" => modified = attribute[2].to_owned(),
+                    _ => {}
+                }
+            }
+
+            // Remote ones have no path
+            let path = gio::File::for_uri(&href?).path()?;
+            path.exists().then_some(Recent { path, modified })
+        })
+        .collect::<Vec<_>>();
+
+    recent.sort_by(|a, b| b.modified.cmp(&a.modified));
+    recent
+}
+
+/// Files recently opened in any application, as recorded by GTK and other toolkits.
+#[derive(Debug)]
+pub struct RecentFiles {
+    home_dir: String,
+    recent: Vec<Recent>,
+}
+
+impl RecentFiles {
+    pub fn new(_: &Context) -> Self {
+        Self {
+            home_dir: std::env::var("HOME").unwrap_or_default(),
+            recent: Vec::new(),
+        }
+    }
+
+    fn recent_to_entry(&self, recent: &Recent, name: FormattedString, context: &Context) -> Entry {
+        let path = &recent.path;
+        let mime = context.apps.guess(path).mime;
+        let parent = path
+            .parent()
+            .map(|x| x.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let parent = match parent.strip_prefix(&self.home_dir) {
+            Some(x) if !self.home_dir.is_empty() => format!("~{x}"),
+            _ => parent,
+        };
+
+        let mut actions = Vec::new();
+        if let Some(app) = context.apps.find_associations(mime).into_iter().next() {
+            actions.push(EntryAction {
+                icon: app.icon().into(),
+                name: format!("Open with {}", app.name),
+                function: EntryAction::open(app.id.clone(), None, Some(path.clone())),
+                ..Default::default()
+            });
+        }
+
+        if let Some(browser) = context.apps.file_browser() {
+            actions.push(EntryAction {
+                icon: browser.icon().into(),
+                name: "Open folder".into(),
+                function: EntryAction::open(
+                    browser.id.clone(),
+                    None,
+                    path.parent().map(|x| x.to_owned()),
+                ),
+                key: Key::Return,
+                modifier: ModifierType::SHIFT_MASK,
+            });
+        }
+
+        actions.push(EntryAction {
+            icon: "edit-copy".into(),
+            name: "Copy location".into(),
+            function: EntryAction::copy(path.to_string_lossy()),
+            key: Key::c,
+            modifier: ModifierType::CONTROL_MASK,
+        });
+
+        Entry {
+            name,
+            tag: recent.modified.get(..10).map(FormattedString::plain),
+            description: Some(FormattedString::plain(parent)),
+            icon: EntryIcon::Name(context.apps.mime_db.lookup_icon_name(mime)),
+            actions,
+            id: path.to_string_lossy().into_owned(),
+            drag_file: Some(path.clone()),
+            ..Default::default()
+        }
+    }
+}
+
+impl Plugin for RecentFiles {
+    fn open(&mut self) {
+        self.recent = load();
+    }
+
+    fn name(&self) -> &str {
+        "Recent files"
+    }
+
+    fn icon(&self) -> Option<&str> {
+        Some("document-open-recent")
+    }
+
+    fn search(&self, query: &str, context: &Context, token: &CancellationToken) -> Vec<Entry> {
+        let file_name = |x: &Recent| {
+            x.path
+                .file_name()
+                .map(|x| x.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        };
+
+        if query.is_empty() {
+            return self
+                .recent
+                .iter()
+                .map(|x| self.recent_to_entry(x, FormattedString::plain(file_name(x)), context))
+                .collect();
+        }
+
+        let matcher = SkimMatcherV2::default().smart_case();
+        self.recent
+            .iter()
+            .take_while(|_| !token.is_cancelled())
+            .filter_map(|x| {
+                let name = file_name(x);
+                let (score, indices) = matcher.fuzzy_indices(&name, query)?;
+                let name = FormattedString::from_indices(&name, indices);
+
+                Some(Entry {
+                    score: score.try_into().ok()?,
+                    ..self.recent_to_entry(x, name, context)
+                })
+            })
+            .collect()
+    }
+}
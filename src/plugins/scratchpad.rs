@@ -0,0 +1,213 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use gtk::gdk::{Key, ModifierType};
+use serde::Deserialize;
+use tokio_util::sync::CancellationToken;
+use xdg::BaseDirectories;
+
+use crate::interface::{
+    ActionResult, ActionType, Context, Entry, EntryAction, EntryIcon, FormatStyle, FormattedString,
+    Plugin, parse_options,
+};
+
+/// Between the time and the text of each line.
+const SEPARATOR: &str = "  ";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ScratchpadConfig {
+    /// `scratchpad.txt` in the data directory when not set.
+    file: Option<String>,
+}
+
+fn append(path: PathBuf, text: String) -> Box<ActionType> {
+    Box::new(move |_| {
+        let line = format!(
+            "{}{SEPARATOR}{text}\n",
+            chrono::Local::now().format("%Y-%m-%d %H:%M")
+        );
+
+        match std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .and_then(|mut x| x.write_all(line.as_bytes()))
+        {
+            Ok(()) => ActionResult::Ok,
+            Err(error) => {
+                tracing::warn!(?error, ?path, "Failed to write the note");
+                ActionResult::Error
+            }
+        }
+    })
+}
+
+/// Removes the first line equal to this one, the file may have changed since it was read.
+fn delete(path: PathBuf, line: String) -> Box<ActionType> {
+    Box::new(move |_| {
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            return ActionResult::Error;
+        };
+
+        let mut removed = false;
+        let content = content
+            .lines()
+            .filter(|x| {
+                if !removed && *x == line {
+                    removed = true;
+                    return false;
+                }
+                true
+            })
+            .map(|x| format!("{x}\n"))
+            .collect::<String>();
+
+        match std::fs::write(&path, content) {
+            Ok(()) => ActionResult::Refresh,
+            Err(error) => {
+                tracing::warn!(?error, ?path, "Failed to delete the note");
+                ActionResult::Error
+            }
+        }
+    })
+}
+
+/// Quick notes typed after `note`, each a timestamped line of a plain text file.
+#[derive(Debug)]
+pub struct Scratchpad {
+    path: Option<PathBuf>,
+}
+
+impl Scratchpad {
+    pub fn new(_: &Context, options: &toml::Table) -> Self {
+        let config: ScratchpadConfig = parse_options(options);
+
+        let path = match config.file {
+            Some(file) => expanduser::expanduser(file).ok(),
+            None => BaseDirectories::with_prefix("jogger")
+                .ok()
+                .and_then(|x| x.place_data_file("scratchpad.txt").ok()),
+        };
+
+        Self { path }
+    }
+
+    fn note_to_entry(
+        &self,
+        path: &Path,
+        number: usize,
+        line: &str,
+        text: FormattedString,
+        score: u64,
+    ) -> Entry {
+        let time = line.split_once(SEPARATOR).map_or("", |(time, _)| time);
+
+        Entry {
+            name: text,
+            tag: Some(FormattedString::plain(time)),
+            icon: EntryIcon::Name("accessories-text-editor".into()),
+            actions: vec![
+                EntryAction {
+                    icon: "edit-copy".into(),
+                    name: "Copy".into(),
+                    function: EntryAction::copy(line.split_once(SEPARATOR).map_or(line, |x| x.1)),
+                    ..Default::default()
+                },
+                EntryAction {
+                    icon: "document-edit".into(),
+                    name: "Edit".into(),
+                    function: EntryAction::edit(path.to_owned(), Some(number + 1)),
+                    key: Key::e,
+                    modifier: ModifierType::CONTROL_MASK,
+                },
+                EntryAction {
+                    icon: "edit-delete".into(),
+                    name: "Delete".into(),
+                    function: delete(path.to_owned(), line.to_owned()),
+                    key: Key::Delete,
+                    modifier: ModifierType::CONTROL_MASK,
+                },
+            ],
+            score,
+            ..Default::default()
+        }
+    }
+}
+
+impl Plugin for Scratchpad {
+    fn name(&self) -> &str {
+        "Scratchpad"
+    }
+
+    fn icon(&self) -> Option<&str> {
+        Some("accessories-text-editor")
+    }
+
+    fn search(&self, query: &str, _: &Context, _: &CancellationToken) -> Vec<Entry> {
+        let Some(path) = &self.path else {
+            return Vec::new();
+        };
+
+        let query = query
+            .strip_prefix("note")
+            .filter(|x| x.is_empty() || x.starts_with(char::is_whitespace))
+            .unwrap_or(query)
+            .trim();
+
+        let content = std::fs::read_to_string(path).unwrap_or_default();
+        let matcher = SkimMatcherV2::default().smart_case();
+
+        // The latest first, numbered for the editor
+        let lines = content.lines().collect::<Vec<_>>();
+        let mut entries = lines
+            .into_iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, x)| !x.trim().is_empty())
+            .filter_map(|(number, line)| {
+                let text = line.split_once(SEPARATOR).map_or(line, |x| x.1);
+                if query.is_empty() {
+                    return Some(self.note_to_entry(
+                        path,
+                        number,
+                        line,
+                        FormattedString::plain(text),
+                        0,
+                    ));
+                }
+
+                let (score, indices) = matcher.fuzzy_indices(text, query)?;
+                let name = FormattedString::from_indices(text, indices);
+                Some(self.note_to_entry(path, number, line, name, score.try_into().ok()?))
+            })
+            .collect::<Vec<_>>();
+
+        if !query.is_empty() {
+            entries.insert(
+                0,
+                Entry {
+                    name: FormattedString::plain(query),
+                    tag: Some(FormattedString::from_style(
+                        "New note",
+                        FormatStyle::Special,
+                    )),
+                    icon: EntryIcon::Name("list-add".into()),
+                    actions: vec![EntryAction {
+                        icon: "list-add".into(),
+                        name: "Add note".into(),
+                        function: append(path.clone(), query.to_owned()),
+                        ..Default::default()
+                    }],
+                    // Kept above the notes it matches
+                    score: u64::MAX,
+                    ..Default::default()
+                },
+            );
+        }
+
+        entries
+    }
+}
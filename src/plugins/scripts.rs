@@ -0,0 +1,248 @@
+//! User scripts written in [Rhai](https://rhai.rs), loaded from `$XDG_CONFIG_HOME/jogger/scripts`.
+//!
+//! A script can set `name`, `icon` and `prefix` at its top level, and defines
+//! `search(query)` returning an array of maps like:
+//!
+//! ```rhai
+//! let prefix = "gh";
+//!
+//! fn search(query) {
+//!     [#{ name: `Open ${query}`, open: `https://github.com/${query}` }]
+//! }
+//! ```
+//!
+//! Each map has a `name` and optionally a `description`, `icon`, `score`, and
+//! what to do with it: `copy` some text, `open` a URL or file, or run a `shell` command.
+
+use std::path::{Path, PathBuf};
+
+use derivative::Derivative;
+use gtk::gdk::{Key, ModifierType};
+use rhai::{AST, CallFnOptions, Engine, Map, Scope};
+use serde::Deserialize;
+use tokio_util::sync::CancellationToken;
+use xdg::BaseDirectories;
+
+use crate::interface::{
+    Context, Entry, EntryAction, EntryIcon, FormattedString, Plugin, parse_options,
+};
+
+/// Keeps a script stuck in a loop from freezing its search.
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ScriptsConfig {
+    /// Defaults to `scripts` in the config directory.
+    directory: Option<String>,
+}
+
+#[derive(Debug)]
+struct Script {
+    name: String,
+    icon: Option<String>,
+    /// Scripts with a prefix only run when the query starts with it.
+    prefix: Option<String>,
+    ast: AST,
+    /// The variables set by the script when it was loaded.
+    scope: Scope<'static>,
+}
+
+impl Script {
+    fn load(engine: &Engine, path: &Path) -> Option<Self> {
+        let ast = match engine.compile_file(path.to_path_buf()) {
+            Ok(x) => x,
+            Err(error) => {
+                tracing::warn!(path = %path.display(), %error, "Failed to compile script");
+                return None;
+            }
+        };
+
+        let mut scope = Scope::new();
+        if let Err(error) = engine.run_ast_with_scope(&mut scope, &ast) {
+            tracing::warn!(path = %path.display(), %error, "Failed to run script");
+            return None;
+        }
+
+        let fallback = path.file_stem()?.to_string_lossy().into_owned();
+
+        Some(Self {
+            name: scope.get_value::<String>("name").unwrap_or(fallback),
+            icon: scope.get_value("icon"),
+            prefix: scope
+                .get_value::<String>("prefix")
+                .filter(|x| !x.is_empty()),
+            ast,
+            scope,
+        })
+    }
+
+    /// The query for the script, without its prefix.
+    fn query<'a>(&self, query: &'a str) -> Option<&'a str> {
+        let Some(prefix) = &self.prefix else {
+            return Some(query);
+        };
+
+        let rest = query.strip_prefix(prefix.as_str())?;
+        if prefix.ends_with(char::is_alphanumeric) && !rest.is_empty() {
+            rest.strip_prefix(char::is_whitespace)
+        } else {
+            Some(rest)
+        }
+    }
+
+    fn search(&self, engine: &Engine, query: &str) -> Vec<Map> {
+        let mut scope = self.scope.clone();
+        let options = CallFnOptions::new().eval_ast(false);
+
+        match engine.call_fn_with_options::<rhai::Array>(
+            options,
+            &mut scope,
+            &self.ast,
+            "search",
+            (query.to_owned(),),
+        ) {
+            Ok(x) => x.into_iter().filter_map(|x| x.try_cast()).collect(),
+            Err(error) => {
+                tracing::warn!(script = %self.name, %error, "Script failed");
+                Vec::new()
+            }
+        }
+    }
+}
+
+fn string(map: &Map, key: &str) -> Option<String> {
+    map.get(key).cloned().and_then(|x| x.into_string().ok())
+}
+
+fn result_to_entry(script: &Script, result: &Map) -> Option<Entry> {
+    let name = string(result, "name")?;
+
+    let mut actions = Vec::new();
+    if let Some(url) = string(result, "open") {
+        actions.push(EntryAction {
+            icon: "document-open".into(),
+            name: "Open".into(),
+            function: EntryAction::command("xdg-open".into(), vec![url], None),
+            ..Default::default()
+        });
+    }
+    if let Some(command) = string(result, "shell") {
+        actions.push(EntryAction {
+            icon: "utilities-terminal".into(),
+            name: "Run".into(),
+            function: EntryAction::command("sh".into(), vec!["-c".into(), command], None),
+            ..Default::default()
+        });
+    }
+    if let Some(text) = string(result, "copy") {
+        let first = actions.is_empty();
+        actions.push(EntryAction {
+            icon: "edit-copy".into(),
+            name: "Copy".into(),
+            function: EntryAction::copy(text),
+            key: if first { Key::Return } else { Key::c },
+            modifier: if first {
+                ModifierType::empty()
+            } else {
+                ModifierType::CONTROL_MASK
+            },
+        });
+    }
+
+    Some(Entry {
+        name: FormattedString::plain(name),
+        tag: Some(FormattedString::plain(&script.name)),
+        description: string(result, "description").map(FormattedString::plain),
+        icon: EntryIcon::Name(
+            string(result, "icon")
+                .or_else(|| script.icon.clone())
+                .unwrap_or_else(|| "text-x-script".into()),
+        ),
+        actions,
+        score: result
+            .get("score")
+            .and_then(|x| x.as_int().ok())
+            .and_then(|x| x.try_into().ok())
+            .unwrap_or_default(),
+        ..Default::default()
+    })
+}
+
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct Scripts {
+    #[derivative(Debug = "ignore")]
+    engine: Engine,
+    scripts: Vec<Script>,
+}
+
+impl Scripts {
+    pub fn new(_: &Context, options: &toml::Table) -> Self {
+        let config: ScriptsConfig = parse_options(options);
+
+        let directory = match config.directory {
+            Some(x) => expanduser::expanduser(x).ok(),
+            None => BaseDirectories::with_prefix("jogger")
+                .ok()
+                .map(|x| x.get_config_home().join("scripts")),
+        };
+
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+
+        let mut paths = directory
+            .and_then(|x| std::fs::read_dir(x).ok())
+            .into_iter()
+            .flatten()
+            .filter_map(|x| Some(x.ok()?.path()))
+            .filter(|x| x.extension().is_some_and(|x| x == "rhai"))
+            .collect::<Vec<PathBuf>>();
+        paths.sort();
+
+        let scripts = paths
+            .iter()
+            .filter_map(|x| Script::load(&engine, x))
+            .collect();
+
+        Self { engine, scripts }
+    }
+}
+
+impl Plugin for Scripts {
+    fn name(&self) -> &str {
+        "Scripts"
+    }
+
+    fn icon(&self) -> Option<&str> {
+        Some("text-x-script")
+    }
+
+    fn search(&self, query: &str, _: &Context, token: &CancellationToken) -> Vec<Entry> {
+        let mut entries = Vec::new();
+
+        for script in &self.scripts {
+            if token.is_cancelled() {
+                return Vec::new();
+            }
+
+            let Some(query) = script.query(query) else {
+                continue;
+            };
+
+            // Scripts without a prefix would otherwise fill the home grid
+            if script.prefix.is_none() && query.is_empty() {
+                continue;
+            }
+
+            entries.extend(
+                script
+                    .search(&self.engine, query)
+                    .iter()
+                    .filter_map(|x| result_to_entry(script, x)),
+            );
+        }
+
+        entries
+    }
+}
@@ -0,0 +1,245 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use dbus::Path;
+use dbus::arg::{RefArg, Variant};
+use dbus::blocking::Connection;
+use dbus::blocking::stdintf::org_freedesktop_dbus::Properties;
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use parking_lot::Mutex;
+use tokio_util::sync::CancellationToken;
+
+use crate::interface::{
+    ActionResult, ActionType, Context, Entry, EntryAction, EntryIcon, FormattedString, Plugin,
+};
+
+const SERVICE: &str = "org.freedesktop.secrets";
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The secret itself, with the session it's encoded for and its content type.
+type Secret = (Path<'static>, Vec<u8>, Vec<u8>, String);
+
+#[derive(Debug, Clone)]
+struct Item {
+    path: Path<'static>,
+    label: String,
+    /// Like the user name or the service the secret is for.
+    details: Option<String>,
+    collection: String,
+}
+
+#[derive(Debug, Clone)]
+struct Collection {
+    path: Path<'static>,
+    label: String,
+}
+
+#[derive(Debug, Default)]
+struct Keyring {
+    items: Vec<Item>,
+    /// Their items can't be listed until they are unlocked.
+    locked: Vec<Collection>,
+}
+
+fn load() -> Result<Keyring, dbus::Error> {
+    let conn = Connection::new_session()?;
+    let service = conn.with_proxy(SERVICE, "/org/freedesktop/secrets", TIMEOUT);
+    let collections: Vec<Path<'static>> =
+        service.get("org.freedesktop.Secret.Service", "Collections")?;
+
+    let mut keyring = Keyring::default();
+    for path in collections {
+        let collection = conn.with_proxy(SERVICE, &path, TIMEOUT);
+        let label: String = collection
+            .get("org.freedesktop.Secret.Collection", "Label")
+            .unwrap_or_default();
+        let locked: bool = collection.get("org.freedesktop.Secret.Collection", "Locked")?;
+
+        if locked {
+            keyring.locked.push(Collection { path, label });
+            continue;
+        }
+
+        let items: Vec<Path<'static>> =
+            collection.get("org.freedesktop.Secret.Collection", "Items")?;
+        for path in items {
+            let item = conn.with_proxy(SERVICE, &path, TIMEOUT);
+            let Ok(name) = item.get::<String>("org.freedesktop.Secret.Item", "Label") else {
+                continue;
+            };
+            let attributes: HashMap<String, String> = item
+                .get("org.freedesktop.Secret.Item", "Attributes")
+                .unwrap_or_default();
+
+            // KeePassXC, then the attributes libsecret applications tend to use
+            let details = ["UserName", "username", "user", "service", "URL"]
+                .iter()
+                .find_map(|x| attributes.get(*x))
+                .filter(|x| !x.is_empty())
+                .cloned();
+
+            keyring.items.push(Item {
+                path,
+                label: name,
+                details,
+                collection: label.clone(),
+            });
+        }
+    }
+
+    Ok(keyring)
+}
+
+fn get_secret(path: &Path<'static>) -> Result<Vec<u8>, dbus::Error> {
+    let conn = Connection::new_session()?;
+    let service = conn.with_proxy(SERVICE, "/org/freedesktop/secrets", TIMEOUT);
+
+    // Unencrypted, the secret only travels over the session bus
+    let (_, session): (Variant<Box<dyn RefArg>>, Path<'static>) = service.method_call(
+        "org.freedesktop.Secret.Service",
+        "OpenSession",
+        ("plain", Variant("")),
+    )?;
+
+    let item = conn.with_proxy(SERVICE, path, TIMEOUT);
+    let result = item
+        .method_call::<(Secret,), _, _, _>(
+            "org.freedesktop.Secret.Item",
+            "GetSecret",
+            (session.clone(),),
+        )
+        .map(|(secret,)| secret.2);
+
+    let _ = conn
+        .with_proxy(SERVICE, &session, TIMEOUT)
+        .method_call::<(), _, _, _>("org.freedesktop.Secret.Session", "Close", ());
+
+    result
+}
+
+fn copy_secret(path: Path<'static>) -> Box<ActionType> {
+    Box::new(move |context| match get_secret(&path) {
+        Ok(secret) => EntryAction::copy_secret(String::from_utf8_lossy(&secret))(context),
+        Err(error) => {
+            tracing::warn!(%error, "Failed to get the secret");
+            ActionResult::Error
+        }
+    })
+}
+
+/// Asks the keyring to unlock the collection, which shows its own password prompt.
+fn unlock(path: Path<'static>) -> Box<ActionType> {
+    Box::new(move |_| {
+        let result = Connection::new_session().and_then(|conn| {
+            let service = conn.with_proxy(SERVICE, "/org/freedesktop/secrets", TIMEOUT);
+            let (_, prompt): (Vec<Path<'static>>, Path<'static>) = service.method_call(
+                "org.freedesktop.Secret.Service",
+                "Unlock",
+                (vec![path.clone()],),
+            )?;
+
+            // "/" when nothing needs to be asked
+            if &*prompt != "/" {
+                conn.with_proxy(SERVICE, &prompt, TIMEOUT)
+                    .method_call::<(), _, _, _>("org.freedesktop.Secret.Prompt", "Prompt", ("",))?;
+            }
+
+            Ok(())
+        });
+
+        match result {
+            Ok(()) => ActionResult::Ok,
+            Err(error) => {
+                tracing::warn!(%error, "Failed to unlock the collection");
+                ActionResult::Error
+            }
+        }
+    })
+}
+
+/// Searches the secrets of KeePassXC, GNOME Keyring and other Secret Service providers.
+#[derive(Debug)]
+pub struct Secrets {
+    keyring: Arc<Mutex<Keyring>>,
+}
+
+impl Secrets {
+    pub fn new(_: &Context) -> Self {
+        Self {
+            keyring: Arc::new(Mutex::new(Keyring::default())),
+        }
+    }
+}
+
+impl Plugin for Secrets {
+    fn open(&mut self) {
+        // Collections may have been locked or unlocked since last time
+        let keyring = self.keyring.clone();
+        std::thread::spawn(move || match load() {
+            Ok(loaded) => *keyring.lock() = loaded,
+            Err(error) => tracing::debug!(%error, "Failed to list the secrets"),
+        });
+    }
+
+    fn name(&self) -> &str {
+        "Secrets"
+    }
+
+    fn icon(&self) -> Option<&str> {
+        Some("dialog-password")
+    }
+
+    fn search(&self, query: &str, _: &Context, _: &CancellationToken) -> Vec<Entry> {
+        let query = query
+            .strip_prefix("secret")
+            .filter(|x| x.is_empty() || x.starts_with(char::is_whitespace))
+            .unwrap_or(query)
+            .trim();
+
+        let keyring = self.keyring.lock();
+        let matcher = SkimMatcherV2::default().ignore_case();
+
+        let locked = keyring.locked.iter().map(|x| Entry {
+            name: FormattedString::plain(format!("Unlock {}", x.label)),
+            description: Some(FormattedString::plain("Locked collection")),
+            icon: EntryIcon::Name("changes-prevent".into()),
+            actions: vec![EntryAction {
+                icon: "changes-allow".into(),
+                name: "Unlock".into(),
+                function: unlock(x.path.clone()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        });
+
+        if query.is_empty() {
+            return locked.collect();
+        }
+
+        keyring
+            .items
+            .iter()
+            .filter_map(|x| {
+                let (score, indices) = matcher.fuzzy_indices(&x.label, query)?;
+                Some(Entry {
+                    name: FormattedString::from_indices(&x.label, indices),
+                    tag: Some(FormattedString::plain(&x.collection)),
+                    description: x.details.as_ref().map(FormattedString::plain),
+                    icon: EntryIcon::Name("dialog-password".into()),
+                    actions: vec![EntryAction {
+                        icon: "edit-copy".into(),
+                        name: "Copy secret".into(),
+                        function: copy_secret(x.path.clone()),
+                        ..Default::default()
+                    }],
+                    id: format!("secrets:{}", x.path),
+                    score: score.try_into().ok()?,
+                    ..Default::default()
+                })
+            })
+            .chain(locked)
+            .collect()
+    }
+}
@@ -0,0 +1,155 @@
+use std::path::PathBuf;
+
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use serde::Deserialize;
+use tokio_util::sync::CancellationToken;
+
+use crate::interface::{
+    Condition, Context, Entry, EntryAction, EntryIcon, FormattedString, Plugin, parse_options,
+};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ShortcutsConfig {
+    entries: Vec<Shortcut>,
+}
+
+/// An entry written in the config, like
+/// `{ name = "Router", icon = "network-wired", url = "http://192.168.1.1" }`.
+#[derive(Debug, Deserialize)]
+struct Shortcut {
+    name: String,
+    description: Option<String>,
+    /// An icon name or the path to an image.
+    icon: Option<String>,
+    /// Other words it is found by.
+    #[serde(default)]
+    keywords: Vec<String>,
+    /// Run by `sh`, so it can use pipes and variables.
+    command: Option<String>,
+    url: Option<String>,
+    /// Left out where it doesn't hold.
+    #[serde(default)]
+    when: Condition,
+}
+
+#[derive(Debug)]
+pub struct Shortcuts {
+    entries: Vec<Shortcut>,
+    handler: Option<String>,
+}
+
+impl Shortcuts {
+    pub fn new(context: &Context, options: &toml::Table) -> Self {
+        let mut config: ShortcutsConfig = parse_options(options);
+        config.entries.retain(|x| x.when.holds());
+
+        for entry in &config.entries {
+            if entry.command.is_none() && entry.url.is_none() {
+                tracing::warn!(name = %entry.name, "Shortcut without a command or URL");
+            }
+        }
+
+        Self {
+            entries: config.entries,
+            handler: context
+                .apps
+                .default_for_mime(&"x-scheme-handler/https".parse().unwrap())
+                .map(|x| x.id.clone()),
+        }
+    }
+
+    fn actions(&self, shortcut: &Shortcut) -> Vec<EntryAction> {
+        let mut actions = Vec::new();
+
+        if let Some(command) = &shortcut.command {
+            actions.push(EntryAction {
+                icon: "utilities-terminal".into(),
+                name: "Run".into(),
+                function: EntryAction::command(
+                    "sh".into(),
+                    vec!["-c".into(), command.clone()],
+                    None,
+                ),
+                ..Default::default()
+            });
+        }
+
+        if let Some(url) = &shortcut.url {
+            let web = url.starts_with("http://") || url.starts_with("https://");
+            actions.push(EntryAction {
+                icon: "web-browser".into(),
+                name: "Open".into(),
+                function: match &self.handler {
+                    Some(handler) if web => {
+                        EntryAction::open(handler.clone(), None, Some(PathBuf::from(url)))
+                    }
+                    _ => EntryAction::command("xdg-open".into(), vec![url.clone()], None),
+                },
+                ..Default::default()
+            });
+        }
+
+        actions
+    }
+}
+
+impl Plugin for Shortcuts {
+    fn name(&self) -> &str {
+        "Shortcuts"
+    }
+
+    fn icon(&self) -> Option<&str> {
+        Some("emblem-symbolic-link")
+    }
+
+    fn search(&self, query: &str, _: &Context, _: &CancellationToken) -> Vec<Entry> {
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let matcher = SkimMatcherV2::default();
+
+        self.entries
+            .iter()
+            .filter_map(|shortcut| {
+                let matched = matcher.fuzzy_indices(&shortcut.name, query);
+                let keyword = shortcut
+                    .keywords
+                    .iter()
+                    .filter_map(|x| matcher.fuzzy_match(x, query))
+                    .max();
+
+                // Only the name is highlighted, but a keyword can rank it higher
+                let (name, score) = match (matched, keyword) {
+                    (Some((score, indices)), keyword) => (
+                        FormattedString::from_indices(&shortcut.name, indices),
+                        score.max(keyword.unwrap_or_default()),
+                    ),
+                    (None, Some(score)) => (FormattedString::plain(&shortcut.name), score),
+                    (None, None) => return None,
+                };
+
+                Some(Entry {
+                    name,
+                    description: shortcut
+                        .description
+                        .clone()
+                        .or_else(|| shortcut.url.clone())
+                        .or_else(|| shortcut.command.clone())
+                        .map(FormattedString::plain),
+                    icon: match &shortcut.icon {
+                        Some(x) if x.starts_with('/') => EntryIcon::Path(x.into()),
+                        Some(x) => EntryIcon::Name(x.clone()),
+                        None => EntryIcon::Name("emblem-symbolic-link".into()),
+                    },
+                    actions: self.actions(shortcut),
+                    id: format!("shortcuts:{}", shortcut.name),
+                    score: score.try_into().ok()?,
+                    ..Default::default()
+                })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,180 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use gtk::gdk::{Key, ModifierType};
+use serde::Deserialize;
+use tokio_util::sync::CancellationToken;
+use xdg::BaseDirectories;
+
+use crate::interface::{
+    ActionResult, ActionType, Context, Entry, EntryAction, EntryIcon, FormattedString, Plugin,
+    parse_options,
+};
+use crate::utils::read_clipboard;
+
+/// Long enough for the window to be hidden and the focus back on the previous one.
+const TYPE_DELAY: Duration = Duration::from_millis(300);
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct SnippetsConfig {
+    /// A TOML or YAML file of keywords and their text, `snippets.toml` or
+    /// `snippets.yaml` in the config directory when not set.
+    file: Option<String>,
+}
+
+fn find_file(config: SnippetsConfig) -> Option<PathBuf> {
+    if let Some(file) = config.file {
+        return expanduser::expanduser(file).ok();
+    }
+
+    let base_dirs = BaseDirectories::with_prefix("jogger").ok()?;
+    base_dirs
+        .find_config_file("snippets.toml")
+        .or_else(|| base_dirs.find_config_file("snippets.yaml"))
+}
+
+fn load(path: &Path) -> BTreeMap<String, String> {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return BTreeMap::new();
+    };
+
+    let snippets = if path.extension().is_some_and(|x| x == "toml") {
+        toml::from_str(&content).map_err(|x| x.to_string())
+    } else {
+        serde_yaml::from_str(&content).map_err(|x| x.to_string())
+    };
+
+    snippets.unwrap_or_else(|error| {
+        tracing::warn!(%error, ?path, "Invalid snippets file");
+        BTreeMap::new()
+    })
+}
+
+/// Replaces the placeholders, when the snippet is used rather than when it's shown.
+fn expand(text: &str) -> String {
+    let now = chrono::Local::now();
+
+    let mut result = text
+        .replace("{date}", &now.format("%Y-%m-%d").to_string())
+        .replace("{time}", &now.format("%H:%M").to_string())
+        .replace("{datetime}", &now.format("%Y-%m-%d %H:%M").to_string());
+
+    // Not read unless needed, it can take a while
+    if result.contains("{clipboard}") {
+        result = result.replace("{clipboard}", &read_clipboard().unwrap_or_default());
+    }
+
+    result
+}
+
+fn copy(text: String) -> Box<ActionType> {
+    Box::new(move |context| EntryAction::copy(expand(&text))(context))
+}
+
+/// Types the text in the focused window with `wtype`, once the launcher is hidden.
+fn type_text(text: String) -> Box<ActionType> {
+    Box::new(move |_| {
+        let text = expand(&text);
+        std::thread::spawn(move || {
+            std::thread::sleep(TYPE_DELAY);
+            if let Err(error) = Command::new("wtype").args(["--", &text]).status() {
+                tracing::warn!(?error, "Failed to run wtype");
+            }
+        });
+
+        ActionResult::Ok
+    })
+}
+
+/// Text blocks from a file of the user, by keyword, with placeholders like `{date}` and `{clipboard}`.
+#[derive(Debug)]
+pub struct Snippets {
+    path: Option<PathBuf>,
+    snippets: BTreeMap<String, String>,
+}
+
+impl Snippets {
+    pub fn new(_: &Context, options: &toml::Table) -> Self {
+        let config: SnippetsConfig = parse_options(options);
+
+        Self {
+            path: find_file(config),
+            snippets: BTreeMap::new(),
+        }
+    }
+
+    fn snippet_to_entry(&self, text: &str, name: FormattedString, score: u64) -> Entry {
+        let preview = text.lines().next().unwrap_or_default();
+
+        Entry {
+            name,
+            description: Some(FormattedString::plain(if text.contains('\n') {
+                format!("{preview} …")
+            } else {
+                preview.to_owned()
+            })),
+            icon: EntryIcon::Name("accessories-text-editor".into()),
+            actions: vec![
+                EntryAction {
+                    icon: "edit-copy".into(),
+                    name: "Copy".into(),
+                    function: copy(text.to_owned()),
+                    ..Default::default()
+                },
+                EntryAction {
+                    icon: "input-keyboard".into(),
+                    name: "Type".into(),
+                    function: type_text(text.to_owned()),
+                    key: Key::Return,
+                    modifier: ModifierType::SHIFT_MASK,
+                },
+            ],
+            score,
+            ..Default::default()
+        }
+    }
+}
+
+impl Plugin for Snippets {
+    fn open(&mut self) {
+        // Read every time, to pick up edits
+        if let Some(path) = &self.path {
+            self.snippets = load(path);
+        }
+    }
+
+    fn name(&self) -> &str {
+        "Snippets"
+    }
+
+    fn icon(&self) -> Option<&str> {
+        Some("accessories-text-editor")
+    }
+
+    fn search(&self, query: &str, _: &Context, _: &CancellationToken) -> Vec<Entry> {
+        let query = query
+            .strip_prefix("snip")
+            .filter(|x| x.is_empty() || x.starts_with(char::is_whitespace))
+            .unwrap_or(query)
+            .trim();
+
+        let matcher = SkimMatcherV2::default().smart_case();
+        self.snippets
+            .iter()
+            .filter_map(|(keyword, text)| {
+                if query.is_empty() {
+                    return Some(self.snippet_to_entry(text, FormattedString::plain(keyword), 0));
+                }
+
+                let (score, indices) = matcher.fuzzy_indices(keyword, query)?;
+                let name = FormattedString::from_indices(keyword, indices);
+                Some(self.snippet_to_entry(text, name, score.try_into().ok()?))
+            })
+            .collect()
+    }
+}
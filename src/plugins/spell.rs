@@ -0,0 +1,257 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+
+use gtk::gdk::{Key, ModifierType};
+use serde::Deserialize;
+use tokio_util::sync::CancellationToken;
+
+use crate::interface::{
+    Context, Entry, EntryAction, EntryIcon, FormatStyle, FormattedString, Plugin, parse_options,
+};
+
+const MAX_SUGGESTIONS: usize = 5;
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+struct SpellConfig {
+    /// Hunspell dictionary, like `en_US` or `de_DE`.
+    language: String,
+    /// MyThes thesaurus, defaults to the one of the language in `/usr/share/mythes`.
+    thesaurus: Option<String>,
+}
+
+impl Default for SpellConfig {
+    fn default() -> Self {
+        Self {
+            language: "en_US".into(),
+            thesaurus: None,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Misspelling {
+    word: String,
+    suggestions: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct Spell {
+    language: String,
+    thesaurus: PathBuf,
+    /// Offsets of the words in the thesaurus, read on the first lookup.
+    index: OnceLock<HashMap<String, u64>>,
+}
+
+impl Spell {
+    pub fn new(_: &Context, options: &toml::Table) -> Self {
+        let config: SpellConfig = parse_options(options);
+
+        #[allow(deprecated)]
+        let thesaurus = config
+            .thesaurus
+            .and_then(|x| expanduser::expanduser(x).ok())
+            .unwrap_or_else(|| {
+                PathBuf::from(format!("/usr/share/mythes/th_{}_v2.dat", config.language))
+            });
+
+        Self {
+            language: config.language,
+            thesaurus,
+            index: OnceLock::new(),
+        }
+    }
+
+    /// Runs hunspell in pipe mode, which prints a line for every word of the input.
+    fn check(&self, text: &str) -> std::io::Result<Vec<Misspelling>> {
+        let mut child = Command::new("hunspell")
+            .args(["-a", "-d", &self.language])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            // A leading `^` keeps the line from being read as a command
+            writeln!(stdin, "^{text}")?;
+        }
+
+        let output = child.wait_with_output()?;
+
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| {
+                if let Some(rest) = line.strip_prefix("& ") {
+                    let (info, suggestions) = rest.split_once(": ")?;
+                    Some(Misspelling {
+                        word: info.split_whitespace().next()?.to_owned(),
+                        suggestions: suggestions
+                            .split(", ")
+                            .take(MAX_SUGGESTIONS)
+                            .map(str::to_owned)
+                            .collect(),
+                    })
+                } else {
+                    let rest = line.strip_prefix("# ")?;
+                    Some(Misspelling {
+                        word: rest.split_whitespace().next()?.to_owned(),
+                        suggestions: Vec::new(),
+                    })
+                }
+            })
+            .collect())
+    }
+
+    fn load_index(&self) -> HashMap<String, u64> {
+        let index = self.thesaurus.with_extension("idx");
+
+        let Ok(file) = File::open(&index) else {
+            tracing::warn!(path = %index.display(), "Thesaurus index not found");
+            return HashMap::new();
+        };
+
+        // The first two lines are the encoding and the number of words
+        BufReader::new(file)
+            .lines()
+            .skip(2)
+            .map_while(Result::ok)
+            .filter_map(|line| {
+                let (word, offset) = line.rsplit_once('|')?;
+                Some((word.to_lowercase(), offset.parse().ok()?))
+            })
+            .collect()
+    }
+
+    /// Looks up the meanings of the word, each with a part of speech and its synonyms.
+    fn synonyms(&self, word: &str) -> Option<Vec<(String, Vec<String>)>> {
+        let offset = *self
+            .index
+            .get_or_init(|| self.load_index())
+            .get(&word.to_lowercase())?;
+
+        let mut file = File::open(&self.thesaurus).ok()?;
+        file.seek(SeekFrom::Start(offset)).ok()?;
+        let mut lines = BufReader::new(file).lines().map_while(Result::ok);
+
+        let (_, count) = lines.next()?.rsplit_once('|')?;
+        let count: usize = count.parse().ok()?;
+
+        Some(
+            lines
+                .take(count)
+                .filter_map(|line| {
+                    let mut parts = line.split('|');
+                    let part = parts.next()?.trim_matches(['(', ')']).to_owned();
+                    Some((part, parts.map(str::to_owned).collect()))
+                })
+                .collect(),
+        )
+    }
+
+    fn search_synonyms(&self, word: &str) -> Vec<Entry> {
+        let Some(meanings) = self.synonyms(word) else {
+            return Vec::new();
+        };
+
+        meanings
+            .into_iter()
+            .flat_map(|(part, synonyms)| {
+                synonyms.into_iter().map(move |synonym| Entry {
+                    name: FormattedString::plain(&synonym),
+                    tag: Some(FormattedString::plain(&part)),
+                    icon: EntryIcon::Name("accessories-dictionary".into()),
+                    actions: vec![EntryAction {
+                        icon: "edit-copy".into(),
+                        name: "Copy".into(),
+                        function: EntryAction::copy(synonym.clone()),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                })
+            })
+            .collect()
+    }
+
+    fn search_spelling(&self, text: &str) -> Vec<Entry> {
+        let misspellings = match self.check(text) {
+            Ok(x) => x,
+            Err(error) => {
+                tracing::error!(?error, "Failed to run hunspell");
+                return Vec::new();
+            }
+        };
+
+        let corrected =
+            misspellings
+                .iter()
+                .fold(text.to_owned(), |text, x| match x.suggestions.first() {
+                    Some(suggestion) => text.replacen(&x.word, suggestion, 1),
+                    None => text,
+                });
+
+        misspellings
+            .into_iter()
+            .map(|x| {
+                let mut actions = x
+                    .suggestions
+                    .iter()
+                    .map(|suggestion| EntryAction {
+                        icon: "edit-copy".into(),
+                        name: format!("Copy \"{suggestion}\""),
+                        function: EntryAction::copy(suggestion.clone()),
+                        ..Default::default()
+                    })
+                    .collect::<Vec<_>>();
+
+                if corrected != text {
+                    actions.push(EntryAction {
+                        icon: "tools-check-spelling".into(),
+                        name: "Copy corrected text".into(),
+                        function: EntryAction::copy(corrected.clone()),
+                        key: Key::c,
+                        modifier: ModifierType::CONTROL_MASK | ModifierType::SHIFT_MASK,
+                    });
+                }
+
+                Entry {
+                    name: FormattedString::from_style(&x.word, FormatStyle::Highlight),
+                    description: Some(FormattedString::plain(if x.suggestions.is_empty() {
+                        "No suggestions".to_owned()
+                    } else {
+                        x.suggestions.join(", ")
+                    })),
+                    icon: EntryIcon::Name("tools-check-spelling".into()),
+                    actions,
+                    ..Default::default()
+                }
+            })
+            .collect()
+    }
+}
+
+impl Plugin for Spell {
+    fn name(&self) -> &str {
+        "Spelling"
+    }
+
+    fn icon(&self) -> Option<&str> {
+        Some("tools-check-spelling")
+    }
+
+    fn search(&self, query: &str, _: &Context, _: &CancellationToken) -> Vec<Entry> {
+        if let Some(word) = query.strip_prefix("syn ") {
+            return self.search_synonyms(word.trim());
+        }
+
+        let text = query.strip_prefix("check ").unwrap_or(query).trim();
+        if text.is_empty() {
+            return Vec::new();
+        }
+
+        self.search_spelling(text)
+    }
+}
@@ -1,5 +1,9 @@
 use std::path::Path;
 
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use tokio_util::sync::CancellationToken;
+
 use crate::interface::{Context, Entry, EntryAction, EntryIcon, FormattedString, Plugin};
 
 #[derive(Debug)]
@@ -93,13 +97,22 @@ impl Plugin for Ssh {
         Some("network-wired")
     }
 
-    fn search(&self, query: &str, _: &Context) -> Vec<Entry> {
-        let query = query.to_owned();
+    fn search(&self, query: &str, _: &Context, _: &CancellationToken) -> Vec<Entry> {
+        let matcher = SkimMatcherV2::default().smart_case();
         self.connections
             .iter()
-            .filter(move |x| x.name.contains(&query) || x.address.contains(&query))
-            .map(|x| Entry {
-                name: FormattedString::plain(&x.name),
+            .filter_map(|x| {
+                let name = matcher.fuzzy_indices(&x.name, query);
+                let address = matcher.fuzzy_match(&x.address, query);
+
+                match (name, address) {
+                    (Some((score, indices)), _) => Some((x, score, indices)),
+                    (None, Some(score)) => Some((x, score, Vec::new())),
+                    (None, None) => None,
+                }
+            })
+            .map(|(x, score, indices)| Entry {
+                name: FormattedString::from_indices(&x.name, indices),
                 description: Some(FormattedString::plain(format!(
                     "{}{}{}",
                     x.user.clone().map(|x| x + "@").unwrap_or_default(),
@@ -110,13 +123,15 @@ impl Plugin for Ssh {
                 actions: vec![EntryAction {
                     icon: "network-wired".into(),
                     name: "Connect".into(),
-                    function: EntryAction::launch_terminal(
+                    function: EntryAction::launch_terminal_with_profile(
+                        Some("ssh".into()),
                         Some("ssh".into()),
                         vec![x.name.clone()],
                         None,
                     ),
                     ..Default::default()
                 }],
+                score: score.try_into().unwrap_or(0),
                 ..Default::default()
             })
             .collect()
@@ -0,0 +1,257 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use gtk::gdk::{Key, ModifierType};
+use parking_lot::Mutex;
+use serde::Deserialize;
+use tokio_util::sync::CancellationToken;
+
+use crate::interface::{
+    Context, Entry, EntryAction, EntryIcon, FormattedString, Plugin, parse_options,
+};
+
+/// Quotes are fetched again once they are this old.
+const MAX_AGE: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+struct StocksConfig {
+    /// Yahoo Finance symbols, like `AAPL`, `^GSPC` or `BTC-USD`.
+    tickers: Vec<String>,
+}
+
+impl Default for StocksConfig {
+    fn default() -> Self {
+        Self {
+            tickers: vec!["^GSPC".into(), "BTC-USD".into()],
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Chart {
+    chart: ChartResults,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChartResults {
+    result: Option<Vec<ChartResult>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChartResult {
+    meta: Quote,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Quote {
+    symbol: String,
+    short_name: Option<String>,
+    long_name: Option<String>,
+    currency: Option<String>,
+    regular_market_price: f64,
+    chart_previous_close: Option<f64>,
+}
+
+impl Quote {
+    /// The change since the previous close, in percent.
+    fn change(&self) -> Option<f64> {
+        let previous = self.chart_previous_close.filter(|x| *x != 0.0)?;
+        Some((self.regular_market_price - previous) / previous * 100.0)
+    }
+}
+
+type Cache = HashMap<String, (Instant, Quote)>;
+
+#[derive(Debug)]
+pub struct Stocks {
+    tickers: Vec<String>,
+    handler: Option<String>,
+    cache: Arc<Mutex<Cache>>,
+    fetching: Arc<AtomicBool>,
+}
+
+impl Stocks {
+    pub fn new(context: &Context, options: &toml::Table) -> Self {
+        let config: StocksConfig = parse_options(options);
+
+        Self {
+            tickers: config.tickers,
+            handler: context
+                .apps
+                .default_for_mime(&"x-scheme-handler/https".parse().unwrap())
+                .map(|x| x.id.clone()),
+            cache: Arc::new(Mutex::new(HashMap::new())),
+            fetching: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Fetches the stale quotes on a background thread.
+    fn fetch(&self, tickers: &[String]) {
+        let stale = {
+            let cache = self.cache.lock();
+            tickers
+                .iter()
+                .filter(|x| {
+                    cache
+                        .get(*x)
+                        .is_none_or(|(time, _)| time.elapsed() > MAX_AGE)
+                })
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+
+        if stale.is_empty() || self.fetching.swap(true, Ordering::AcqRel) {
+            return;
+        }
+
+        let cache = self.cache.clone();
+        let fetching = self.fetching.clone();
+
+        std::thread::spawn(move || {
+            let client = reqwest::blocking::Client::new();
+
+            for ticker in stale {
+                let Ok(url) = reqwest::Url::parse_with_params(
+                    &format!("https://query1.finance.yahoo.com/v8/finance/chart/{ticker}"),
+                    [("range", "1d"), ("interval", "1d")],
+                ) else {
+                    continue;
+                };
+
+                let result = client
+                    .get(url)
+                    .header("User-Agent", "jogger")
+                    .send()
+                    .and_then(|x| x.json::<Chart>());
+
+                match result {
+                    Ok(chart) => {
+                        if let Some(quote) = chart
+                            .chart
+                            .result
+                            .and_then(|x| x.into_iter().next())
+                            .map(|x| x.meta)
+                        {
+                            cache.lock().insert(ticker, (Instant::now(), quote));
+                        }
+                    }
+                    Err(error) => tracing::warn!(%ticker, %error, "Failed to fetch quote"),
+                }
+            }
+
+            fetching.store(false, Ordering::Release);
+        });
+    }
+
+    fn quote_to_entry(&self, quote: &Quote) -> Entry {
+        let chart = format!("https://finance.yahoo.com/quote/{}", quote.symbol);
+        let currency = quote.currency.as_deref().unwrap_or_default();
+        let price = format!("{:.2} {currency}", quote.regular_market_price);
+
+        Entry {
+            name: FormattedString::plain(format!("{}  {}", quote.symbol, price.trim_end())),
+            tag: quote.change().map(|x| {
+                FormattedString::plain(format!(
+                    "{} {:.2}%",
+                    if x < 0.0 { "▼" } else { "▲" },
+                    x.abs()
+                ))
+            }),
+            description: quote
+                .long_name
+                .clone()
+                .or_else(|| quote.short_name.clone())
+                .map(FormattedString::plain),
+            icon: EntryIcon::Name("office-chart-line".into()),
+            actions: vec![
+                EntryAction {
+                    icon: "web-browser".into(),
+                    name: "Open chart".into(),
+                    function: match &self.handler {
+                        Some(handler) => {
+                            EntryAction::open(handler.clone(), None, Some(PathBuf::from(&chart)))
+                        }
+                        None => EntryAction::command("xdg-open".into(), vec![chart], None),
+                    },
+                    ..Default::default()
+                },
+                EntryAction {
+                    icon: "edit-copy".into(),
+                    name: "Copy price".into(),
+                    function: EntryAction::copy(quote.regular_market_price.to_string()),
+                    key: Key::c,
+                    modifier: ModifierType::CONTROL_MASK,
+                },
+            ],
+            id: format!("stocks:{}", quote.symbol),
+            ..Default::default()
+        }
+    }
+}
+
+impl Plugin for Stocks {
+    fn open(&mut self) {
+        self.fetch(&self.tickers);
+    }
+
+    fn name(&self) -> &str {
+        "Stocks"
+    }
+
+    fn icon(&self) -> Option<&str> {
+        Some("office-chart-line")
+    }
+
+    fn search(&self, query: &str, _: &Context, token: &CancellationToken) -> Vec<Entry> {
+        let query = query
+            .strip_prefix('$')
+            .unwrap_or(query)
+            .trim()
+            .to_uppercase();
+
+        let mut tickers = self
+            .tickers
+            .iter()
+            .filter(|x| x.contains(&query))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        // Any other symbol is looked up as it's typed
+        if tickers.is_empty()
+            && !query.is_empty()
+            && query
+                .chars()
+                .all(|x| x.is_ascii_alphanumeric() || "^.-=".contains(x))
+        {
+            tickers.push(query);
+        }
+
+        self.fetch(&tickers);
+
+        // Stale quotes are shown while they're updated, but missing ones are worth waiting for
+        while self.fetching.load(Ordering::Acquire)
+            && tickers.iter().any(|x| !self.cache.lock().contains_key(x))
+        {
+            if token.is_cancelled() {
+                return Vec::new();
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+
+        let cache = self.cache.lock();
+        tickers
+            .iter()
+            .filter_map(|x| cache.get(x))
+            .map(|(_, x)| self.quote_to_entry(x))
+            .collect()
+    }
+
+    fn refresh_interval(&self) -> Option<Duration> {
+        Some(MAX_AGE)
+    }
+}
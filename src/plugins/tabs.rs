@@ -0,0 +1,238 @@
+use std::process::Command;
+use std::sync::Arc;
+use std::time::Duration;
+
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use gtk::gdk::{Key, ModifierType};
+use parking_lot::Mutex;
+use serde::Deserialize;
+use tokio_util::sync::CancellationToken;
+
+use crate::interface::{
+    ActionResult, ActionType, Context, Entry, EntryAction, EntryIcon, FormattedString, Plugin,
+    parse_options,
+};
+
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum Backend {
+    /// The `bt` client of the BroTab extension, which talks to Firefox and
+    /// Chromium through native messaging.
+    Brotab,
+    /// The remote debugging protocol of Chromium, started with `--remote-debugging-port`.
+    Devtools,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+struct TabsConfig {
+    backend: Backend,
+    port: u16,
+}
+
+impl Default for TabsConfig {
+    fn default() -> Self {
+        Self {
+            backend: Backend::Brotab,
+            port: 9222,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Tab {
+    id: String,
+    title: String,
+    url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct DevtoolsTarget {
+    id: String,
+    r#type: String,
+    title: String,
+    url: String,
+}
+
+fn load_brotab() -> Option<Vec<Tab>> {
+    let output = match Command::new("bt").arg("list").output() {
+        Ok(x) if x.status.success() => x.stdout,
+        Ok(x) => {
+            let error = String::from_utf8_lossy(&x.stderr);
+            tracing::debug!(%error, "Failed to list the tabs");
+            return None;
+        }
+        Err(error) => {
+            tracing::debug!(?error, "Failed to run bt");
+            return None;
+        }
+    };
+
+    // One tab per line, as the id, title and address separated by tabs
+    Some(
+        String::from_utf8_lossy(&output)
+            .lines()
+            .filter_map(|x| {
+                let mut fields = x.splitn(3, '\t');
+                Some(Tab {
+                    id: fields.next()?.to_owned(),
+                    title: fields.next()?.to_owned(),
+                    url: fields.next()?.to_owned(),
+                })
+            })
+            .collect(),
+    )
+}
+
+fn devtools(port: u16, path: &str) -> reqwest::Result<reqwest::blocking::Response> {
+    reqwest::blocking::Client::builder()
+        .timeout(TIMEOUT)
+        .build()?
+        .get(format!("http://localhost:{port}/json{path}"))
+        .send()?
+        .error_for_status()
+}
+
+fn load_devtools(port: u16) -> Option<Vec<Tab>> {
+    match devtools(port, "/list").and_then(|x| x.json::<Vec<DevtoolsTarget>>()) {
+        Ok(targets) => Some(
+            targets
+                .into_iter()
+                // Also lists workers and extension pages
+                .filter(|x| x.r#type == "page")
+                .map(|x| Tab {
+                    id: x.id,
+                    title: x.title,
+                    url: x.url,
+                })
+                .collect(),
+        ),
+        Err(error) => {
+            tracing::debug!(%error, "Failed to list the tabs");
+            None
+        }
+    }
+}
+
+/// Lists the open tabs of the browser and switches to them.
+#[derive(Debug)]
+pub struct Tabs {
+    backend: Backend,
+    port: u16,
+    tabs: Arc<Mutex<Vec<Tab>>>,
+}
+
+impl Tabs {
+    pub fn new(_: &Context, options: &toml::Table) -> Self {
+        let config: TabsConfig = parse_options(options);
+
+        Self {
+            backend: config.backend,
+            port: config.port,
+            tabs: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    fn tab_action(&self, command: &'static str, id: &str) -> Box<ActionType> {
+        match self.backend {
+            Backend::Brotab => {
+                let mut args = vec![command.to_owned(), id.to_owned()];
+                if command == "activate" {
+                    // Also raises the browser window
+                    args.insert(1, "--focused".into());
+                }
+
+                EntryAction::command("bt".into(), args, None)
+            }
+            Backend::Devtools => {
+                let port = self.port;
+                let path = format!("/{command}/{id}");
+
+                Box::new(move |_| match devtools(port, &path) {
+                    Ok(_) => ActionResult::Ok,
+                    Err(error) => {
+                        tracing::warn!(%error, "Failed to reach the browser");
+                        ActionResult::Error
+                    }
+                })
+            }
+        }
+    }
+}
+
+impl Plugin for Tabs {
+    fn open(&mut self) {
+        // Asking the browser can take a moment
+        let backend = self.backend;
+        let port = self.port;
+        let tabs = self.tabs.clone();
+        std::thread::spawn(move || {
+            let loaded = match backend {
+                Backend::Brotab => load_brotab(),
+                Backend::Devtools => load_devtools(port),
+            };
+            *tabs.lock() = loaded.unwrap_or_default();
+        });
+    }
+
+    fn name(&self) -> &str {
+        "Tabs"
+    }
+
+    fn icon(&self) -> Option<&str> {
+        Some("tab-new")
+    }
+
+    fn search(&self, query: &str, _: &Context, _: &CancellationToken) -> Vec<Entry> {
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let matcher = SkimMatcherV2::default().ignore_case();
+        self.tabs
+            .lock()
+            .iter()
+            .filter_map(|x| {
+                let title = matcher.fuzzy_indices(&x.title, query);
+                let url = matcher.fuzzy_match(&x.url, query).map(|x| x / 2);
+
+                let (score, name) = match (title, url) {
+                    (Some((score, _)), Some(url)) if url > score => {
+                        (url, FormattedString::plain(&x.title))
+                    }
+                    (Some((score, indices)), _) => {
+                        (score, FormattedString::from_indices(&x.title, indices))
+                    }
+                    (None, url) => (url?, FormattedString::plain(&x.title)),
+                };
+
+                Some(Entry {
+                    name,
+                    tag: Some(FormattedString::plain("Tab")),
+                    description: Some(FormattedString::plain(&x.url)),
+                    icon: EntryIcon::Name("web-browser".into()),
+                    actions: vec![
+                        EntryAction {
+                            icon: "go-jump".into(),
+                            name: "Switch to tab".into(),
+                            function: self.tab_action("activate", &x.id),
+                            ..Default::default()
+                        },
+                        EntryAction {
+                            icon: "window-close".into(),
+                            name: "Close tab".into(),
+                            function: self.tab_action("close", &x.id),
+                            key: Key::w,
+                            modifier: ModifierType::CONTROL_MASK,
+                        },
+                    ],
+                    score: score.try_into().ok()?,
+                    ..Default::default()
+                })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,318 @@
+use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant};
+
+use gtk::gdk::{Key, ModifierType};
+use parking_lot::Mutex;
+use tokio_util::sync::CancellationToken;
+
+use crate::interface::{
+    ActionResult, ActionType, Context, Entry, EntryAction, EntryIcon, FormatStyle, FormattedString,
+    Plugin,
+};
+use crate::utils::notify;
+
+#[derive(Debug)]
+struct Timer {
+    id: u64,
+    label: String,
+    /// The length of a countdown, none for a stopwatch.
+    length: Option<Duration>,
+    /// Counted up to the last pause.
+    elapsed: Duration,
+    /// When it was last started or resumed, none while paused.
+    started: Option<Instant>,
+}
+
+impl Timer {
+    fn elapsed(&self) -> Duration {
+        self.elapsed + self.started.map(|x| x.elapsed()).unwrap_or_default()
+    }
+
+    /// What's shown, the time left for a countdown.
+    fn shown(&self) -> Duration {
+        match self.length {
+            Some(length) => length.saturating_sub(self.elapsed()),
+            None => self.elapsed(),
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        self.length.is_some_and(|x| self.elapsed() >= x)
+    }
+}
+
+/// Like `5:07` or `1:02:03`.
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    if seconds >= 3600 {
+        format!(
+            "{}:{:02}:{:02}",
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60
+        )
+    } else {
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    }
+}
+
+/// Like `10m`, `1h30m`, `90s` or a bare `10` for minutes.
+fn parse_duration(value: &str) -> Option<Duration> {
+    if let Ok(minutes) = value.parse::<u64>() {
+        return Some(Duration::from_secs(minutes.checked_mul(60)?));
+    }
+
+    let mut seconds = 0u64;
+    let mut rest = value;
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|x: char| !x.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let amount: u64 = rest[..digits].parse().ok()?;
+        rest = &rest[digits..];
+
+        let letters = rest
+            .find(|x: char| !x.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        let unit = match &rest[..letters] {
+            "h" | "hr" | "hrs" | "hour" | "hours" => 3600,
+            "m" | "min" | "mins" | "minute" | "minutes" => 60,
+            "s" | "sec" | "secs" | "second" | "seconds" => 1,
+            _ => return None,
+        };
+        rest = &rest[letters..];
+
+        seconds = seconds.checked_add(amount.checked_mul(unit)?)?;
+    }
+
+    (seconds > 0).then(|| Duration::from_secs(seconds))
+}
+
+/// Countdowns like `timer 10m tea` and stopwatches, kept while the launcher runs.
+#[derive(Debug)]
+pub struct Timers {
+    timers: Arc<Mutex<Vec<Timer>>>,
+    next_id: Arc<Mutex<u64>>,
+}
+
+impl Timers {
+    pub fn new(_: &Context) -> Self {
+        let plugin = Self {
+            timers: Arc::new(Mutex::new(Vec::new())),
+            next_id: Arc::new(Mutex::new(0)),
+        };
+
+        plugin.watch();
+        plugin
+    }
+
+    /// Notifies of the countdowns that are over, even while hidden, until the plugin is dropped.
+    fn watch(&self) {
+        let timers = Arc::downgrade(&self.timers);
+
+        std::thread::spawn(move || {
+            while let Some(timers) = Weak::upgrade(&timers) {
+                let done = {
+                    let mut timers = timers.lock();
+                    let (done, running): (Vec<_>, Vec<_>) = std::mem::take(&mut *timers)
+                        .into_iter()
+                        .partition(Timer::is_done);
+                    *timers = running;
+                    done
+                };
+                drop(timers);
+
+                for timer in done {
+                    let length = timer.length.map(format_duration).unwrap_or_default();
+                    notify(&format!("{} is done", timer.label), &length, "alarm");
+                }
+
+                std::thread::sleep(Duration::from_millis(250));
+            }
+        });
+    }
+
+    fn start(&self, label: String, length: Option<Duration>) -> Box<ActionType> {
+        let timers = self.timers.clone();
+        let next_id = self.next_id.clone();
+
+        Box::new(move |_| {
+            let mut id = next_id.lock();
+            *id += 1;
+
+            timers.lock().push(Timer {
+                id: *id,
+                label: label.clone(),
+                length,
+                elapsed: Duration::ZERO,
+                started: Some(Instant::now()),
+            });
+
+            // Lists the timers, to see it running
+            ActionResult::SetText("timer".into())
+        })
+    }
+
+    fn toggle(&self, id: u64) -> Box<ActionType> {
+        let timers = self.timers.clone();
+
+        Box::new(move |_| {
+            if let Some(timer) = timers.lock().iter_mut().find(|x| x.id == id) {
+                match timer.started.take() {
+                    Some(started) => timer.elapsed += started.elapsed(),
+                    None => timer.started = Some(Instant::now()),
+                }
+            }
+
+            ActionResult::Refresh
+        })
+    }
+
+    fn cancel(&self, id: u64) -> Box<ActionType> {
+        let timers = self.timers.clone();
+
+        Box::new(move |_| {
+            timers.lock().retain(|x| x.id != id);
+            ActionResult::Refresh
+        })
+    }
+
+    fn timer_to_entry(&self, timer: &Timer, score: u64) -> Entry {
+        let running = timer.started.is_some();
+        let label = format!("{}  ", timer.label);
+        let shown = format_duration(timer.shown());
+
+        Entry {
+            name: FormattedString::from_styles(vec![
+                (&label, None),
+                (&shown, Some(FormatStyle::Monospace)),
+            ]),
+            tag: Some(FormattedString::plain(if running {
+                "Running"
+            } else {
+                "Paused"
+            })),
+            description: Some(FormattedString::plain(match timer.length {
+                Some(length) => format!("Timer of {}", format_duration(length)),
+                None => "Stopwatch".to_owned(),
+            })),
+            icon: EntryIcon::Name("alarm".into()),
+            actions: vec![
+                EntryAction {
+                    icon: if running {
+                        "media-playback-pause"
+                    } else {
+                        "media-playback-start"
+                    }
+                    .into(),
+                    name: if running { "Pause" } else { "Resume" }.into(),
+                    function: self.toggle(timer.id),
+                    ..Default::default()
+                },
+                EntryAction {
+                    icon: "media-playback-stop".into(),
+                    name: "Cancel".into(),
+                    function: self.cancel(timer.id),
+                    key: Key::Delete,
+                    modifier: ModifierType::CONTROL_MASK,
+                },
+                EntryAction {
+                    icon: "edit-copy".into(),
+                    name: "Copy time".into(),
+                    function: EntryAction::copy(&shown),
+                    key: Key::c,
+                    modifier: ModifierType::CONTROL_MASK,
+                },
+            ],
+            score,
+            ..Default::default()
+        }
+    }
+}
+
+impl Plugin for Timers {
+    fn name(&self) -> &str {
+        "Timers"
+    }
+
+    fn icon(&self) -> Option<&str> {
+        Some("alarm")
+    }
+
+    fn search(&self, query: &str, _: &Context, _: &CancellationToken) -> Vec<Entry> {
+        let strip = |prefix: &str| {
+            query
+                .strip_prefix(prefix)
+                .filter(|x: &&str| x.is_empty() || x.starts_with(char::is_whitespace))
+        };
+        let (stopwatch, rest) = match strip("stopwatch") {
+            Some(rest) => (true, rest),
+            None => (false, strip("timer").unwrap_or(query)),
+        };
+
+        // The leading durations, then the label
+        let mut length = Duration::ZERO;
+        let mut words = rest.split_whitespace().peekable();
+        if !stopwatch {
+            while let Some(x) = words.peek().and_then(|x| parse_duration(x)) {
+                length += x;
+                words.next();
+            }
+        }
+        let label = words.collect::<Vec<_>>().join(" ");
+
+        let timers = self.timers.lock();
+        let count = timers.len() as u64;
+
+        // The latest first
+        let mut entries = timers
+            .iter()
+            .enumerate()
+            .map(|(i, x)| self.timer_to_entry(x, i as u64 + 1))
+            .collect::<Vec<_>>();
+
+        let new = if stopwatch {
+            Some((None, "Start stopwatch".to_owned()))
+        } else if !length.is_zero() {
+            Some((
+                Some(length),
+                format!("Start {} timer", format_duration(length)),
+            ))
+        } else {
+            None
+        };
+
+        if let Some((length, name)) = new {
+            let label = match label.is_empty() {
+                true if stopwatch => "Stopwatch".to_owned(),
+                true => "Timer".to_owned(),
+                false => label,
+            };
+
+            entries.push(Entry {
+                name: FormattedString::plain(&label),
+                tag: Some(FormattedString::from_style(name, FormatStyle::Special)),
+                icon: EntryIcon::Name("list-add".into()),
+                actions: vec![EntryAction {
+                    icon: "media-playback-start".into(),
+                    name: "Start".into(),
+                    function: self.start(label, length),
+                    ..Default::default()
+                }],
+                // Above the running ones
+                score: count + 1,
+                ..Default::default()
+            });
+        }
+
+        entries
+    }
+
+    fn refresh_interval(&self) -> Option<Duration> {
+        self.timers
+            .lock()
+            .iter()
+            .any(|x| x.started.is_some())
+            .then_some(Duration::from_secs(1))
+    }
+}
@@ -0,0 +1,297 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, FixedOffset};
+use gtk::gdk::{Key, ModifierType};
+use parking_lot::Mutex;
+use serde::Deserialize;
+use tokio_util::sync::CancellationToken;
+
+use crate::interface::{
+    ActionResult, ActionType, Context, Entry, EntryAction, EntryIcon, FormattedString, Plugin,
+    parse_options,
+};
+
+/// Departures are fetched again once they are this old.
+const MAX_AGE: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Deserialize)]
+struct Stop {
+    id: String,
+    /// Defaults to the name given by the API.
+    name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+struct TransitConfig {
+    stops: Vec<Stop>,
+    /// A `transport.rest` compatible API, the default one covers Germany and most of Europe.
+    api: String,
+    /// Departures shown for each stop.
+    results: usize,
+}
+
+impl Default for TransitConfig {
+    fn default() -> Self {
+        Self {
+            stops: Vec::new(),
+            api: "https://v6.db.transport.rest".into(),
+            results: 5,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Departures {
+    departures: Vec<Departure>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Departure {
+    when: Option<DateTime<FixedOffset>>,
+    planned_when: Option<DateTime<FixedOffset>>,
+    /// In seconds.
+    delay: Option<i64>,
+    platform: Option<String>,
+    direction: Option<String>,
+    line: Option<Line>,
+    stop: Option<StopInfo>,
+    #[serde(default)]
+    cancelled: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Line {
+    name: Option<String>,
+    product: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct StopInfo {
+    name: Option<String>,
+}
+
+impl Departure {
+    fn time(&self) -> Option<DateTime<FixedOffset>> {
+        self.when.or(self.planned_when)
+    }
+}
+
+type Cache = HashMap<String, (Instant, Vec<Departure>)>;
+
+#[derive(Debug)]
+pub struct Transit {
+    stops: Vec<Stop>,
+    api: String,
+    results: usize,
+    cache: Arc<Mutex<Cache>>,
+    fetching: Arc<AtomicBool>,
+}
+
+impl Transit {
+    pub fn new(_: &Context, options: &toml::Table) -> Self {
+        let config: TransitConfig = parse_options(options);
+
+        Self {
+            stops: config.stops,
+            api: config.api.trim_end_matches('/').to_owned(),
+            results: config.results,
+            cache: Arc::new(Mutex::new(HashMap::new())),
+            fetching: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Fetches the stale stops on a background thread.
+    fn fetch(&self) {
+        let stale = {
+            let cache = self.cache.lock();
+            self.stops
+                .iter()
+                .filter(|x| {
+                    cache
+                        .get(&x.id)
+                        .is_none_or(|(time, _)| time.elapsed() > MAX_AGE)
+                })
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+
+        if stale.is_empty() || self.fetching.swap(true, Ordering::AcqRel) {
+            return;
+        }
+
+        let api = self.api.clone();
+        let results = self.results;
+        let cache = self.cache.clone();
+        let fetching = self.fetching.clone();
+
+        std::thread::spawn(move || {
+            for stop in stale {
+                let url = format!(
+                    "{api}/stops/{}/departures?results={results}&duration=120",
+                    stop.id
+                );
+
+                match reqwest::blocking::get(url).and_then(|x| x.json::<Departures>()) {
+                    Ok(x) => {
+                        cache.lock().insert(stop.id, (Instant::now(), x.departures));
+                    }
+                    Err(error) => {
+                        tracing::warn!(stop = %stop.id, %error, "Failed to fetch departures");
+                    }
+                }
+            }
+
+            fetching.store(false, Ordering::Release);
+        });
+    }
+
+    fn departure_to_entry(&self, stop: &Stop, departure: &Departure) -> Option<Entry> {
+        let time = departure.time()?;
+        let minutes = (time.timestamp() - chrono::Utc::now().timestamp()).div_euclid(60);
+
+        let line = departure
+            .line
+            .as_ref()
+            .and_then(|x| x.name.clone())
+            .unwrap_or_default();
+        let direction = departure.direction.clone().unwrap_or_default();
+        let stop_name = stop
+            .name
+            .clone()
+            .or_else(|| departure.stop.as_ref().and_then(|x| x.name.clone()))
+            .unwrap_or_else(|| stop.id.clone());
+
+        let clock = time
+            .with_timezone(&chrono::Local)
+            .format("%H:%M")
+            .to_string();
+        let mut details = vec![
+            if departure.cancelled {
+                "Cancelled".to_owned()
+            } else if minutes <= 0 {
+                "Now".to_owned()
+            } else {
+                format!("in {minutes} min")
+            },
+            clock.clone(),
+        ];
+        if let Some(delay) = departure.delay.filter(|x| *x >= 60) {
+            details.push(format!("+{} min", delay / 60));
+        }
+        if let Some(platform) = &departure.platform {
+            details.push(format!("Platform {platform}"));
+        }
+
+        let icon = match departure.line.as_ref().and_then(|x| x.product.as_deref()) {
+            Some("bus") => "bus",
+            Some("tram") => "tram",
+            Some("subway") => "subway",
+            Some("ferry") => "ferry",
+            _ => "train",
+        };
+
+        Some(Entry {
+            name: FormattedString::plain(format!("{line} → {direction}")),
+            tag: Some(FormattedString::plain(stop_name)),
+            description: Some(FormattedString::plain(details.join("  ·  "))),
+            icon: EntryIcon::Name(icon.into()),
+            actions: vec![
+                EntryAction {
+                    icon: "edit-copy".into(),
+                    name: "Copy".into(),
+                    function: EntryAction::copy(format!("{line} to {direction} at {clock}")),
+                    ..Default::default()
+                },
+                EntryAction {
+                    icon: "view-refresh".into(),
+                    name: "Refresh".into(),
+                    function: refresh(self.cache.clone()),
+                    key: Key::u,
+                    modifier: ModifierType::CONTROL_MASK,
+                },
+            ],
+            ..Default::default()
+        })
+    }
+}
+
+/// Forgets the cached departures, so they are fetched on the next search.
+fn refresh(cache: Arc<Mutex<Cache>>) -> Box<ActionType> {
+    Box::new(move |_| {
+        cache.lock().clear();
+        ActionResult::Refresh
+    })
+}
+
+impl Plugin for Transit {
+    fn open(&mut self) {
+        self.fetch();
+    }
+
+    fn name(&self) -> &str {
+        "Departures"
+    }
+
+    fn icon(&self) -> Option<&str> {
+        Some("train")
+    }
+
+    fn search(&self, query: &str, _: &Context, token: &CancellationToken) -> Vec<Entry> {
+        self.fetch();
+
+        // Stale departures are shown while they're updated, but missing ones are worth waiting for
+        while self.fetching.load(Ordering::Acquire)
+            && self
+                .stops
+                .iter()
+                .any(|x| !self.cache.lock().contains_key(&x.id))
+        {
+            if token.is_cancelled() {
+                return Vec::new();
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+
+        let query = query.strip_prefix("dep").unwrap_or(query).trim();
+        let query = query.to_lowercase();
+        let now = chrono::Utc::now();
+        let cache = self.cache.lock();
+
+        let mut departures = self
+            .stops
+            .iter()
+            .flat_map(|stop| {
+                cache
+                    .get(&stop.id)
+                    .into_iter()
+                    .flat_map(|(_, x)| x.iter())
+                    // Cached departures that already left are dropped
+                    .filter(|x| {
+                        x.time()
+                            .is_some_and(|x| x >= now - chrono::Duration::minutes(1))
+                    })
+                    .map(move |x| (stop, x))
+            })
+            .filter_map(|(stop, x)| Some((x.time()?, self.departure_to_entry(stop, x)?)))
+            .filter(|(_, x)| {
+                query.is_empty()
+                    || x.name.text.to_lowercase().contains(&query)
+                    || x.tag
+                        .as_ref()
+                        .is_some_and(|x| x.text.to_lowercase().contains(&query))
+            })
+            .collect::<Vec<_>>();
+
+        departures.sort_by_key(|(time, _)| *time);
+        departures.into_iter().map(|(_, x)| x).collect()
+    }
+
+    fn refresh_interval(&self) -> Option<Duration> {
+        Some(Duration::from_secs(30))
+    }
+}
@@ -0,0 +1,224 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// A subset of the RFC 1345 digraphs, as used by Vim.
+#[rustfmt::skip]
+const DIGRAPHS: &[(&str, char)] = &[
+    // Latin letters with diacritics
+    ("A!", 'À'), ("A'", 'Á'), ("A>", 'Â'), ("A?", 'Ã'), ("A:", 'Ä'), ("AA", 'Å'),
+    ("AE", 'Æ'), ("C,", 'Ç'), ("E!", 'È'), ("E'", 'É'), ("E>", 'Ê'), ("E:", 'Ë'),
+    ("I!", 'Ì'), ("I'", 'Í'), ("I>", 'Î'), ("I:", 'Ï'), ("D-", 'Ð'), ("N?", 'Ñ'),
+    ("O!", 'Ò'), ("O'", 'Ó'), ("O>", 'Ô'), ("O?", 'Õ'), ("O:", 'Ö'), ("O/", 'Ø'),
+    ("U!", 'Ù'), ("U'", 'Ú'), ("U>", 'Û'), ("U:", 'Ü'), ("Y'", 'Ý'), ("TH", 'Þ'),
+    ("ss", 'ß'), ("a!", 'à'), ("a'", 'á'), ("a>", 'â'), ("a?", 'ã'), ("a:", 'ä'),
+    ("aa", 'å'), ("ae", 'æ'), ("c,", 'ç'), ("e!", 'è'), ("e'", 'é'), ("e>", 'ê'),
+    ("e:", 'ë'), ("i!", 'ì'), ("i'", 'í'), ("i>", 'î'), ("i:", 'ï'), ("d-", 'ð'),
+    ("n?", 'ñ'), ("o!", 'ò'), ("o'", 'ó'), ("o>", 'ô'), ("o?", 'õ'), ("o:", 'ö'),
+    ("o/", 'ø'), ("u!", 'ù'), ("u'", 'ú'), ("u>", 'û'), ("u:", 'ü'), ("y'", 'ý'),
+    ("th", 'þ'), ("y:", 'ÿ'), ("C<", 'Č'), ("c<", 'č'), ("S<", 'Š'), ("s<", 'š'),
+    ("Z<", 'Ž'), ("z<", 'ž'), ("E<", 'Ě'), ("e<", 'ě'), ("R<", 'Ř'), ("r<", 'ř'),
+    ("L/", 'Ł'), ("l/", 'ł'), ("OE", 'Œ'), ("oe", 'œ'), ("G(", 'Ğ'), ("g(", 'ğ'),
+    ("S,", 'Ş'), ("s,", 'ş'), ("I.", 'İ'), ("i.", 'ı'), ("O\"", 'Ő'), ("o\"", 'ő'),
+    ("U\"", 'Ű'), ("u\"", 'ű'), ("A;", 'Ą'), ("a;", 'ą'), ("E;", 'Ę'), ("e;", 'ę'),
+    // Greek
+    ("A*", 'Α'), ("B*", 'Β'), ("G*", 'Γ'), ("D*", 'Δ'), ("E*", 'Ε'), ("Z*", 'Ζ'),
+    ("Y*", 'Η'), ("H*", 'Θ'), ("I*", 'Ι'), ("K*", 'Κ'), ("L*", 'Λ'), ("M*", 'Μ'),
+    ("N*", 'Ν'), ("C*", 'Ξ'), ("O*", 'Ο'), ("P*", 'Π'), ("R*", 'Ρ'), ("S*", 'Σ'),
+    ("T*", 'Τ'), ("U*", 'Υ'), ("F*", 'Φ'), ("X*", 'Χ'), ("Q*", 'Ψ'), ("W*", 'Ω'),
+    ("a*", 'α'), ("b*", 'β'), ("g*", 'γ'), ("d*", 'δ'), ("e*", 'ε'), ("z*", 'ζ'),
+    ("y*", 'η'), ("h*", 'θ'), ("i*", 'ι'), ("k*", 'κ'), ("l*", 'λ'), ("m*", 'μ'),
+    ("n*", 'ν'), ("c*", 'ξ'), ("o*", 'ο'), ("p*", 'π'), ("r*", 'ρ'), ("*s", 'ς'),
+    ("s*", 'σ'), ("t*", 'τ'), ("u*", 'υ'), ("f*", 'φ'), ("x*", 'χ'), ("q*", 'ψ'),
+    ("w*", 'ω'),
+    // Punctuation
+    ("!I", '¡'), ("?I", '¿'), ("<<", '«'), (">>", '»'), ("'6", '‘'), ("'9", '’'),
+    (".9", '‚'), ("\"6", '“'), ("\"9", '”'), (":9", '„'), ("-N", '–'), ("-M", '—'),
+    ("-1", '‐'), (",.", '…'), (".P", '·'), ("SE", '§'), ("PI", '¶'), ("/-", '†'),
+    ("/=", '‡'), ("oo", '•'), ("NS", '\u{a0}'),
+    // Symbols
+    ("Eu", '€'), ("Pd", '£'), ("Ye", '¥'), ("Ct", '¢'), ("Co", '©'), ("Rg", '®'),
+    ("TM", '™'), ("DG", '°'), ("My", 'µ'), ("%0", '‰'), ("1S", '¹'), ("2S", '²'),
+    ("3S", '³'), ("0S", '⁰'), ("1s", '₁'), ("2s", '₂'), ("12", '½'), ("14", '¼'),
+    ("34", '¾'), ("13", '⅓'), ("23", '⅔'), ("cH", '♥'), ("*1", '☆'), ("*2", '★'),
+    ("OK", '✓'), ("XX", '✗'),
+    // Arrows
+    ("->", '→'), ("<-", '←'), ("-!", '↑'), ("-v", '↓'), ("<>", '↔'), ("UD", '↕'),
+    ("=>", '⇒'), ("<=", '⇐'), ("==", '⇔'),
+    // Mathematics
+    ("+-", '±'), ("*X", '×'), ("-:", '÷'), ("!=", '≠'), ("=<", '≤'), (">=", '≥'),
+    ("?=", '≅'), ("?2", '≈'), ("=3", '≡'), ("00", '∞'), ("FA", '∀'), ("dP", '∂'),
+    ("TE", '∃'), ("/0", '∅'), ("DE", '∆'), ("NB", '∇'), ("(-", '∈'), ("-)", '∋'),
+    ("*P", '∏'), ("+Z", '∑'), ("RT", '√'), ("0(", '∝'), ("AN", '∧'), ("OR", '∨'),
+    ("(U", '∩'), (")U", '∪'), ("In", '∫'), ("(C", '⊂'), (")C", '⊃'), ("(_", '⊆'),
+    (")_", '⊇'), ("NO", '¬'), ("-T", '⊥'), ("-X", '∖'),
+];
+
+/// Looks up the character for a two character digraph.
+pub fn digraph(query: &str) -> Option<(&'static str, char)> {
+    DIGRAPHS.iter().find(|(x, _)| *x == query).copied()
+}
+
+#[derive(Debug)]
+pub struct ComposeSequence {
+    /// The characters typed after the compose key.
+    pub keys: String,
+    pub result: String,
+    pub description: Option<String>,
+}
+
+/// Reads the user's compose file, falling back to the one of the current locale.
+pub fn load_compose() -> Vec<ComposeSequence> {
+    let user = std::env::var_os("XCOMPOSEFILE")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|x| PathBuf::from(x).join(".XCompose")))
+        .filter(|x| x.is_file());
+
+    let mut sequences = Vec::new();
+    let mut visited = HashSet::new();
+
+    match user.or_else(locale_compose_file) {
+        Some(path) => parse_file(&path, &mut sequences, &mut visited),
+        None => return sequences,
+    }
+
+    // Later definitions override the included ones
+    let mut seen = HashSet::new();
+    sequences.reverse();
+    sequences.retain(|x| seen.insert(x.keys.clone()));
+    sequences.reverse();
+    sequences
+}
+
+fn locale_compose_file() -> Option<PathBuf> {
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .into_iter()
+        .filter_map(|x| std::env::var(x).ok())
+        .find(|x| !x.is_empty())
+        .unwrap_or("C".into());
+    let locale = locale.split('@').next().unwrap_or_default();
+
+    let root = Path::new("/usr/share/X11/locale");
+    let directory = std::fs::read_to_string(root.join("compose.dir")).ok()?;
+
+    directory
+        .lines()
+        .filter(|x| !x.starts_with('#'))
+        .filter_map(|x| x.split_once(char::is_whitespace))
+        .find(|(_, name)| name.trim().trim_end_matches(':') == locale)
+        .map(|(file, _)| root.join(file.trim_end_matches(':')))
+}
+
+fn parse_file(path: &Path, sequences: &mut Vec<ComposeSequence>, visited: &mut HashSet<PathBuf>) {
+    if !visited.insert(path.to_owned()) {
+        return;
+    }
+
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return;
+    };
+
+    for line in content.lines().map(str::trim) {
+        if let Some(include) = line.strip_prefix("include") {
+            let include = include.trim().trim_matches('"');
+
+            let include = match include {
+                "%L" => locale_compose_file(),
+                x => Some(PathBuf::from(
+                    x.replace("%H", &std::env::var("HOME").unwrap_or_default())
+                        .replace("%S", "/usr/share/X11/locale"),
+                )),
+            };
+
+            if let Some(include) = include {
+                parse_file(&include, sequences, visited);
+            }
+        } else if let Some(sequence) = parse_line(line) {
+            sequences.push(sequence);
+        }
+    }
+}
+
+fn parse_line(line: &str) -> Option<ComposeSequence> {
+    let (keys, rest) = line.split_once(':')?;
+    let keys = keys.trim().strip_prefix("<Multi_key>")?;
+
+    let keys = keys
+        .split('>')
+        .map(|x| x.trim().trim_start_matches('<'))
+        .filter(|x| !x.is_empty())
+        .map(keysym_to_char)
+        .collect::<Option<String>>()?;
+
+    let rest = rest.trim().strip_prefix('"')?;
+
+    let mut result = String::new();
+    let mut chars = rest.char_indices();
+    let end = loop {
+        match chars.next()? {
+            (_, '\\') => result.push(chars.next()?.1),
+            (i, '"') => break i,
+            (_, c) => result.push(c),
+        }
+    };
+
+    let description = rest[end + 1..]
+        .split_once('#')
+        .map(|(_, x)| x.trim().to_owned())
+        .filter(|x| !x.is_empty());
+
+    Some(ComposeSequence {
+        keys,
+        result,
+        description,
+    })
+}
+
+fn keysym_to_char(name: &str) -> Option<char> {
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(c);
+    }
+
+    if let Some(hex) = name.strip_prefix('U')
+        && let Ok(codepoint) = u32::from_str_radix(hex, 16)
+    {
+        return char::from_u32(codepoint);
+    }
+
+    Some(match name {
+        "space" => ' ',
+        "exclam" => '!',
+        "quotedbl" => '"',
+        "numbersign" => '#',
+        "dollar" => '$',
+        "percent" => '%',
+        "ampersand" => '&',
+        "apostrophe" => '\'',
+        "parenleft" => '(',
+        "parenright" => ')',
+        "asterisk" => '*',
+        "plus" => '+',
+        "comma" => ',',
+        "minus" => '-',
+        "period" => '.',
+        "slash" => '/',
+        "colon" => ':',
+        "semicolon" => ';',
+        "less" => '<',
+        "equal" => '=',
+        "greater" => '>',
+        "question" => '?',
+        "at" => '@',
+        "bracketleft" => '[',
+        "backslash" => '\\',
+        "bracketright" => ']',
+        "asciicircum" => '^',
+        "underscore" => '_',
+        "grave" => '`',
+        "braceleft" => '{',
+        "bar" => '|',
+        "braceright" => '}',
+        "asciitilde" => '~',
+        _ => return None,
+    })
+}
@@ -1,19 +1,120 @@
 use bstr::ByteSlice;
+use std::collections::HashSet;
+mod compose;
 mod data;
 mod types;
 
+use crate::favorites::{self, FAVORITE_SCORE};
 use crate::interface::{
     Context, Entry, EntryAction, EntryIcon, FormatStyle, FormattedString, Plugin,
 };
+use crate::plugins::unicode::compose::ComposeSequence;
 use crate::plugins::unicode::data::DATA;
+use crate::utils::SearchCache;
 use gtk::gdk::ModifierType;
+use itertools::Itertools;
+use tokio_util::sync::CancellationToken;
+
+/// The kind of the favorites of this plugin.
+const FAVORITES: &str = "unicode";
+
+/// Past that the entries take too long to build, even when hidden behind "Show more".
+const MAX_RESULTS: usize = 1024;
 
 #[derive(Debug)]
-pub struct Unicode {}
+pub struct Unicode {
+    compose: Vec<ComposeSequence>,
+    /// The indices of the characters matched by name by the last query.
+    cache: SearchCache<usize>,
+}
 
 impl Unicode {
     pub fn new(_: &Context) -> Self {
-        Self {}
+        Self {
+            compose: compose::load_compose(),
+            cache: SearchCache::default(),
+        }
+    }
+
+    fn search_compose(&self, keys: &str) -> Vec<Entry> {
+        let description = keys.to_uppercase();
+
+        self.compose
+            .iter()
+            .filter(|x| {
+                x.keys.starts_with(keys)
+                    || keys.len() > 1
+                        && x.description
+                            .as_ref()
+                            .is_some_and(|x| x.contains(&description))
+            })
+            .take(MAX_RESULTS)
+            .map(|x| {
+                let sequence = x
+                    .keys
+                    .chars()
+                    .map(|x| if x == ' ' { '⎵' } else { x })
+                    .join(" ");
+
+                Entry {
+                    name: FormattedString::plain(match &x.description {
+                        Some(description) => titlecase(description.as_bytes()),
+                        None => x.result.clone(),
+                    }),
+                    tag: Some(FormattedString::from_style(
+                        format!("⎄ {sequence}"),
+                        FormatStyle::Monospace,
+                    )),
+                    description: Some(FormattedString::plain(
+                        x.result
+                            .chars()
+                            .map(|x| format!("{:04X}", x as u32))
+                            .join(" "),
+                    )),
+                    icon: EntryIcon::Text(x.result.clone()),
+                    actions: vec![
+                        EntryAction {
+                            icon: "edit-copy".into(),
+                            name: "Copy".into(),
+                            function: EntryAction::copy(&x.result),
+                            ..Default::default()
+                        },
+                        EntryAction {
+                            icon: "edit-copy".into(),
+                            name: "Copy sequence".into(),
+                            modifier: ModifierType::SHIFT_MASK,
+                            function: EntryAction::copy(&x.keys),
+                            ..Default::default()
+                        },
+                    ],
+                    ..Default::default()
+                }
+            })
+            .collect()
+    }
+}
+
+fn digraph_to_entry(digraph: &str, c: char) -> Entry {
+    let name = DATA
+        .binary_search_by(|x| x.scalar.cmp(&c))
+        .map(|x| titlecase(DATA[x].name))
+        .unwrap_or(c.to_string());
+
+    Entry {
+        name: FormattedString::plain(name),
+        tag: Some(FormattedString::from_style(
+            format!("Digraph {digraph}"),
+            FormatStyle::Monospace,
+        )),
+        description: Some(FormattedString::plain(format!("{:04X}", c as u32))),
+        icon: EntryIcon::Text(c.to_string()),
+        actions: vec![EntryAction {
+            icon: "edit-copy".into(),
+            name: "Copy".into(),
+            function: EntryAction::copy(c.to_string()),
+            ..Default::default()
+        }],
+        ..Default::default()
     }
 }
 
@@ -48,73 +149,191 @@ impl Plugin for Unicode {
         Some("accessories-character-map")
     }
 
-    fn search(&self, query: &str, _: &Context) -> Vec<Entry> {
-        if query.is_empty() {
-            // TODO: add recents
-        } else if query.chars().count() == 1 {
-        } else if query.chars().all(is_unicode_name) {
-            let iter1 = u32::from_str_radix(query, 16)
-                .into_iter()
-                .flat_map(|codepoint| DATA.binary_search_by(|x| x.codepoint.cmp(&codepoint)))
-                .map(|x| &DATA[x])
-                .map(|x| Entry {
-                    name: FormattedString::plain(titlecase(x.name)),
-                    tag: Some(FormattedString::plain(x.category.to_string())),
-                    description: Some(FormattedString::from_style(
-                        format!("{:04X}", x.codepoint),
-                        FormatStyle::Highlight,
-                    )),
-                    icon: EntryIcon::Text(x.representation().to_string()),
-                    actions: vec![EntryAction {
+    fn search(&self, query: &str, context: &Context, _: &CancellationToken) -> Vec<Entry> {
+        if let Some(keys) = query
+            .strip_prefix("compose")
+            .filter(|x| x.is_empty() || x.starts_with(' '))
+        {
+            return self.search_compose(keys.strip_prefix(' ').unwrap_or(keys));
+        }
+
+        let digraph = compose::digraph(query.trim_end()).map(|(x, c)| digraph_to_entry(x, c));
+
+        digraph
+            .into_iter()
+            .chain(search_characters(query, context, &self.cache))
+            .collect()
+    }
+}
+
+/// The starred characters, in the order they were added.
+fn favorite_entries(context: &Context) -> Vec<Entry> {
+    let favorites = context.favorites.get(FAVORITES);
+
+    favorites
+        .iter()
+        .enumerate()
+        .filter_map(|(i, value)| {
+            let c = value.chars().next()?;
+            let x = &DATA[DATA.binary_search_by(|x| x.scalar.cmp(&c)).ok()?];
+
+            Some(Entry {
+                name: FormattedString::plain(titlecase(x.name)),
+                tag: Some(FormattedString::from_style(
+                    "Favorite",
+                    FormatStyle::Special,
+                )),
+                description: Some(FormattedString::plain(format!("{:04X}", x.codepoint))),
+                icon: EntryIcon::Text(x.representation().to_string()),
+                actions: vec![
+                    EntryAction {
                         icon: "edit-copy".into(),
                         name: "Copy".into(),
-                        function: EntryAction::copy_bytes(x.name),
+                        function: EntryAction::copy(value),
+                        ..Default::default()
+                    },
+                    EntryAction {
+                        icon: "edit-copy".into(),
+                        name: "Copy codepoint".into(),
+                        modifier: ModifierType::SHIFT_MASK,
+                        function: EntryAction::copy(format!("{:X}", x.codepoint)),
                         ..Default::default()
-                    }],
+                    },
+                    favorites::action(FAVORITES, value, context),
+                ],
+                score: FAVORITE_SCORE - i as u64,
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+fn search_characters(query: &str, context: &Context, cache: &SearchCache<usize>) -> Vec<Entry> {
+    if query.is_empty() {
+        // TODO: add recents
+        return favorite_entries(context);
+    } else if query.chars().count() == 1 {
+    } else if query.chars().all(is_unicode_name) {
+        let iter1 = u32::from_str_radix(query, 16)
+            .into_iter()
+            .flat_map(|codepoint| DATA.binary_search_by(|x| x.codepoint.cmp(&codepoint)))
+            .map(|x| &DATA[x])
+            .map(|x| Entry {
+                name: FormattedString::plain(titlecase(x.name)),
+                tag: Some(FormattedString::plain(x.category.to_string())),
+                description: Some(FormattedString::from_style(
+                    format!("{:04X}", x.codepoint),
+                    FormatStyle::Highlight,
+                )),
+                icon: EntryIcon::Text(x.representation().to_string()),
+                actions: vec![EntryAction {
+                    icon: "edit-copy".into(),
+                    name: "Copy".into(),
+                    function: EntryAction::copy_bytes(x.name),
                     ..Default::default()
-                });
-
-            let query = Vec::from(query.to_uppercase());
-            let len = query.len();
-            let finder = bstr::Finder::new(&query);
-
-            let iter2 = DATA.iter().flat_map(|x| {
-                let vals = if let Some(i) = finder.find(x.name) {
-                    Some((
-                        FormattedString {
-                            text: titlecase(x.name),
-                            ranges: vec![(FormatStyle::Highlight, i..(i + len))],
-                        },
-                        FormattedString::plain(x.category.to_string()),
-                    ))
-                } else if let Some((alias, i)) = x
-                    .aliases
-                    .iter()
-                    .take_while(|x| !x.is_empty())
-                    .flat_map(|x| finder.find(x).map(|i| (x, i)))
-                    .next()
-                {
-                    Some((
-                        FormattedString::plain(titlecase(x.name)),
-                        FormattedString {
-                            text: titlecase(alias),
-                            ranges: vec![(FormatStyle::Highlight, i..(i + len))],
-                        },
-                    ))
-                } else {
-                    None
-                };
-
-                vals.map(|(name, tag)| Entry {
-                    name,
-                    tag: Some(tag),
+                }],
+                ..Default::default()
+            });
+
+        let candidates = cache.take(query);
+        let mut matched = HashSet::new();
+
+        let upper = Vec::from(query.to_uppercase());
+        let len = upper.len();
+        let finder = bstr::Finder::new(&upper);
+
+        let iter2 = DATA.iter().enumerate().flat_map(|(index, x)| {
+            if candidates.as_ref().is_some_and(|x| !x.contains(&index)) {
+                return None;
+            }
+
+            let vals = if let Some(i) = finder.find(x.name) {
+                Some((
+                    FormattedString {
+                        text: titlecase(x.name),
+                        ranges: vec![(FormatStyle::Highlight, i..(i + len))],
+                    },
+                    FormattedString::plain(x.category.to_string()),
+                ))
+            } else if let Some((alias, i)) = x
+                .aliases
+                .iter()
+                .take_while(|x| !x.is_empty())
+                .flat_map(|x| finder.find(x).map(|i| (x, i)))
+                .next()
+            {
+                Some((
+                    FormattedString::plain(titlecase(x.name)),
+                    FormattedString {
+                        text: titlecase(alias),
+                        ranges: vec![(FormatStyle::Highlight, i..(i + len))],
+                    },
+                ))
+            } else {
+                None
+            };
+
+            if vals.is_some() {
+                matched.insert(index);
+            }
+
+            vals.map(|(name, tag)| Entry {
+                name,
+                tag: Some(tag),
+                description: Some(FormattedString::plain(format!("{:04X}", x.codepoint))),
+                icon: EntryIcon::Text(x.representation().to_string()),
+                actions: vec![
+                    EntryAction {
+                        icon: "edit-copy".into(),
+                        name: "Copy".into(),
+                        function: EntryAction::copy(x.scalar.to_string()),
+                        ..Default::default()
+                    },
+                    EntryAction {
+                        icon: "edit-copy".into(),
+                        name: "Copy codepoint".into(),
+                        modifier: ModifierType::SHIFT_MASK,
+                        function: EntryAction::copy(format!("{:X}", x.codepoint)),
+                        ..Default::default()
+                    },
+                    EntryAction {
+                        icon: "edit-copy".into(),
+                        name: "Copy name".into(),
+                        modifier: ModifierType::SHIFT_MASK,
+                        function: EntryAction::copy(titlecase(x.name)),
+                        ..Default::default()
+                    },
+                    favorites::action(FAVORITES, &x.scalar.to_string(), context),
+                ],
+                ..Default::default()
+            })
+        });
+
+        let entries = iter1.chain(iter2).take(MAX_RESULTS).collect_vec();
+
+        // Otherwise the search stopped before finding every match
+        if entries.len() < MAX_RESULTS {
+            cache.store(query, matched);
+        }
+
+        return entries;
+    }
+
+    query
+        .chars()
+        .map(|c| {
+            DATA.binary_search_by(|x| x.scalar.cmp(&c))
+                .map(|x| &DATA[x])
+                .map(|x| Entry {
+                    name: FormattedString::plain(titlecase(x.name)),
+                    tag: Some(FormattedString::plain(x.category.to_string())),
                     description: Some(FormattedString::plain(format!("{:04X}", x.codepoint))),
                     icon: EntryIcon::Text(x.representation().to_string()),
                     actions: vec![
                         EntryAction {
                             icon: "edit-copy".into(),
                             name: "Copy".into(),
-                            function: EntryAction::copy(x.scalar.to_string()),
+                            function: EntryAction::copy_bytes(x.name),
                             ..Default::default()
                         },
                         EntryAction {
@@ -124,55 +343,16 @@ impl Plugin for Unicode {
                             function: EntryAction::copy(format!("{:X}", x.codepoint)),
                             ..Default::default()
                         },
-                        EntryAction {
-                            icon: "edit-copy".into(),
-                            name: "Copy name".into(),
-                            modifier: ModifierType::SHIFT_MASK,
-                            function: EntryAction::copy(titlecase(x.name)),
-                            ..Default::default()
-                        },
+                        favorites::action(FAVORITES, &x.scalar.to_string(), context),
                     ],
                     ..Default::default()
                 })
-            });
-
-            return iter1.chain(iter2).take(128).collect();
-        }
-
-        query
-            .chars()
-            .map(|c| {
-                DATA.binary_search_by(|x| x.scalar.cmp(&c))
-                    .map(|x| &DATA[x])
-                    .map(|x| Entry {
-                        name: FormattedString::plain(titlecase(x.name)),
-                        tag: Some(FormattedString::plain(x.category.to_string())),
-                        description: Some(FormattedString::plain(format!("{:04X}", x.codepoint))),
-                        icon: EntryIcon::Text(x.representation().to_string()),
-                        actions: vec![
-                            EntryAction {
-                                icon: "edit-copy".into(),
-                                name: "Copy".into(),
-                                function: EntryAction::copy_bytes(x.name),
-                                ..Default::default()
-                            },
-                            EntryAction {
-                                icon: "edit-copy".into(),
-                                name: "Copy codepoint".into(),
-                                modifier: ModifierType::SHIFT_MASK,
-                                function: EntryAction::copy(format!("{:X}", x.codepoint)),
-                                ..Default::default()
-                            },
-                        ],
-                        ..Default::default()
-                    })
-                    .unwrap_or(Entry {
-                        name: FormattedString::plain("<unknown>"),
-                        description: Some(FormattedString::plain(format!("{:04X}", c as u32))),
-                        icon: EntryIcon::Text(c.to_string()),
-                        ..Default::default()
-                    })
-            })
-            .collect()
-    }
+                .unwrap_or(Entry {
+                    name: FormattedString::plain("<unknown>"),
+                    description: Some(FormattedString::plain(format!("{:04X}", c as u32))),
+                    icon: EntryIcon::Text(c.to_string()),
+                    ..Default::default()
+                })
+        })
+        .collect()
 }
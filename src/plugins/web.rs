@@ -1,10 +1,105 @@
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::mpsc::RecvTimeoutError;
+use std::time::Duration;
 
-use crate::interface::{Context, Entry, EntryAction, FormatStyle, FormattedString, Plugin};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tokio_util::sync::CancellationToken;
+use xdg::BaseDirectories;
+
+use crate::interface::{
+    ActionResult, ActionType, Context, Entry, EntryAction, EntryIcon, FormatStyle, FormattedString,
+    Plugin,
+};
+
+const MAX_SEARCHES: usize = 100;
+const MAX_SUGGESTIONS: usize = 500;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Cache {
+    /// Most recent first.
+    searches: Vec<String>,
+    /// The last suggestions received for each query, to show them offline.
+    suggestions: HashMap<String, Vec<String>>,
+    /// The queries of `suggestions`, oldest first.
+    #[serde(default)]
+    order: Vec<String>,
+}
+
+#[derive(Debug)]
+struct History {
+    path: Option<PathBuf>,
+    cache: Cache,
+}
+
+impl History {
+    fn load() -> Self {
+        let path = BaseDirectories::with_prefix("jogger")
+            .ok()
+            .and_then(|x| x.place_data_file("web.json").ok());
+
+        let cache = path
+            .as_ref()
+            .and_then(|x| std::fs::read_to_string(x).ok())
+            .and_then(|x| serde_json::from_str(&x).ok())
+            .unwrap_or_default();
+
+        Self { path, cache }
+    }
+
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+
+        match serde_json::to_string(&self.cache) {
+            Ok(content) => {
+                if let Err(error) = std::fs::write(path, content) {
+                    tracing::error!(?error, "Failed to save web searches");
+                }
+            }
+            Err(error) => tracing::error!(?error, "Failed to serialize web searches"),
+        }
+    }
+
+    fn record_search(&mut self, search: &str) {
+        self.cache.searches.retain(|x| x != search);
+        self.cache.searches.insert(0, search.to_owned());
+        self.cache.searches.truncate(MAX_SEARCHES);
+        self.save();
+    }
+
+    fn record_suggestions(&mut self, query: &str, suggestions: &[String]) {
+        if self.cache.suggestions.get(query).map(Vec::as_slice) == Some(suggestions) {
+            return;
+        }
+
+        self.cache.order.retain(|x| x != query);
+        self.cache.order.push(query.to_owned());
+        self.cache
+            .suggestions
+            .insert(query.to_owned(), suggestions.to_vec());
+
+        if self.cache.order.len() > MAX_SUGGESTIONS {
+            for query in self
+                .cache
+                .order
+                .drain(..self.cache.order.len() - MAX_SUGGESTIONS)
+            {
+                self.cache.suggestions.remove(&query);
+            }
+        }
+
+        self.save();
+    }
+}
 
 #[derive(Debug)]
 pub struct Web {
     handler: String,
+    history: Arc<Mutex<History>>,
 }
 
 impl Web {
@@ -16,6 +111,81 @@ impl Web {
                 .unwrap()
                 .id
                 .clone(),
+            history: Arc::new(Mutex::new(History::load())),
+        }
+    }
+
+    fn search_action(&self, search: String) -> Box<ActionType> {
+        let path = PathBuf::from(format!(
+            "https://www.startpage.com/sp/search?query={search}"
+        ));
+        let open = EntryAction::open(self.handler.clone(), None, Some(path));
+        let history = self.history.clone();
+
+        EntryAction::map_result(open, move |result| {
+            if !matches!(result, ActionResult::Error) {
+                history.lock().record_search(&search);
+            }
+            result
+        })
+    }
+
+    fn suggestion_to_entry(&self, text: String, len: usize, recent: bool) -> Entry {
+        let icon = if recent {
+            "document-open-recent"
+        } else {
+            "search"
+        };
+
+        Entry {
+            name: FormattedString {
+                ranges: vec![(FormatStyle::Highlight, 0..len.min(text.len()))],
+                text: text.clone(),
+            },
+            description: Some(
+                if recent {
+                    "Recent search"
+                } else {
+                    "Web search"
+                }
+                .into(),
+            ),
+            icon: EntryIcon::Name(icon.into()),
+            actions: vec![EntryAction {
+                icon: "search".into(),
+                name: "Search".into(),
+                function: self.search_action(text),
+                ..Default::default()
+            }],
+            // Searches made before go first
+            score: if recent { 1100 } else { 1000 },
+            ..Default::default()
+        }
+    }
+}
+
+/// Fetches the suggestions on its own thread, giving up as soon as the token is cancelled.
+fn fetch(query: &str, token: &CancellationToken) -> Option<Vec<String>> {
+    // The blocking request can't be interrupted, so it's left to finish
+    // on its own thread while this one keeps checking for cancellation
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let url = format!("https://www.startpage.com/osuggestions?q={query}");
+    std::thread::spawn(move || {
+        let results = reqwest::blocking::get(url)
+            .and_then(|x| x.json::<(String, Vec<String>)>())
+            .ok();
+        let _ = sender.send(results);
+    });
+
+    loop {
+        if token.is_cancelled() {
+            return None;
+        }
+
+        match receiver.recv_timeout(Duration::from_millis(20)) {
+            Ok(results) => return results.map(|x| x.1),
+            Err(RecvTimeoutError::Disconnected) => return None,
+            Err(RecvTimeoutError::Timeout) => {}
         }
     }
 }
@@ -29,41 +199,57 @@ impl Plugin for Web {
         Some("search")
     }
 
-    fn search(&self, query: &str, _: &Context) -> Vec<Entry> {
+    fn search_stream(
+        &self,
+        query: &str,
+        _: &Context,
+        token: &CancellationToken,
+        sink: &mut dyn FnMut(Vec<Entry>),
+    ) {
         let len = query.len();
+        let lowercase = query.to_lowercase();
 
-        let Ok(request) =
-            reqwest::blocking::get(format!("https://www.startpage.com/osuggestions?q={query}"))
-        else {
-            return vec![];
-        };
+        let recent = self
+            .history
+            .lock()
+            .cache
+            .searches
+            .iter()
+            .filter(|x| x.to_lowercase().starts_with(&lowercase))
+            .cloned()
+            .collect::<Vec<_>>();
 
-        let Ok(results) = request.json::<(String, Vec<String>)>() else {
-            return vec![];
+        // Shown right away, the suggestions take a request
+        sink(
+            recent
+                .iter()
+                .map(|x| self.suggestion_to_entry(x.clone(), len, true))
+                .collect(),
+        );
+
+        let suggestions = match fetch(query, token) {
+            Some(suggestions) => {
+                self.history.lock().record_suggestions(query, &suggestions);
+                suggestions
+            }
+            None if token.is_cancelled() => return,
+            // Probably offline
+            None => self
+                .history
+                .lock()
+                .cache
+                .suggestions
+                .get(query)
+                .cloned()
+                .unwrap_or_default(),
         };
 
-        results
-            .1
-            .into_iter()
-            .map(|x| {
-                let path = PathBuf::from(format!("https://www.startpage.com/sp/search?query={x}"));
-                Entry {
-                    name: FormattedString {
-                        text: x,
-                        ranges: vec![(FormatStyle::Highlight, 0..len)],
-                    },
-                    description: Some("Web search".into()),
-                    icon: Some("search".to_owned()).into(),
-                    actions: vec![EntryAction {
-                        icon: "search".into(),
-                        name: "Search".into(),
-                        function: EntryAction::open(self.handler.clone(), None, Some(path)),
-                        ..Default::default()
-                    }],
-                    score: 1000,
-                    ..Default::default()
-                }
-            })
-            .collect()
+        sink(
+            suggestions
+                .into_iter()
+                .filter(|x| !recent.contains(x))
+                .map(|x| self.suggestion_to_entry(x, len, false))
+                .collect(),
+        );
     }
 }
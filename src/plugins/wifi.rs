@@ -0,0 +1,159 @@
+use std::process::Command;
+
+use gtk::gdk::{Key, ModifierType};
+use tokio_util::sync::CancellationToken;
+
+use crate::interface::{
+    ActionResult, ActionType, Context, Entry, EntryAction, EntryIcon, FormattedString, Plugin,
+};
+use crate::utils::qr_code;
+
+#[derive(Debug)]
+struct Network {
+    ssid: String,
+    /// `None` for open networks.
+    password: Option<String>,
+    key_management: String,
+}
+
+impl Network {
+    /// The payload phones read to join the network.
+    fn payload(&self) -> String {
+        let security = match (self.key_management.as_str(), &self.password) {
+            (_, None) => "nopass",
+            ("none" | "ieee8021x", Some(_)) => "WEP",
+            _ => "WPA",
+        };
+
+        let mut payload = format!("WIFI:T:{security};S:{};", escape(&self.ssid));
+        if let Some(password) = &self.password {
+            payload.push_str(&format!("P:{};", escape(password)));
+        }
+        payload.push(';');
+        payload
+    }
+}
+
+/// Escapes the characters with a meaning in the `WIFI:` payload.
+fn escape(value: &str) -> String {
+    value
+        .chars()
+        .flat_map(|x| match x {
+            '\\' | ';' | ',' | ':' | '"' => vec!['\\', x],
+            x => vec![x],
+        })
+        .collect()
+}
+
+fn nmcli(args: &[&str]) -> Option<String> {
+    match Command::new("nmcli").args(args).output() {
+        Ok(x) if x.status.success() => Some(String::from_utf8_lossy(&x.stdout).into_owned()),
+        Ok(_) => None,
+        Err(error) => {
+            tracing::debug!(?error, "Failed to run nmcli");
+            None
+        }
+    }
+}
+
+/// The Wi-Fi connection NetworkManager is using, with its password.
+fn current_network() -> Option<Network> {
+    let active = nmcli(&["-t", "-f", "NAME,TYPE", "connection", "show", "--active"])?;
+    // Colons in the name are escaped, the type never has any
+    let name = active.lines().find_map(|x| {
+        x.strip_suffix(":802-11-wireless")
+            .map(|x| x.replace("\\:", ":"))
+    })?;
+
+    let details = nmcli(&[
+        "-s",
+        "-g",
+        "802-11-wireless.ssid,802-11-wireless-security.key-mgmt,802-11-wireless-security.psk",
+        "connection",
+        "show",
+        &name,
+    ])?;
+    let mut lines = details.lines();
+
+    Some(Network {
+        ssid: lines.next()?.to_owned(),
+        key_management: lines.next().unwrap_or_default().to_owned(),
+        password: lines.next().filter(|x| !x.is_empty()).map(str::to_owned),
+    })
+}
+
+/// Shows the network the computer is connected to as a QR code, for guests to join it.
+#[derive(Debug)]
+pub struct Wifi;
+
+impl Wifi {
+    pub fn new(_: &Context) -> Self {
+        Self
+    }
+}
+
+/// Asks NetworkManager for the password only once the action runs, never while typing.
+fn open_qr_code() -> Box<ActionType> {
+    Box::new(|context| {
+        let Some(path) = current_network().and_then(|x| qr_code(&x.payload(), "wifi")) else {
+            return ActionResult::Error;
+        };
+
+        EntryAction::command(
+            "xdg-open".into(),
+            vec![path.to_string_lossy().into_owned()],
+            None,
+        )(context)
+    })
+}
+
+fn copy_password() -> Box<ActionType> {
+    Box::new(|context| match current_network().and_then(|x| x.password) {
+        Some(password) => EntryAction::copy_secret(password)(context),
+        None => ActionResult::Error,
+    })
+}
+
+impl Plugin for Wifi {
+    fn name(&self) -> &str {
+        "Wi-Fi"
+    }
+
+    fn icon(&self) -> Option<&str> {
+        Some("network-wireless")
+    }
+
+    fn search(&self, query: &str, _: &Context, _: &CancellationToken) -> Vec<Entry> {
+        if query
+            .strip_prefix("wifi")
+            .is_none_or(|x| !x.is_empty() && !x.starts_with(char::is_whitespace))
+        {
+            return Vec::new();
+        }
+
+        vec![Entry {
+            name: FormattedString::plain("Share Wi-Fi network"),
+            tag: Some(FormattedString::plain("Wi-Fi")),
+            description: Some(FormattedString::plain(
+                "Scan the QR code to join the network",
+            )),
+            icon: EntryIcon::Name("network-wireless".into()),
+            actions: vec![
+                EntryAction {
+                    icon: "image-x-generic".into(),
+                    name: "Open QR code".into(),
+                    function: open_qr_code(),
+                    ..Default::default()
+                },
+                EntryAction {
+                    icon: "edit-copy".into(),
+                    name: "Copy password".into(),
+                    function: copy_password(),
+                    key: Key::c,
+                    modifier: ModifierType::CONTROL_MASK,
+                },
+            ],
+            ..Default::default()
+        }]
+    }
+}
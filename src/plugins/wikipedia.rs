@@ -0,0 +1,228 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::mpsc::RecvTimeoutError;
+use std::time::{Duration, Instant};
+
+use gtk::gdk::{Key, ModifierType};
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use tokio_util::sync::CancellationToken;
+
+use crate::interface::{
+    Context, Entry, EntryAction, EntryIcon, FormattedString, Plugin, parse_options,
+};
+
+const MAX_RESULTS: usize = 10;
+/// Requests are only sent once typing pauses for this long.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+struct WikipediaConfig {
+    /// The language edition, like `en` or `de`.
+    language: String,
+}
+
+impl Default for WikipediaConfig {
+    fn default() -> Self {
+        Self {
+            language: "en".into(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Details {
+    query: Pages,
+}
+
+#[derive(Debug, Deserialize)]
+struct Pages {
+    pages: Vec<Page>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Page {
+    title: String,
+    description: Option<String>,
+    extract: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct Wikipedia {
+    language: String,
+    handler: Option<String>,
+}
+
+impl Wikipedia {
+    pub fn new(context: &Context, options: &toml::Table) -> Self {
+        let config: WikipediaConfig = parse_options(options);
+
+        Self {
+            language: config.language,
+            handler: context
+                .apps
+                .default_for_mime(&"x-scheme-handler/https".parse().unwrap())
+                .map(|x| x.id.clone()),
+        }
+    }
+
+    fn api(&self) -> String {
+        format!("https://{}.wikipedia.org/w/api.php", self.language)
+    }
+}
+
+/// Runs the blocking request on its own thread, giving up as soon as the token is cancelled.
+fn get<T: DeserializeOwned + Send + 'static>(
+    url: String,
+    query: Vec<(&'static str, String)>,
+    token: &CancellationToken,
+) -> Option<T> {
+    let url = reqwest::Url::parse_with_params(&url, query).ok()?;
+
+    let (sender, receiver) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let result = reqwest::blocking::Client::new()
+            .get(url)
+            .header("User-Agent", "jogger")
+            .send()
+            .and_then(|x| x.json::<T>());
+
+        if let Err(error) = &result {
+            tracing::warn!(%error, "Wikipedia request failed");
+        }
+        let _ = sender.send(result.ok());
+    });
+
+    loop {
+        if token.is_cancelled() {
+            return None;
+        }
+
+        match receiver.recv_timeout(Duration::from_millis(20)) {
+            Ok(result) => return result,
+            Err(RecvTimeoutError::Disconnected) => return None,
+            Err(RecvTimeoutError::Timeout) => {}
+        }
+    }
+}
+
+impl Plugin for Wikipedia {
+    fn name(&self) -> &str {
+        "Wikipedia"
+    }
+
+    fn icon(&self) -> Option<&str> {
+        Some("accessories-dictionary")
+    }
+
+    fn search(&self, query: &str, _: &Context, token: &CancellationToken) -> Vec<Entry> {
+        let query = query.strip_prefix("wiki ").unwrap_or(query).trim();
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        // Every keystroke cancels the previous search, so only the last one gets through
+        let start = Instant::now();
+        while start.elapsed() < DEBOUNCE {
+            if token.is_cancelled() {
+                return Vec::new();
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+
+        let Some((_, titles, _, links)) = get::<(String, Vec<String>, Vec<String>, Vec<String>)>(
+            self.api(),
+            vec![
+                ("action", "opensearch".into()),
+                ("format", "json".into()),
+                ("limit", MAX_RESULTS.to_string()),
+                ("search", query.into()),
+            ],
+            token,
+        ) else {
+            return Vec::new();
+        };
+
+        if titles.is_empty() {
+            return Vec::new();
+        }
+
+        // OpenSearch no longer fills in descriptions, they're fetched for all titles at once
+        let details = get::<Details>(
+            self.api(),
+            vec![
+                ("action", "query".into()),
+                ("format", "json".into()),
+                ("formatversion", "2".into()),
+                ("prop", "description|extracts".into()),
+                ("exintro", "1".into()),
+                ("explaintext", "1".into()),
+                ("exlimit", "max".into()),
+                ("titles", titles.join("|")),
+            ],
+            token,
+        )
+        .map(|x| {
+            x.query
+                .pages
+                .into_iter()
+                .map(|x| (x.title.clone(), x))
+                .collect::<HashMap<_, _>>()
+        })
+        .unwrap_or_default();
+
+        titles
+            .into_iter()
+            .zip(links)
+            .map(|(title, link)| {
+                let page = details.get(&title);
+                let summary = page
+                    .and_then(|x| x.extract.as_deref())
+                    .map(str::trim)
+                    .filter(|x| !x.is_empty());
+                let snippet = page
+                    .and_then(|x| x.description.clone())
+                    .or_else(|| summary.map(|x| x.lines().next().unwrap_or_default().to_owned()));
+
+                let mut actions = vec![EntryAction {
+                    icon: "web-browser".into(),
+                    name: "Open".into(),
+                    function: match &self.handler {
+                        Some(handler) => {
+                            EntryAction::open(handler.clone(), None, Some(PathBuf::from(&link)))
+                        }
+                        None => EntryAction::command("xdg-open".into(), vec![link.clone()], None),
+                    },
+                    ..Default::default()
+                }];
+
+                if let Some(summary) = summary {
+                    actions.push(EntryAction {
+                        icon: "edit-copy".into(),
+                        name: "Copy summary".into(),
+                        function: EntryAction::copy(summary),
+                        key: Key::c,
+                        modifier: ModifierType::CONTROL_MASK,
+                    });
+                }
+
+                actions.push(EntryAction {
+                    icon: "edit-copy".into(),
+                    name: "Copy link".into(),
+                    function: EntryAction::copy(link),
+                    key: Key::c,
+                    modifier: ModifierType::CONTROL_MASK | ModifierType::SHIFT_MASK,
+                });
+
+                Entry {
+                    name: FormattedString::plain(title),
+                    description: snippet.map(FormattedString::plain),
+                    icon: EntryIcon::Name("accessories-dictionary".into()),
+                    actions,
+                    ..Default::default()
+                }
+            })
+            .collect()
+    }
+}
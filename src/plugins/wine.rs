@@ -0,0 +1,280 @@
+use std::path::{Path, PathBuf};
+
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use serde::Deserialize;
+use tokio_util::sync::CancellationToken;
+
+use crate::interface::{
+    Context, Entry, EntryAction, EntryIcon, FormattedString, Plugin, parse_options,
+};
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+struct WineConfig {
+    /// Wine prefixes, directories in these are also checked for one.
+    prefixes: Vec<String>,
+    /// The Steam installation, whose Proton prefixes are in `steamapps/compatdata`.
+    steam: String,
+    /// The `proton` script, defaults to the newest version installed by Steam.
+    proton: Option<String>,
+}
+
+impl Default for WineConfig {
+    fn default() -> Self {
+        Self {
+            prefixes: vec!["~/.wine".into(), "~/.local/share/wineprefixes".into()],
+            steam: "~/.local/share/Steam".into(),
+            proton: None,
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Runner {
+    Wine,
+    /// The compatdata directory of the game, the one containing `pfx`.
+    Proton(PathBuf),
+}
+
+#[derive(Debug)]
+struct Shortcut {
+    name: String,
+    /// Where it is in the start menu.
+    location: String,
+    path: PathBuf,
+    prefix: PathBuf,
+    label: String,
+    runner: Runner,
+}
+
+fn expand(path: &str) -> Option<PathBuf> {
+    expanduser::expanduser(path).ok()
+}
+
+fn is_prefix(path: &Path) -> bool {
+    path.join("drive_c").is_dir()
+}
+
+/// Collects the `.lnk` files under a directory.
+fn find_links(directory: &Path, links: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(directory) else {
+        return;
+    };
+
+    for path in entries.filter_map(|x| Some(x.ok()?.path())) {
+        if path.is_dir() {
+            find_links(&path, links);
+        } else if path
+            .extension()
+            .is_some_and(|x| x.eq_ignore_ascii_case("lnk"))
+        {
+            links.push(path);
+        }
+    }
+}
+
+fn start_menus(prefix: &Path) -> Vec<PathBuf> {
+    let drive = prefix.join("drive_c");
+    let mut menus = vec![drive.join("ProgramData/Microsoft/Windows/Start Menu/Programs")];
+
+    if let Ok(users) = std::fs::read_dir(drive.join("users")) {
+        menus.extend(users.filter_map(|x| {
+            Some(
+                x.ok()?
+                    .path()
+                    .join("AppData/Roaming/Microsoft/Windows/Start Menu/Programs"),
+            )
+        }));
+    }
+
+    menus
+}
+
+fn scan(prefix: &Path, label: &str, runner: impl Fn() -> Runner) -> Vec<Shortcut> {
+    let mut shortcuts = Vec::new();
+
+    for menu in start_menus(prefix) {
+        let mut links = Vec::new();
+        find_links(&menu, &mut links);
+
+        for path in links {
+            let Some(name) = path.file_stem().map(|x| x.to_string_lossy().into_owned()) else {
+                continue;
+            };
+
+            // Nobody launches these on purpose
+            if name.to_lowercase().contains("uninstall") {
+                continue;
+            }
+
+            let location = path
+                .parent()
+                .and_then(|x| x.strip_prefix(&menu).ok())
+                .map(|x| x.to_string_lossy().into_owned())
+                .unwrap_or_default();
+
+            shortcuts.push(Shortcut {
+                name,
+                location,
+                path,
+                prefix: prefix.to_owned(),
+                label: label.to_owned(),
+                runner: runner(),
+            });
+        }
+    }
+
+    shortcuts
+}
+
+/// The newest Proton installed by Steam, as their names sort by version.
+fn find_proton(steam: &Path) -> Option<PathBuf> {
+    let mut versions = std::fs::read_dir(steam.join("steamapps/common"))
+        .ok()?
+        .filter_map(|x| Some(x.ok()?.path()))
+        .filter(|x| {
+            x.file_name()
+                .is_some_and(|x| x.to_string_lossy().starts_with("Proton"))
+                && x.join("proton").is_file()
+        })
+        .collect::<Vec<_>>();
+    versions.sort();
+
+    Some(versions.pop()?.join("proton"))
+}
+
+/// Windows applications installed in Wine prefixes and the Proton prefixes of Steam.
+#[derive(Debug)]
+pub struct Wine {
+    shortcuts: Vec<Shortcut>,
+    steam: PathBuf,
+    proton: Option<PathBuf>,
+}
+
+impl Wine {
+    pub fn new(_: &Context, options: &toml::Table) -> Self {
+        let config: WineConfig = parse_options(options);
+        let mut shortcuts = Vec::new();
+
+        for path in config.prefixes.iter().filter_map(|x| expand(x)) {
+            let prefixes = if is_prefix(&path) {
+                vec![path]
+            } else {
+                std::fs::read_dir(&path)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|x| Some(x.ok()?.path()))
+                    .filter(|x| is_prefix(x))
+                    .collect()
+            };
+
+            for prefix in prefixes {
+                let label = prefix
+                    .file_name()
+                    .map(|x| x.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                shortcuts.extend(scan(&prefix, &label, || Runner::Wine));
+            }
+        }
+
+        let steam = expand(&config.steam).unwrap_or_default();
+        if let Ok(games) = std::fs::read_dir(steam.join("steamapps/compatdata")) {
+            for game in games.filter_map(|x| Some(x.ok()?.path())) {
+                let prefix = game.join("pfx");
+                if !is_prefix(&prefix) {
+                    continue;
+                }
+
+                let label = format!(
+                    "Proton {}",
+                    game.file_name().unwrap_or_default().to_string_lossy()
+                );
+                shortcuts.extend(scan(&prefix, &label, || Runner::Proton(game.clone())));
+            }
+        }
+
+        let proton = config
+            .proton
+            .and_then(|x| expand(&x))
+            .or_else(|| find_proton(&steam));
+
+        Self {
+            shortcuts,
+            steam,
+            proton,
+        }
+    }
+
+    fn launch(&self, shortcut: &Shortcut) -> Option<EntryAction> {
+        let path = shortcut.path.to_string_lossy().into_owned();
+
+        // `start /unix` is what makes Wine follow the shortcut
+        let args = match &shortcut.runner {
+            Runner::Wine => vec![
+                format!("WINEPREFIX={}", shortcut.prefix.display()),
+                "wine".into(),
+                "start".into(),
+                "/unix".into(),
+                path,
+            ],
+            Runner::Proton(compatdata) => vec![
+                format!("STEAM_COMPAT_DATA_PATH={}", compatdata.display()),
+                format!("STEAM_COMPAT_CLIENT_INSTALL_PATH={}", self.steam.display()),
+                self.proton.as_ref()?.to_string_lossy().into_owned(),
+                "run".into(),
+                "start".into(),
+                "/unix".into(),
+                path,
+            ],
+        };
+
+        Some(EntryAction {
+            icon: "wine".into(),
+            name: "Launch".into(),
+            function: EntryAction::command(
+                "env".into(),
+                args,
+                shortcut.path.parent().map(Path::to_path_buf),
+            ),
+            ..Default::default()
+        })
+    }
+}
+
+impl Plugin for Wine {
+    fn name(&self) -> &str {
+        "Wine"
+    }
+
+    fn icon(&self) -> Option<&str> {
+        Some("wine")
+    }
+
+    fn search(&self, query: &str, _: &Context, _: &CancellationToken) -> Vec<Entry> {
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let matcher = SkimMatcherV2::default();
+
+        self.shortcuts
+            .iter()
+            .filter_map(|shortcut| {
+                let (score, indices) = matcher.fuzzy_indices(&shortcut.name, query)?;
+
+                Some(Entry {
+                    name: FormattedString::from_indices(&shortcut.name, indices),
+                    tag: Some(FormattedString::plain(&shortcut.label)),
+                    description: Some(FormattedString::plain(&shortcut.location))
+                        .filter(|x| !x.text.is_empty()),
+                    icon: EntryIcon::Name("wine".into()),
+                    actions: self.launch(shortcut).into_iter().collect(),
+                    id: format!("wine:{}", shortcut.path.display()),
+                    score: score.try_into().ok()?,
+                    ..Default::default()
+                })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,204 @@
+use std::process::Command;
+
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use gtk::gdk::{Key, ModifierType};
+use serde::Deserialize;
+use tokio_util::sync::CancellationToken;
+
+use crate::interface::{
+    ActionResult, ActionType, Context, Entry, EntryAction, EntryIcon, FormattedString, Plugin,
+    parse_options,
+};
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+struct ZellijConfig {
+    /// The terminal profile sessions are opened with.
+    profile: String,
+}
+
+impl Default for ZellijConfig {
+    fn default() -> Self {
+        Self {
+            profile: "zellij".into(),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Session {
+    name: String,
+    /// Like "Created 2h 5m ago".
+    created: Option<String>,
+    /// Kept to be resurrected, but not running.
+    exited: bool,
+}
+
+fn list_sessions() -> Vec<Session> {
+    let output = match Command::new("zellij")
+        .args(["list-sessions", "--no-formatting"])
+        .output()
+    {
+        Ok(x) => x.stdout,
+        Err(error) => {
+            tracing::debug!(?error, "Failed to run zellij");
+            return Vec::new();
+        }
+    };
+
+    // One per line, like `work [Created 2h 5m ago] (EXITED - attach to resurrect)`
+    String::from_utf8_lossy(&output)
+        .lines()
+        .filter_map(|line| {
+            let (name, rest) = line.split_once(' ').unwrap_or((line, ""));
+            if name.is_empty() {
+                return None;
+            }
+
+            Some(Session {
+                name: name.to_owned(),
+                created: rest
+                    .split_once('[')
+                    .and_then(|(_, x)| x.split_once(']'))
+                    .map(|(x, _)| x.to_owned()),
+                exited: rest.contains("EXITED"),
+            })
+        })
+        .collect()
+}
+
+/// Runs the zellij command and refreshes the sessions.
+fn manage(args: Vec<String>) -> Box<ActionType> {
+    Box::new(move |_| match Command::new("zellij").args(&args).status() {
+        Ok(x) if x.success() => ActionResult::Refresh,
+        status => {
+            tracing::warn!(?status, ?args, "Failed to manage the zellij session");
+            ActionResult::Error
+        }
+    })
+}
+
+/// The sessions of the zellij multiplexer, attached to in a terminal.
+#[derive(Debug)]
+pub struct Zellij {
+    profile: String,
+}
+
+impl Zellij {
+    pub fn new(_: &Context, options: &toml::Table) -> Self {
+        let config: ZellijConfig = parse_options(options);
+
+        Self {
+            profile: config.profile,
+        }
+    }
+
+    fn attach(&self, name: &str, create: bool) -> Box<ActionType> {
+        let mut args = vec!["attach".to_owned()];
+        if create {
+            args.push("--create".into());
+        }
+        args.push(name.to_owned());
+
+        EntryAction::launch_terminal_with_profile(
+            Some(self.profile.clone()),
+            Some("zellij".into()),
+            args,
+            None,
+        )
+    }
+
+    fn session_to_entry(&self, session: &Session, name: FormattedString, score: u64) -> Entry {
+        let (remove, command) = if session.exited {
+            ("Delete session", "delete-session")
+        } else {
+            ("Kill session", "kill-session")
+        };
+
+        Entry {
+            name,
+            tag: session.exited.then(|| FormattedString::plain("Exited")),
+            description: session.created.as_deref().map(FormattedString::plain),
+            icon: EntryIcon::Name("utilities-terminal".into()),
+            actions: vec![
+                EntryAction {
+                    icon: "terminal".into(),
+                    name: if session.exited {
+                        "Resurrect".into()
+                    } else {
+                        "Attach".into()
+                    },
+                    function: self.attach(&session.name, false),
+                    ..Default::default()
+                },
+                EntryAction {
+                    icon: "window-close".into(),
+                    name: remove.into(),
+                    function: EntryAction::confirm(
+                        format!("{remove} {}?", session.name),
+                        manage(vec![command.into(), session.name.clone()]),
+                    ),
+                    key: Key::Delete,
+                    modifier: ModifierType::CONTROL_MASK,
+                },
+            ],
+            id: format!("zellij:{}", session.name),
+            score,
+            ..Default::default()
+        }
+    }
+}
+
+impl Plugin for Zellij {
+    fn name(&self) -> &str {
+        "Zellij"
+    }
+
+    fn icon(&self) -> Option<&str> {
+        Some("utilities-terminal")
+    }
+
+    fn search(&self, query: &str, _: &Context, _: &CancellationToken) -> Vec<Entry> {
+        let query = query
+            .strip_prefix("zj")
+            .filter(|x| x.is_empty() || x.starts_with(char::is_whitespace))
+            .unwrap_or(query)
+            .trim();
+
+        let sessions = list_sessions();
+        let matcher = SkimMatcherV2::default().smart_case();
+        let mut entries = sessions
+            .iter()
+            .filter_map(|x| {
+                if query.is_empty() {
+                    return Some(self.session_to_entry(x, FormattedString::plain(&x.name), 0));
+                }
+
+                let (score, indices) = matcher.fuzzy_indices(&x.name, query)?;
+                let name = FormattedString::from_indices(&x.name, indices);
+                Some(self.session_to_entry(x, name, score.try_into().ok()?))
+            })
+            .collect::<Vec<_>>();
+
+        // Session names can't have spaces
+        if !query.is_empty()
+            && !query.contains(char::is_whitespace)
+            && !sessions.iter().any(|x| x.name == query)
+        {
+            entries.push(Entry {
+                name: FormattedString::plain(format!("New session \"{query}\"")),
+                icon: EntryIcon::Name("list-add".into()),
+                actions: vec![EntryAction {
+                    icon: "terminal".into(),
+                    name: "Start".into(),
+                    function: self.attach(query, true),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            });
+        }
+
+        entries
+    }
+}
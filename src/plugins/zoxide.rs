@@ -0,0 +1,146 @@
+use std::path::PathBuf;
+use std::process::Command;
+
+use gtk::gdk::{Key, ModifierType};
+use tokio_util::sync::CancellationToken;
+
+use crate::interface::{
+    ActionResult, ActionType, Context, Entry, EntryAction, EntryIcon, FormattedString, Plugin,
+};
+
+const MAX_RESULTS: usize = 50;
+
+/// Ranked by zoxide itself, which matches the keywords in order against the path.
+fn query(keywords: &str) -> Vec<PathBuf> {
+    let output = Command::new("zoxide")
+        .args(["query", "--list", "--"])
+        .args(keywords.split_whitespace())
+        .output();
+
+    match output {
+        // Fails when nothing matches
+        Ok(x) => String::from_utf8_lossy(&x.stdout)
+            .lines()
+            .take(MAX_RESULTS)
+            .map(PathBuf::from)
+            .collect(),
+        Err(error) => {
+            tracing::debug!(?error, "Failed to run zoxide");
+            Vec::new()
+        }
+    }
+}
+
+/// Bumps the directory in zoxide once the action ran, like `z` would.
+fn visit(path: PathBuf, function: Box<ActionType>) -> Box<ActionType> {
+    EntryAction::map_result(function, move |result| {
+        if !matches!(result, ActionResult::Error) {
+            let _ = Command::new("zoxide").arg("add").arg(&path).status();
+        }
+        result
+    })
+}
+
+/// The directories visited most, from the zoxide database.
+#[derive(Debug)]
+pub struct Zoxide {
+    home_dir: String,
+}
+
+impl Zoxide {
+    pub fn new(_: &Context) -> Self {
+        Self {
+            home_dir: std::env::var("HOME").unwrap_or_default(),
+        }
+    }
+
+    fn path_to_entry(&self, path: PathBuf, score: u64, context: &Context) -> Entry {
+        let display = path.to_string_lossy().into_owned();
+        let display = match display.strip_prefix(&self.home_dir) {
+            Some(x) if !self.home_dir.is_empty() => format!("~{x}"),
+            _ => display,
+        };
+        let name = path
+            .file_name()
+            .map(|x| x.to_string_lossy().into_owned())
+            .unwrap_or_else(|| display.clone());
+
+        let mut actions = Vec::new();
+        if let Some(browser) = context.apps.file_browser() {
+            actions.push(EntryAction {
+                icon: browser.icon().into(),
+                name: "Open in file manager".into(),
+                function: visit(
+                    path.clone(),
+                    EntryAction::open(browser.id.clone(), None, Some(path.clone())),
+                ),
+                ..Default::default()
+            });
+        }
+
+        actions.push(EntryAction {
+            icon: "terminal".into(),
+            name: "Open in terminal".into(),
+            function: visit(
+                path.clone(),
+                EntryAction::launch_terminal(None, vec![], Some(path.clone())),
+            ),
+            key: Key::t,
+            modifier: ModifierType::CONTROL_MASK,
+        });
+
+        actions.push(EntryAction {
+            icon: "folder_open".into(),
+            name: "Navigate".into(),
+            function: EntryAction::write(format!("{display}/")),
+            key: Key::Escape,
+            modifier: ModifierType::NO_MODIFIER_MASK,
+        });
+
+        actions.push(EntryAction {
+            icon: "edit-copy".into(),
+            name: "Copy path".into(),
+            function: EntryAction::copy(path.to_string_lossy()),
+            key: Key::c,
+            modifier: ModifierType::CONTROL_MASK,
+        });
+
+        Entry {
+            name: FormattedString::plain(name),
+            description: Some(FormattedString::plain(display)),
+            icon: EntryIcon::Name("folder".into()),
+            actions,
+            id: path.to_string_lossy().into_owned(),
+            drag_file: Some(path),
+            score,
+            ..Default::default()
+        }
+    }
+}
+
+impl Plugin for Zoxide {
+    fn name(&self) -> &str {
+        "Zoxide"
+    }
+
+    fn icon(&self) -> Option<&str> {
+        Some("folder-recent")
+    }
+
+    fn search(&self, query: &str, context: &Context, _: &CancellationToken) -> Vec<Entry> {
+        let keywords = query
+            .strip_prefix('z')
+            .filter(|x| x.is_empty() || x.starts_with(char::is_whitespace))
+            .unwrap_or(query);
+
+        let paths = self::query(keywords);
+        let count = paths.len() as u64;
+
+        // Keeps the order of zoxide
+        paths
+            .into_iter()
+            .enumerate()
+            .map(|(i, x)| self.path_to_entry(x, count - i as u64, context))
+            .collect()
+    }
+}
@@ -0,0 +1,129 @@
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+/// One per line on the socket, like `{"command": "query", "query": "firefox"}`.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "command", rename_all = "lowercase")]
+pub enum Request {
+    Show,
+    Toggle,
+    Hide,
+    /// Shows the launcher searching the query.
+    Query {
+        query: String,
+    },
+    /// Shows the launcher and answers with the entry the user picks, instead of running it.
+    Pick {
+        query: Option<String>,
+    },
+}
+
+#[derive(Debug, Serialize)]
+pub struct Picked {
+    pub name: String,
+    pub id: String,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct Response {
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub picked: Option<Picked>,
+}
+
+impl Response {
+    pub fn ok() -> Self {
+        Self {
+            ok: true,
+            ..Default::default()
+        }
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            error: Some(message.into()),
+            ..Default::default()
+        }
+    }
+
+    pub fn picked(picked: Picked) -> Self {
+        Self {
+            ok: true,
+            picked: Some(picked),
+            ..Default::default()
+        }
+    }
+}
+
+pub fn socket_path() -> Option<PathBuf> {
+    std::env::var_os("XDG_RUNTIME_DIR").map(|x| PathBuf::from(x).join("jogger.sock"))
+}
+
+fn serve(stream: UnixStream, handler: &dyn Fn(Request) -> Response) -> std::io::Result<()> {
+    let mut writer = stream.try_clone()?;
+
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match serde_json::from_str(&line) {
+            Ok(request) => handler(request),
+            Err(error) => Response::error(error.to_string()),
+        };
+
+        let mut response = serde_json::to_string(&response).unwrap_or_default();
+        response.push('\n');
+        writer.write_all(response.as_bytes())?;
+    }
+
+    Ok(())
+}
+
+/// Answers the requests of each client on its own thread, a pick waits for the user.
+pub fn listen(handler: impl Fn(Request) -> Response + Send + Sync + 'static) {
+    let Some(path) = socket_path() else {
+        tracing::warn!("XDG_RUNTIME_DIR isn't set, not listening for remote commands");
+        return;
+    };
+
+    // Left behind by a previous instance that didn't exit cleanly
+    if UnixStream::connect(&path).is_err() {
+        let _ = std::fs::remove_file(&path);
+    }
+
+    let listener = match UnixListener::bind(&path) {
+        Ok(x) => x,
+        Err(error) => {
+            tracing::warn!(?error, ?path, "Failed to listen for remote commands");
+            return;
+        }
+    };
+
+    let handler = Arc::new(handler);
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let stream = match stream {
+                Ok(x) => x,
+                Err(error) => {
+                    tracing::debug!(?error, "Failed to accept a remote connection");
+                    continue;
+                }
+            };
+
+            let handler = handler.clone();
+            std::thread::spawn(move || {
+                if let Err(error) = serve(stream, &*handler) {
+                    tracing::debug!(?error, "Remote connection closed");
+                }
+            });
+        }
+    });
+}
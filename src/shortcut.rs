@@ -0,0 +1,37 @@
+use hyprland::event_listener::EventListener;
+use hyprland::keyword::Keyword;
+
+/// Binds the shortcut to toggle the launcher through the Hyprland socket, so it
+/// needn't be added to `hyprland.conf`. Reloading the config drops it, so it's bound again then.
+pub fn register(shortcut: &str) {
+    // Not running on Hyprland
+    if std::env::var_os("HYPRLAND_INSTANCE_SIGNATURE").is_none() {
+        return;
+    }
+
+    let exe = match std::env::current_exe() {
+        Ok(x) => x,
+        Err(error) => {
+            tracing::warn!(?error, "Failed to find the launcher executable");
+            return;
+        }
+    };
+
+    let bind = format!("{shortcut}, exec, {} --toggle", exe.display());
+    let set = move || {
+        if let Err(error) = Keyword::set("bind", bind.clone()) {
+            tracing::warn!(?error, "Failed to bind the Hyprland shortcut");
+        }
+    };
+
+    set();
+
+    std::thread::spawn(move || {
+        let mut listener = EventListener::new();
+        listener.add_config_reloaded_handler(set);
+
+        if let Err(error) = listener.start_listener() {
+            tracing::warn!(?error, "Failed to listen to Hyprland events");
+        }
+    });
+}
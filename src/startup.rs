@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use xdg::BaseDirectories;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PluginStats {
+    /// In milliseconds, leaning towards the latest loads.
+    load_time: f64,
+    uses: u32,
+}
+
+impl PluginStats {
+    /// How long the plugin keeps the others waiting for each time it's used.
+    fn cost(&self) -> f64 {
+        self.load_time / (1.0 + self.uses as f64)
+    }
+}
+
+/// Remembers how long each plugin takes to load and how often its entries are used,
+/// so the quick and useful ones are searchable first.
+#[derive(Debug, Default)]
+pub struct StartupProfile {
+    path: Option<PathBuf>,
+    plugins: HashMap<String, PluginStats>,
+}
+
+impl StartupProfile {
+    pub fn load() -> Self {
+        let path = BaseDirectories::with_prefix("jogger")
+            .ok()
+            .and_then(|x| x.place_data_file("startup.json").ok());
+
+        let plugins = path
+            .as_ref()
+            .and_then(|x| std::fs::read_to_string(x).ok())
+            .and_then(|x| serde_json::from_str(&x).ok())
+            .unwrap_or_default();
+
+        Self { path, plugins }
+    }
+
+    pub fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+
+        match serde_json::to_string(&self.plugins) {
+            Ok(content) => {
+                if let Err(error) = std::fs::write(path, content) {
+                    tracing::error!(?error, "Failed to save startup data");
+                }
+            }
+            Err(error) => tracing::error!(?error, "Failed to serialize startup data"),
+        }
+    }
+
+    /// Kept in memory, saved once every plugin is loaded.
+    pub fn record_load(&mut self, key: &str, time: Duration) {
+        let stats = self.plugins.entry(key.to_owned()).or_default();
+        let time = time.as_secs_f64() * 1000.0;
+
+        stats.load_time = if stats.load_time == 0.0 {
+            time
+        } else {
+            (stats.load_time + time) / 2.0
+        };
+    }
+
+    pub fn record_use(&mut self, key: &str) {
+        let stats = self.plugins.entry(key.to_owned()).or_default();
+        stats.uses = stats.uses.saturating_add(1);
+
+        self.save();
+    }
+
+    /// The indices of the keys in the order to load them, those never seen go first to be measured.
+    pub fn order(&self, keys: &[String]) -> Vec<usize> {
+        let cost = |i: usize| self.plugins.get(&keys[i]).map_or(0.0, PluginStats::cost);
+
+        let mut order = (0..keys.len()).collect::<Vec<_>>();
+        order.sort_by(|&a, &b| cost(a).total_cmp(&cost(b)));
+        order
+    }
+}
@@ -0,0 +1,131 @@
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use xdg::BaseDirectories;
+
+/// The config, with the custom entries of the plugins, and what's edited by hand next to it.
+const CONFIG_FILES: [&str; 6] = [
+    "config.toml",
+    "ignored.conf",
+    "frequency.toml",
+    "style.scss",
+    "snippets.toml",
+    "snippets.yaml",
+];
+
+/// What's remembered while using the launcher, caches are left out.
+const DATA_FILES: [&str; 8] = [
+    "frecency.json",
+    "favorites.json",
+    "notes.json",
+    "habits.json",
+    "mail.json",
+    "web.json",
+    "feeds.json",
+    "scratchpad.txt",
+];
+
+/// The files of the user's setup by name, only those that exist.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct State {
+    #[serde(default)]
+    config: BTreeMap<String, String>,
+    #[serde(default)]
+    data: BTreeMap<String, String>,
+}
+
+/// Writes the state to the file, or to stdout when none is given.
+pub fn export(path: Option<&str>) -> bool {
+    let Ok(base_dirs) = BaseDirectories::with_prefix("jogger") else {
+        tracing::error!("Failed to find the config directories");
+        return false;
+    };
+
+    let read = |files: &[&str], find: &dyn Fn(&str) -> Option<PathBuf>| {
+        files
+            .iter()
+            .filter_map(|&name| {
+                let content = std::fs::read_to_string(find(name)?).ok()?;
+                Some((name.to_owned(), content))
+            })
+            .collect()
+    };
+
+    let state = State {
+        config: read(&CONFIG_FILES, &|x| base_dirs.find_config_file(x)),
+        data: read(&DATA_FILES, &|x| base_dirs.find_data_file(x)),
+    };
+
+    let content = match serde_json::to_string_pretty(&state) {
+        Ok(x) => x,
+        Err(error) => {
+            tracing::error!(?error, "Failed to serialize the state");
+            return false;
+        }
+    };
+
+    let result = match path {
+        Some(path) => std::fs::write(path, content),
+        None => std::io::stdout().write_all(content.as_bytes()),
+    };
+
+    if let Err(error) = result {
+        tracing::error!(?error, ?path, "Failed to export the state");
+        return false;
+    }
+
+    true
+}
+
+/// Replaces the files of the state with those in the file, or read from stdin when none is given.
+/// Those not in it are kept.
+pub fn import(path: Option<&str>) -> bool {
+    let mut content = String::new();
+    let result = match path {
+        Some(path) => std::fs::read_to_string(path).map(|x| content = x),
+        None => std::io::stdin().read_to_string(&mut content).map(|_| ()),
+    };
+
+    if let Err(error) = result {
+        tracing::error!(?error, ?path, "Failed to read the state");
+        return false;
+    }
+
+    let state: State = match serde_json::from_str(&content) {
+        Ok(x) => x,
+        Err(error) => {
+            tracing::error!(%error, "Invalid state file");
+            return false;
+        }
+    };
+
+    let Ok(base_dirs) = BaseDirectories::with_prefix("jogger") else {
+        tracing::error!("Failed to find the config directories");
+        return false;
+    };
+
+    let files = state
+        .config
+        .iter()
+        .filter(|(name, _)| CONFIG_FILES.contains(&name.as_str()))
+        .map(|(name, content)| (base_dirs.place_config_file(name), content))
+        .chain(
+            state
+                .data
+                .iter()
+                .filter(|(name, _)| DATA_FILES.contains(&name.as_str()))
+                .map(|(name, content)| (base_dirs.place_data_file(name), content)),
+        );
+
+    let mut success = true;
+    for (path, content) in files {
+        if let Err(error) = path.and_then(|path| std::fs::write(path, content)) {
+            tracing::error!(?error, "Failed to import a file of the state");
+            success = false;
+        }
+    }
+
+    success
+}
@@ -1,9 +1,17 @@
 use std::{
-    io,
+    any::Any,
+    collections::HashSet,
+    hash::Hash,
+    io::{self, Read, Write},
     ops::Range,
+    os::unix::fs::OpenOptionsExt,
+    path::PathBuf,
     process::{Command, Stdio},
 };
 
+use parking_lot::Mutex;
+use wl_clipboard_rs::paste::{ClipboardType, MimeType, Seat, get_contents};
+
 pub struct Ranges<I: Iterator<Item = usize>> {
     v: I,
     current: Option<(usize, usize)>,
@@ -90,3 +98,132 @@ impl CommandExt for Command {
         self.spawn().map(|_| ())
     }
 }
+
+/// The text content of the clipboard, if it has any.
+pub fn read_clipboard() -> Option<String> {
+    let (mut pipe, _) =
+        get_contents(ClipboardType::Regular, Seat::Unspecified, MimeType::Text).ok()?;
+
+    let mut content = String::new();
+    pipe.read_to_string(&mut content).ok()?;
+
+    Some(content)
+}
+
+/// The message a panic was started with, if it was a string.
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|x| x.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown error".to_owned())
+}
+
+/// Percent-encodes everything but the unreserved characters of RFC 3986.
+pub fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|x| match x {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (x as char).to_string()
+            }
+            x => format!("%{x:02X}"),
+        })
+        .collect()
+}
+
+/// Replaces the predefined entities of XML, for attributes read without a parser.
+pub fn xml_unescape(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Renders the data as a QR code to an SVG in the runtime directory, to be shown as an image.
+pub fn qr_code(data: &str, name: &str) -> Option<PathBuf> {
+    let code = match qrcode::QrCode::new(data.as_bytes()) {
+        Ok(x) => x,
+        Err(error) => {
+            tracing::warn!(%error, "Failed to create QR code");
+            return None;
+        }
+    };
+
+    let svg = code
+        .render::<qrcode::render::svg::Color>()
+        .min_dimensions(320, 320)
+        .quiet_zone(true)
+        .build();
+
+    // The data can be a password, so only the runtime directory is private enough
+    let path =
+        PathBuf::from(std::env::var_os("XDG_RUNTIME_DIR")?).join(format!("jogger-{name}.svg"));
+
+    // Replaced rather than written through, so a planted symlink can't redirect it
+    let _ = std::fs::remove_file(&path);
+    let result = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(&path)
+        .and_then(|mut x| x.write_all(svg.as_bytes()));
+    if let Err(error) = result {
+        tracing::warn!(%error, "Failed to write QR code");
+        return None;
+    }
+
+    Some(path)
+}
+
+/// Sends a desktop notification, for plugins whose events happen while the window is hidden.
+pub fn notify(summary: &str, body: &str, icon: &str) {
+    if let Err(error) = Command::new("notify-send")
+        .args([
+            "--app-name=jogger",
+            &format!("--icon={icon}"),
+            summary,
+            body,
+        ])
+        .status()
+    {
+        tracing::warn!(?error, "Failed to send notification");
+    }
+}
+
+/// What matched the last query of a plugin whose matches only narrow as the query grows,
+/// so a query extending it only needs to search among those.
+#[derive(Debug)]
+pub struct SearchCache<K> {
+    last: Mutex<Option<(String, HashSet<K>)>>,
+}
+
+impl<K> Default for SearchCache<K> {
+    fn default() -> Self {
+        Self {
+            last: Mutex::new(None),
+        }
+    }
+}
+
+impl<K: Eq + Hash> SearchCache<K> {
+    /// The matches of the last query when this one extends it, to be searched instead of everything.
+    pub fn take(&self, query: &str) -> Option<HashSet<K>> {
+        self.last
+            .lock()
+            .take()
+            .filter(|(last, _)| query.starts_with(last.as_str()))
+            .map(|(_, matches)| matches)
+    }
+
+    /// Only to be given every match of the query, not a truncated list.
+    pub fn store(&self, query: &str, matches: HashSet<K>) {
+        *self.last.lock() = Some((query.to_owned(), matches));
+    }
+
+    pub fn clear(&self) {
+        *self.last.lock() = None;
+    }
+}
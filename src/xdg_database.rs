@@ -504,7 +504,7 @@ impl XdgAppDatabase {
             return Some(emulator);
         }
 
-        println!(
+        tracing::warn!(
             "No default terminal emulator could be found, will fallback on the first terminal emulator we find."
         );
 
@@ -519,7 +519,7 @@ impl XdgAppDatabase {
         let exec = app.parse_exec(args, false);
 
         if exec.is_empty() {
-            println!("No program to start the app");
+            tracing::warn!(app = %app.id, "No program to start the app");
             return false;
         }
 
@@ -570,7 +570,7 @@ impl XdgAppDatabase {
         }
 
         if let Err(error) = command.spawn_detached() {
-            println!("Failed to start app {:?} {:?}", command.get_args(), error);
+            tracing::error!(args = ?command.get_args(), ?error, "Failed to start app");
             return false;
         }
 
@@ -584,14 +584,14 @@ impl XdgAppDatabase {
             .find(|x| x.id == action)
             .and_then(|x| x.exec.as_ref())
         else {
-            println!("No action called {action}");
+            tracing::warn!(app = %app.id, action, "No such action");
             return false;
         };
 
         let exec = app.parse_str(exec, args, false);
 
         if exec.is_empty() {
-            println!("No program to start the app");
+            tracing::warn!(app = %app.id, "No program to start the app");
             return false;
         }
 
@@ -624,7 +624,7 @@ impl XdgAppDatabase {
         }
 
         if let Err(error) = command.spawn_detached() {
-            println!("Failed to start app {:?} {:?}", command.get_args(), error);
+            tracing::error!(args = ?command.get_args(), ?error, "Failed to start app");
             return false;
         }
 